@@ -1,4 +1,6 @@
 use clap::Parser;
+use minimal_k_iso_lib::utils::{degree_constrained_graph, erdos_gallai_check};
+use minimal_k_iso_lib::Graph;
 use rand::{rngs::StdRng, Rng, SeedableRng};
 use std::fs::File;
 use std::io::{self, Write};
@@ -70,6 +72,19 @@ struct Args {
     #[arg(long, default_value_t = 1)]
     noise_max: usize,
 
+    /// Comma-separated degree sequence for G, one integer per vertex, used as
+    /// both its out-degree and in-degree (e.g. "3,3,3,3" for a uniform
+    /// 4-vertex pattern). When set, G is built by `degree_constrained_graph`
+    /// instead of `--density-g`/`--multiedge-prob`/`--max-multiedge`, which
+    /// are then ignored for G. Must have exactly `n1` entries and pass
+    /// `erdos_gallai_check`.
+    #[arg(long, value_delimiter = ',')]
+    degree_sequence_g: Option<Vec<usize>>,
+
+    /// Same as `--degree-sequence-g`, for H; must have exactly `n2` entries.
+    #[arg(long, value_delimiter = ',')]
+    degree_sequence_h: Option<Vec<usize>>,
+
     /// Random seed (if omitted, uses entropy)
     #[arg(long)]
     seed: Option<u64>,
@@ -86,17 +101,11 @@ struct Args {
     // RESERVED
 }
 
-/// Generate a random edge count (>=1) possibly becoming a multiedge
-fn random_edge_count<R: Rng>(rng: &mut R, multiedge_prob: f64, max_multiedge: usize) -> usize {
-    if max_multiedge < 2 || rng.gen::<f64>() >= multiedge_prob {
-        1
-    } else {
-        // Uniform between 2..=max_multiedge
-        rng.gen_range(2..=max_multiedge)
-    }
-}
-
-/// Build a random directed multigraph adjacency matrix
+/// Build a random directed multigraph adjacency matrix, delegating the
+/// shared per-pair density/multiplicity draw to `Graph::random` and then
+/// thinning multiedges back down to weight 1 with probability
+/// `1 - multiedge_prob`, so a `max_multiedge` edge only keeps its higher
+/// multiplicity some of the time instead of always.
 fn generate_graph<R: Rng>(
     n: usize,
     density: f64,
@@ -105,14 +114,11 @@ fn generate_graph<R: Rng>(
     // removed allow_self_loops
     rng: &mut R,
 ) -> Vec<Vec<usize>> {
-    let mut adj = vec![vec![0usize; n]; n];
-    for (i, row) in adj.iter_mut().enumerate() {
-        for (j, val) in row.iter_mut().enumerate() {
-            if i == j {
-                continue;
-            }
-            if rng.gen::<f64>() < density {
-                *val = random_edge_count(rng, multiedge_prob, max_multiedge);
+    let mut adj = Graph::random(n, density, max_multiedge, rng.gen()).adj;
+    for row in adj.iter_mut() {
+        for val in row.iter_mut() {
+            if *val > 1 && rng.gen::<f64>() >= multiedge_prob {
+                *val = 1;
             }
         }
     }
@@ -283,6 +289,24 @@ fn main() -> io::Result<()> {
     if args.max_multiedge < 2 && args.multiedge_prob > 0.0 {
         eprintln!("Warning: max_multiedge < 2 makes multiedge_prob ineffective.");
     }
+    for (flag, sequence, n) in [
+        ("--degree-sequence-g", &args.degree_sequence_g, args.n1),
+        ("--degree-sequence-h", &args.degree_sequence_h, args.n2),
+    ] {
+        if let Some(sequence) = sequence {
+            if sequence.len() != n {
+                eprintln!(
+                    "Error: {flag} needs exactly {n} entries, got {}.",
+                    sequence.len()
+                );
+                std::process::exit(1);
+            }
+            if !erdos_gallai_check(sequence, sequence) {
+                eprintln!("Error: {flag}'s sequence is not realizable as a simple directed graph.");
+                std::process::exit(1);
+            }
+        }
+    }
 
     // Initialize RNG
     let mut rng: StdRng = match args.seed {
@@ -294,23 +318,34 @@ fn main() -> io::Result<()> {
         }
     };
 
-    // Generate G
-    let g_adj = generate_graph(
-        args.n1,
-        args.density_g,
-        args.multiedge_prob,
-        args.max_multiedge,
-        &mut rng,
-    );
+    // Generate G, either from a prescribed degree sequence or the usual
+    // density/multiedge random draw.
+    let g_adj = match &args.degree_sequence_g {
+        Some(sequence) => degree_constrained_graph(sequence, sequence, &mut rng)
+            .expect("validated by erdos_gallai_check above")
+            .adj,
+        None => generate_graph(
+            args.n1,
+            args.density_g,
+            args.multiedge_prob,
+            args.max_multiedge,
+            &mut rng,
+        ),
+    };
 
-    // Generate base H
-    let mut h_adj = generate_graph(
-        args.n2,
-        args.density_h,
-        args.multiedge_prob,
-        args.max_multiedge,
-        &mut rng,
-    );
+    // Generate base H, same choice as G.
+    let mut h_adj = match &args.degree_sequence_h {
+        Some(sequence) => degree_constrained_graph(sequence, sequence, &mut rng)
+            .expect("validated by erdos_gallai_check above")
+            .adj,
+        None => generate_graph(
+            args.n2,
+            args.density_h,
+            args.multiedge_prob,
+            args.max_multiedge,
+            &mut rng,
+        ),
+    };
 
     // Choose injective mapping
     let mapping = random_injective_mapping(args.n1, args.n2, &mut rng);
@@ -0,0 +1,583 @@
+//! Library entry point for the exact branch-and-bound search, extracted out
+//! of `exact_solver` and `solver` so it's testable without spawning either
+//! binary. This covers the plain "minimize cost over k mappings" search;
+//! `exact_solver`'s own implementation still exists for its CLI-only
+//! extensions (`--checkpoint`/`--resume`, `--mapping-range`, the
+//! `--max-combinations` guard) that don't fit cleanly behind
+//! [`ProgressSink`]'s three-method interface.
+
+use crate::cost::{
+    calculate_edge_map, calculate_edge_map_with_semantics, marginal_cost, EdgeMapAccumulator,
+    MergeSemantics, Objective, Solution,
+};
+use crate::mapping::{find_all_mappings, find_all_mappings_undirected};
+use crate::utils::BinomialTable;
+use crate::{Graph, Mapping};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::mpsc::Sender;
+use std::time::{Duration, Instant};
+
+/// Why `solve_exact` couldn't produce a solution.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolveError {
+    /// Fewer than `k` distinct mappings exist from `g` into `h` at all.
+    InsufficientMappings { needed: usize, available: usize },
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolveError::InsufficientMappings { needed, available } => write!(
+                f,
+                "need {} distinct mappings from G into H, but only {} exist",
+                needed, available
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SolveError {}
+
+/// How to run `solve_exact`.
+#[derive(Debug, Clone)]
+pub struct ExactOptions {
+    /// Which scalar objective to minimize; see [`Objective`].
+    pub objective: Objective,
+    /// How multiple mappings' demand on the same host edge combines; see
+    /// [`MergeSemantics`].
+    pub merge_semantics: MergeSemantics,
+    /// Treat `g` and `h` as undirected (see `Graph::as_undirected` and
+    /// `mapping::find_all_mappings_undirected`) instead of enumerating every
+    /// directed mapping.
+    pub undirected: bool,
+    /// Stop the search after this long and return the best solution found so
+    /// far instead of running to completion. Unlike `exact_solver`'s own
+    /// `--timeout`, there's no separate flag in the return value saying
+    /// whether the result is proven optimal -- a caller that cares should
+    /// watch for an [`ProgressSink::incumbent`] after the deadline via its own
+    /// timing, the same way it learns of any other incumbent.
+    pub timeout: Option<Duration>,
+}
+
+impl Default for ExactOptions {
+    fn default() -> Self {
+        ExactOptions {
+            objective: Objective::TotalEdges,
+            merge_semantics: MergeSemantics::Shared,
+            undirected: false,
+            timeout: None,
+        }
+    }
+}
+
+/// Progress reporting hook for `solve_exact`'s branch-and-bound search, so a
+/// long-running caller (the TUI, a future batch driver) can show something
+/// other than a frozen terminal while it runs. Every method has a no-op
+/// default, so a caller that only cares about one kind of update doesn't
+/// have to stub out the other two.
+pub trait ProgressSink {
+    /// A short human-readable line describing what the search is doing right
+    /// now (e.g. "enumerating mappings", "running branch-and-bound search").
+    fn status(&mut self, _message: &str) {}
+    /// How many of `total` k-combinations the search has visited so far.
+    fn combinations(&mut self, _done: usize, _total: usize) {}
+    /// A new best-cost solution has been found.
+    fn incumbent(&mut self, _cost: usize) {}
+}
+
+/// A `ProgressSink` that discards every update, for a caller that doesn't
+/// need progress reporting at all (tests, a short-lived one-off call).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoOpProgressSink;
+
+impl ProgressSink for NoOpProgressSink {}
+
+/// An update sent by [`ChannelProgressSink`], mirroring `ProgressSink`'s
+/// three methods as a plain enum so it can cross an `mpsc` channel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProgressUpdate {
+    Status(String),
+    Combinations { done: usize, total: usize },
+    Incumbent(usize),
+}
+
+/// A `ProgressSink` that forwards every update over an `mpsc::Sender`, for a
+/// caller (the TUI) that runs `solve_exact` on a background thread and wants
+/// to render its progress on the main one. A send failing because the
+/// receiver hung up is ignored, the same as dropping any other update would
+/// be once nobody's listening.
+pub struct ChannelProgressSink {
+    tx: Sender<ProgressUpdate>,
+}
+
+impl ChannelProgressSink {
+    pub fn new(tx: Sender<ProgressUpdate>) -> Self {
+        ChannelProgressSink { tx }
+    }
+}
+
+impl ProgressSink for ChannelProgressSink {
+    fn status(&mut self, message: &str) {
+        let _ = self.tx.send(ProgressUpdate::Status(message.to_string()));
+    }
+
+    fn combinations(&mut self, done: usize, total: usize) {
+        let _ = self.tx.send(ProgressUpdate::Combinations { done, total });
+    }
+
+    fn incumbent(&mut self, cost: usize) {
+        let _ = self.tx.send(ProgressUpdate::Incumbent(cost));
+    }
+}
+
+/// Find the minimum-cost set of `k` mappings from `g` into `h` under
+/// `options.objective`, reporting progress to `progress` as it goes.
+///
+/// Returns `Err` only when `h` doesn't even contain `k` distinct mappings of
+/// `g`; otherwise returns the best solution found, which is proven optimal
+/// unless `options.timeout` cut the search off early.
+pub fn solve_exact(
+    g: &Graph,
+    h: &Graph,
+    k: usize,
+    options: &ExactOptions,
+    progress: &mut dyn ProgressSink,
+) -> Result<Solution, SolveError> {
+    progress.status("enumerating mappings from G into H");
+    let all_mappings = if options.undirected {
+        find_all_mappings_undirected(g, h)
+    } else {
+        find_all_mappings(g, h)
+    };
+
+    if all_mappings.len() < k {
+        return Err(SolveError::InsufficientMappings {
+            needed: k,
+            available: all_mappings.len(),
+        });
+    }
+
+    // Exactly one k-combination exists: the whole pool. Nothing to branch
+    // on, so score it directly instead of spinning up the search below.
+    if all_mappings.len() == k {
+        progress.status("exactly k mappings available -- using all of them, no search needed");
+        let edge_map =
+            calculate_edge_map_with_semantics(g, h, &all_mappings, options.merge_semantics);
+        let cost = options.objective.evaluate(&edge_map);
+        progress.incumbent(cost);
+        return Ok(Solution {
+            cost,
+            edge_map,
+            mappings: all_mappings,
+        });
+    }
+
+    progress.status("computing a greedy seed");
+    let seed = greedy_incumbent(
+        &all_mappings,
+        g,
+        h,
+        k,
+        &options.objective,
+        options.merge_semantics,
+    );
+    if let Some(seed) = &seed {
+        progress.incumbent(seed.cost);
+    }
+
+    // Visit cheapest-standalone-cost mappings first: a tight incumbent found
+    // early prunes more of the (still-expensive) remainder. See
+    // `search_combinations`'s doc comment for why this ordering is
+    // load-bearing, not just an optimization.
+    let mut order: Vec<usize> = (0..all_mappings.len()).collect();
+    let standalone_cost: Vec<usize> = all_mappings
+        .iter()
+        .map(|mapping| {
+            options
+                .objective
+                .evaluate(&calculate_edge_map(g, h, std::slice::from_ref(mapping)))
+        })
+        .collect();
+    order.sort_by_key(|&i| standalone_cost[i]);
+    let sorted_mappings: Vec<Mapping> = order.iter().map(|&i| all_mappings[i].clone()).collect();
+    let sorted_costs: Vec<usize> = order.iter().map(|&i| standalone_cost[i]).collect();
+
+    let total_combinations = BinomialTable::new(all_mappings.len()).get(all_mappings.len(), k);
+    progress.status("running branch-and-bound search");
+
+    let deadline = options.timeout.map(|timeout| Instant::now() + timeout);
+
+    let mut accumulator = EdgeMapAccumulator::with_semantics(options.merge_semantics);
+    let mut chosen = Vec::with_capacity(k);
+    let mut best_cost = seed.as_ref().map_or(usize::MAX, |s| s.cost);
+    let mut best_result = seed;
+    let mut nodes_visited = 0usize;
+    let mut stopped = false;
+
+    search_combinations(
+        &sorted_mappings,
+        &sorted_costs,
+        g,
+        h,
+        k,
+        0,
+        &options.objective,
+        &mut accumulator,
+        &mut chosen,
+        &mut best_cost,
+        &mut best_result,
+        &mut nodes_visited,
+        total_combinations,
+        progress,
+        deadline,
+        &mut stopped,
+    );
+
+    progress.combinations(nodes_visited, total_combinations);
+
+    Ok(best_result.expect("a k-combination always exists here: all_mappings.len() > k"))
+}
+
+/// A fast, deterministic greedy construction used only to seed the
+/// branch-and-bound search below with a reasonably tight starting incumbent:
+/// repeatedly commit whichever unused mapping adds the least to the cost
+/// under `objective` given what's already committed (see
+/// `cost::marginal_cost`). Not guaranteed optimal on its own -- the exact
+/// search below still explores every combination the bound can't rule out.
+/// Mirrors `exact_solver`'s own `greedy_incumbent`.
+fn greedy_incumbent(
+    all_mappings: &[Mapping],
+    g: &Graph,
+    h: &Graph,
+    k: usize,
+    objective: &Objective,
+    merge_semantics: MergeSemantics,
+) -> Option<Solution> {
+    let mut committed: HashMap<(usize, usize), usize> = HashMap::new();
+    let mut used = vec![false; all_mappings.len()];
+    let mut chosen = Vec::with_capacity(k);
+
+    for _ in 0..k {
+        let (_, idx, increments) = (0..all_mappings.len())
+            .filter(|&i| !used[i])
+            .map(|i| {
+                let (_, increments) =
+                    marginal_cost(g, h, &committed, &all_mappings[i], merge_semantics);
+                (objective.evaluate(&increments), i, increments)
+            })
+            .min_by_key(|(cost, _, _)| *cost)?;
+
+        for (edge, weight) in increments {
+            *committed.entry(edge).or_insert(0) += weight;
+        }
+        used[idx] = true;
+        chosen.push(all_mappings[idx].clone());
+    }
+
+    Some(Solution {
+        cost: objective.evaluate(&committed),
+        edge_map: committed,
+        mappings: chosen,
+    })
+}
+
+/// Recursively extend `chosen` (indices into `all_mappings`, strictly
+/// increasing) with mappings starting at `next_idx`, pushing each candidate
+/// into `accumulator` before recursing and popping it back out on return.
+/// Once `chosen` reaches length `k`, records the accumulator's cost as a new
+/// best if it beats `best_cost` and reports it via `progress.incumbent`.
+///
+/// `all_mappings` and `costs` must be sorted ascending by `costs[i]`, each
+/// mapping's own standalone cost -- this is what lets the loop below compute
+/// a branch-and-bound lower bound cheaply and `break` out entirely once that
+/// bound can no longer improve on the incumbent, instead of only skipping one
+/// candidate at a time. Mirrors `exact_solver`'s own `search_combinations`,
+/// minus its parallel fan-out and checkpoint/resume bookkeeping: this is a
+/// single sequential walk, which is what keeps it simple enough to be the
+/// thing tests exercise directly.
+///
+/// `deadline`, if set, is checked once per loop iteration; once passed, sets
+/// `*stopped` and unwinds the recursion immediately, leaving `best_result` as
+/// whatever this walk had found so far.
+#[allow(clippy::too_many_arguments)]
+fn search_combinations(
+    all_mappings: &[Mapping],
+    costs: &[usize],
+    g: &Graph,
+    h: &Graph,
+    k: usize,
+    next_idx: usize,
+    objective: &Objective,
+    accumulator: &mut EdgeMapAccumulator,
+    chosen: &mut Vec<usize>,
+    best_cost: &mut usize,
+    best_result: &mut Option<Solution>,
+    nodes_visited: &mut usize,
+    total_combinations: usize,
+    progress: &mut dyn ProgressSink,
+    deadline: Option<Instant>,
+    stopped: &mut bool,
+) {
+    if chosen.len() == k {
+        let total_cost = accumulator.evaluate(objective);
+        if total_cost >= *best_cost {
+            return;
+        }
+
+        let mappings: Vec<Mapping> = chosen.iter().map(|&i| all_mappings[i].clone()).collect();
+        *best_cost = total_cost;
+        *best_result = Some(Solution {
+            cost: total_cost,
+            edge_map: accumulator.edge_map(),
+            mappings,
+        });
+        progress.incumbent(total_cost);
+        return;
+    }
+
+    // Leave enough room in `all_mappings` after `idx` for the remaining slots.
+    let remaining = k - chosen.len();
+    let last_idx = all_mappings.len() - remaining;
+
+    for idx in next_idx..=last_idx {
+        if *stopped {
+            return;
+        }
+        if let Some(deadline) = deadline {
+            if Instant::now() >= deadline {
+                *stopped = true;
+                return;
+            }
+        }
+
+        // Lower bound on any solution completed from here: the accumulated
+        // cost so far never falls as more mappings are merged in, and at
+        // least one more mapping must still be added, whose own standalone
+        // cost is at least `costs[idx]` (the cheapest remaining candidate,
+        // since `all_mappings` is sorted ascending).
+        let bound = accumulator.evaluate(objective).max(costs[idx]);
+        if bound >= *best_cost {
+            // Every later idx has an equal or higher standalone cost, so the
+            // bound only gets worse from here.
+            break;
+        }
+
+        *nodes_visited += 1;
+        if nodes_visited.is_multiple_of(10_000) {
+            progress.combinations(*nodes_visited, total_combinations);
+        }
+
+        accumulator.add_mapping(g, h, &all_mappings[idx]);
+        chosen.push(idx);
+
+        search_combinations(
+            all_mappings,
+            costs,
+            g,
+            h,
+            k,
+            idx + 1,
+            objective,
+            accumulator,
+            chosen,
+            best_cost,
+            best_result,
+            nodes_visited,
+            total_combinations,
+            progress,
+            deadline,
+            stopped,
+        );
+
+        chosen.pop();
+        accumulator.remove_mapping(g, h, &all_mappings[idx]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use itertools::Itertools;
+
+    /// Independent ground truth for `solve_exact`: scores every
+    /// k-combination of `all_mappings` by brute force and returns the
+    /// minimum. Only usable on instances small enough that `C(n, k)` is
+    /// cheap, which is the point -- it's the thing the branch-and-bound
+    /// search exists to avoid doing in general.
+    fn brute_force_min_cost(
+        all_mappings: &[Mapping],
+        g: &Graph,
+        h: &Graph,
+        k: usize,
+        objective: &Objective,
+    ) -> usize {
+        (0..all_mappings.len())
+            .combinations(k)
+            .map(|indices| {
+                let chosen: Vec<&Mapping> = indices.iter().map(|&i| &all_mappings[i]).collect();
+                objective.evaluate(&calculate_edge_map(g, h, &chosen))
+            })
+            .min()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_solve_exact_matches_brute_force_on_small_instances() {
+        let instances = [
+            (
+                Graph::from_adjacency_matrix(vec![vec![0, 1], vec![0, 0]]),
+                Graph::from_adjacency_matrix(vec![vec![0; 4]; 4]),
+                2,
+            ),
+            (
+                Graph::from_adjacency_matrix(vec![vec![0, 1, 0], vec![0, 0, 1], vec![1, 0, 0]]),
+                Graph::from_adjacency_matrix(vec![vec![0; 5]; 5]),
+                3,
+            ),
+            (
+                Graph::from_adjacency_matrix(vec![vec![0, 2], vec![0, 0]]),
+                Graph::from_adjacency_matrix(vec![vec![0, 1], vec![0, 0]]),
+                2,
+            ),
+        ];
+
+        for (g, h, k) in &instances {
+            let all_mappings = find_all_mappings(g, h);
+            assert!(all_mappings.len() >= *k);
+
+            let expected = brute_force_min_cost(&all_mappings, g, h, *k, &Objective::TotalEdges);
+            let solution = solve_exact(g, h, *k, &ExactOptions::default(), &mut NoOpProgressSink)
+                .expect("a k-combination always exists here");
+            assert_eq!(solution.cost, expected);
+            assert_eq!(solution.mappings.len(), *k);
+        }
+    }
+
+    #[test]
+    fn test_solve_exact_reports_not_enough_mappings() {
+        let g = Graph::from_adjacency_matrix(vec![vec![0, 1, 0], vec![0, 0, 1], vec![1, 0, 0]]);
+        let h = Graph::from_adjacency_matrix(vec![vec![0; 5]; 5]);
+        let available = find_all_mappings(&g, &h).len();
+
+        let err = solve_exact(
+            &g,
+            &h,
+            available + 1,
+            &ExactOptions::default(),
+            &mut NoOpProgressSink,
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            err,
+            SolveError::InsufficientMappings {
+                needed: available + 1,
+                available,
+            }
+        );
+    }
+
+    #[test]
+    fn test_solve_exact_reports_incumbents_via_progress_sink() {
+        #[derive(Default)]
+        struct RecordingSink {
+            incumbents: Vec<usize>,
+        }
+        impl ProgressSink for RecordingSink {
+            fn incumbent(&mut self, cost: usize) {
+                self.incumbents.push(cost);
+            }
+        }
+
+        let g = Graph::from_adjacency_matrix(vec![vec![0, 1, 0], vec![0, 0, 1], vec![1, 0, 0]]);
+        let h = Graph::from_adjacency_matrix(vec![vec![0; 6]; 6]);
+        let k = 3;
+
+        let mut sink = RecordingSink::default();
+        let solution = solve_exact(&g, &h, k, &ExactOptions::default(), &mut sink)
+            .expect("a k-combination always exists here");
+
+        assert!(!sink.incumbents.is_empty());
+        assert_eq!(*sink.incumbents.last().unwrap(), solution.cost);
+        // Every incumbent update is a strict improvement on the last.
+        for pair in sink.incumbents.windows(2) {
+            assert!(pair[1] < pair[0]);
+        }
+    }
+
+    #[test]
+    fn test_channel_progress_sink_forwards_incumbent_updates() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let mut sink = ChannelProgressSink::new(tx);
+
+        let g = Graph::from_adjacency_matrix(vec![vec![0, 1, 0], vec![0, 0, 1], vec![1, 0, 0]]);
+        let h = Graph::from_adjacency_matrix(vec![vec![0; 6]; 6]);
+        let solution = solve_exact(&g, &h, 3, &ExactOptions::default(), &mut sink)
+            .expect("a k-combination always exists here");
+
+        let updates: Vec<ProgressUpdate> = rx.try_iter().collect();
+        let last_incumbent = updates
+            .iter()
+            .filter_map(|update| match update {
+                ProgressUpdate::Incumbent(cost) => Some(*cost),
+                _ => None,
+            })
+            .next_back()
+            .expect("at least one incumbent update should have been sent");
+        assert_eq!(last_incumbent, solution.cost);
+    }
+}
+
+#[cfg(test)]
+mod merge_semantics_tests {
+    use super::*;
+
+    /// `Shared` charges the max demand a host edge sees across mappings, so
+    /// it rewards two mappings piling onto the same edge; `Dedicated` charges
+    /// the sum, so it's indifferent to overlap and just wants the two
+    /// cheapest standalone mappings. On this instance those preferences
+    /// settle on genuinely different pairs, not just different costs for the
+    /// same pair.
+    #[test]
+    fn test_shared_and_dedicated_semantics_choose_different_mapping_sets() {
+        let g = Graph::from_adjacency_matrix(vec![vec![0, 3, 0], vec![0, 0, 2], vec![0, 0, 0]]);
+        let h = Graph::from_adjacency_matrix(vec![
+            vec![0, 2, 0, 1, 0],
+            vec![1, 0, 1, 0, 0],
+            vec![0, 0, 0, 0, 0],
+            vec![0, 0, 1, 0, 2],
+            vec![0, 0, 0, 0, 0],
+        ]);
+
+        let shared = solve_exact(
+            &g,
+            &h,
+            2,
+            &ExactOptions {
+                merge_semantics: MergeSemantics::Shared,
+                ..Default::default()
+            },
+            &mut NoOpProgressSink,
+        )
+        .expect("a 2-combination always exists here");
+        let dedicated = solve_exact(
+            &g,
+            &h,
+            2,
+            &ExactOptions {
+                merge_semantics: MergeSemantics::Dedicated,
+                ..Default::default()
+            },
+            &mut NoOpProgressSink,
+        )
+        .expect("a 2-combination always exists here");
+
+        assert_eq!(shared.cost, 3);
+        assert_eq!(dedicated.cost, 4);
+
+        let shared_set: std::collections::HashSet<_> = shared.mappings.iter().collect();
+        let dedicated_set: std::collections::HashSet<_> = dedicated.mappings.iter().collect();
+        assert_ne!(shared_set, dedicated_set);
+    }
+}
@@ -0,0 +1,5 @@
+//! Library-level algorithm implementations that are usable directly from
+//! tests (and any other crate) without spawning a binary, unlike
+//! `src/bin`'s CLI-specific wrappers.
+
+pub mod exact;
@@ -0,0 +1,1281 @@
+use crate::cost::{
+    calculate_edge_map, mapping_cost_matrix, marginal_cost, MergeSemantics, Objective,
+};
+use crate::mapping::MappingSet;
+use crate::{Graph, Mapping};
+use ndarray::Array2;
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::collections::{HashMap, HashSet};
+use std::time::Instant;
+
+/// Iteration budget and cooling schedule for [`refine_mapping_sa`]. The
+/// temperature decreases linearly from `initial_temperature` at iteration 0
+/// to 0 at the final iteration, so the search starts willing to accept
+/// worsening moves (to escape the greedy construction's local optimum) and
+/// ends as pure hill-climbing.
+#[derive(Clone, Copy, Debug)]
+pub struct SaSchedule {
+    pub iterations: usize,
+    pub initial_temperature: f64,
+}
+
+impl Default for SaSchedule {
+    /// 200 iterations, enough for a handful of reassignments and swaps on a
+    /// single mapping without dominating a `k`-mapping construction's
+    /// runtime; a starting temperature of 2.0 makes a cost increase of a
+    /// couple of edges plausible to accept early on, cooling to 0 tolerance
+    /// by the last iteration.
+    fn default() -> Self {
+        SaSchedule {
+            iterations: 200,
+            initial_temperature: 2.0,
+        }
+    }
+}
+
+impl SaSchedule {
+    /// The Metropolis temperature at `iteration`, linearly interpolated from
+    /// `initial_temperature` down to 0 over `[0, iterations)`. Returns 0 for
+    /// `iterations <= 1`, where there's no span to interpolate over.
+    fn temperature_at(&self, iteration: usize) -> f64 {
+        if self.iterations <= 1 {
+            return 0.0;
+        }
+        self.initial_temperature * (1.0 - iteration as f64 / (self.iterations - 1) as f64)
+    }
+}
+
+/// A single perturbation of a mapping's images, proposed by
+/// [`refine_mapping_sa`] and scored against the mapping it would replace.
+enum Move {
+    /// Reassign `g_vertex`'s image to `new_h_vertex`.
+    Reassign { g_vertex: usize, new_h_vertex: usize },
+    /// Swap the images of two G vertices.
+    Swap { g_vertex_a: usize, g_vertex_b: usize },
+}
+
+/// Refine a single already-complete `mapping` by simulated annealing:
+/// repeatedly proposes either reassigning one G vertex's image or swapping
+/// two G vertices' images, and accepts the proposal by the Metropolis
+/// criterion on the marginal cost (`cost::marginal_cost` against `current`
+/// and `merge_semantics` -- the same scoring [`approximate_best_mapping`]
+/// uses for its own candidates, so a refined mapping slots into the same
+/// `minimal_extension` bookkeeping without any special-casing).
+///
+/// Meant to run after [`approximate_best_mapping`] has already produced a
+/// complete mapping: unlike that function's construction-from-scratch
+/// search, this one only ever holds a single complete candidate mapping at a
+/// time, so escaping the greedy construction's local optimum costs one
+/// `marginal_cost` evaluation per proposal instead of a fresh trial.
+///
+/// `forbidden_h_vertices` excludes H vertices this mapping must not use --
+/// typically `globally_used_h_vertices` under `--disjoint`, so a swap or
+/// reassignment can't steal a vertex another mapping already committed to.
+/// `used_mappings` excludes proposals that collapse onto a mapping already
+/// committed earlier in the same search, the same rule
+/// [`approximate_best_mapping`] applies while constructing candidates, so
+/// refinement can't silently turn two of the required `k` distinct mappings
+/// into duplicates of each other.
+///
+/// Returns the best mapping found (possibly unchanged from the input, if no
+/// proposal ever beat it) and the marginal edge map it implies, exactly as
+/// [`approximate_best_mapping`] would have returned for that mapping.
+#[allow(clippy::too_many_arguments)]
+pub fn refine_mapping_sa(
+    g: &Graph,
+    h: &Graph,
+    current: &EdgeMap,
+    mapping: &Mapping,
+    merge_semantics: MergeSemantics,
+    objective: &Objective,
+    forbidden_h_vertices: &HashSet<usize>,
+    used_mappings: &MappingSet,
+    schedule: &SaSchedule,
+    rng: &mut impl Rng,
+) -> (Mapping, EdgeMap) {
+    let n_g = g.num_vertices();
+    let n_h = h.num_vertices();
+
+    let mut state = mapping.clone();
+    let (mut state_cost, mut state_edges) = {
+        let (_, increments) = marginal_cost(g, h, current, &state, merge_semantics);
+        (objective.evaluate(&increments), increments)
+    };
+
+    let mut best = state.clone();
+    let mut best_cost = state_cost;
+    let mut best_edges = state_edges.clone();
+
+    for iteration in 0..schedule.iterations {
+        let used: HashSet<usize> = state.iter().copied().collect();
+        let move_choice = if n_g >= 2 && rng.gen_bool(0.5) {
+            let mut vertices = (0..n_g).collect::<Vec<_>>();
+            vertices.shuffle(rng);
+            Some(Move::Swap {
+                g_vertex_a: vertices[0],
+                g_vertex_b: vertices[1],
+            })
+        } else {
+            let g_vertex = rng.gen_range(0..n_g);
+            let available: Vec<usize> = (0..n_h)
+                .filter(|v| !used.contains(v) && !forbidden_h_vertices.contains(v))
+                .collect();
+            available
+                .choose(rng)
+                .map(|&new_h_vertex| Move::Reassign {
+                    g_vertex,
+                    new_h_vertex,
+                })
+        };
+
+        let Some(move_choice) = move_choice else {
+            continue;
+        };
+
+        let mut candidate = state.clone();
+        match move_choice {
+            Move::Reassign {
+                g_vertex,
+                new_h_vertex,
+            } => candidate[g_vertex] = new_h_vertex,
+            Move::Swap {
+                g_vertex_a,
+                g_vertex_b,
+            } => candidate.swap(g_vertex_a, g_vertex_b),
+        }
+
+        if used_mappings.contains(&candidate) {
+            continue;
+        }
+
+        let (_, candidate_edges) = marginal_cost(g, h, current, &candidate, merge_semantics);
+        let candidate_cost = objective.evaluate(&candidate_edges);
+
+        let accept = if candidate_cost <= state_cost {
+            true
+        } else {
+            let temperature = schedule.temperature_at(iteration);
+            temperature > 0.0
+                && rng.gen::<f64>()
+                    < (-((candidate_cost - state_cost) as f64) / temperature).exp()
+        };
+
+        if accept {
+            state = candidate;
+            state_cost = candidate_cost;
+            state_edges = candidate_edges;
+
+            if state_cost < best_cost {
+                best = state.clone();
+                best_cost = state_cost;
+                best_edges = state_edges.clone();
+            }
+        }
+    }
+
+    (best, best_edges)
+}
+
+/// Iteration budget and tabu tenure for [`refine_mapping_tabu`]. Unlike
+/// [`SaSchedule`]'s cooling temperature, tenure doesn't change over the
+/// search: a move stays forbidden for exactly `tenure` iterations after it's
+/// made, regardless of how far into the run that is.
+#[derive(Clone, Copy, Debug)]
+pub struct TabuConfig {
+    pub iterations: usize,
+    pub tenure: usize,
+}
+
+impl Default for TabuConfig {
+    /// 5000 iterations is enough for a full best-of-neighborhood search to
+    /// churn through several tenure cycles on a single mapping; a tenure of
+    /// 15 keeps a just-abandoned assignment off the table for a while
+    /// without permanently excluding it, should every other move get worse.
+    fn default() -> Self {
+        TabuConfig {
+            iterations: 5000,
+            tenure: 15,
+        }
+    }
+}
+
+/// Moves made and aspiration-criterion overrides taken by
+/// [`refine_mapping_tabu`], reported back to the caller alongside the
+/// refined mapping for `--refine tabu`'s progress output.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TabuStats {
+    pub moves: usize,
+    pub aspirations: usize,
+}
+
+/// Refine a single already-complete `mapping` by tabu search: each iteration
+/// scans the full reassignment-and-swap neighborhood (the same `Move` shapes
+/// [`refine_mapping_sa`] proposes at random) and commits whichever
+/// non-tabu move yields the lowest marginal cost, breaking ties uniformly at
+/// random via `rng` (the only place randomness enters this otherwise
+/// deterministic scan). A move that reassigns or swaps away from
+/// `(g_vertex, h_vertex)` marks that pairing tabu for the next
+/// `config.tenure` iterations, so the search can't immediately undo it and
+/// cycle between the same two states; the aspiration criterion lifts that
+/// ban whenever the move would beat `best_cost` anyway, since a move that
+/// sets a new global best can't be the start of a cycle back to something
+/// worse.
+///
+/// Unlike simulated annealing's single random proposal per iteration, a full
+/// neighborhood scan costs `O(n_g * n_h)` `marginal_cost` evaluations per
+/// iteration, so `config.iterations` should be tuned down from
+/// [`SaSchedule`]'s defaults on larger instances.
+///
+/// Returns the best mapping found (possibly unchanged from the input), its
+/// marginal edge map exactly as [`approximate_best_mapping`] would have
+/// returned for that mapping, and [`TabuStats`] covering the whole run.
+#[allow(clippy::too_many_arguments)]
+pub fn refine_mapping_tabu<R: Rng + ?Sized>(
+    g: &Graph,
+    h: &Graph,
+    current: &EdgeMap,
+    mapping: &Mapping,
+    merge_semantics: MergeSemantics,
+    objective: &Objective,
+    forbidden_h_vertices: &HashSet<usize>,
+    used_mappings: &MappingSet,
+    config: &TabuConfig,
+    rng: &mut R,
+) -> (Mapping, EdgeMap, TabuStats) {
+    let n_g = g.num_vertices();
+    let n_h = h.num_vertices();
+
+    let mut state = mapping.clone();
+    let (mut state_cost, mut state_edges) = {
+        let (_, increments) = marginal_cost(g, h, current, &state, merge_semantics);
+        (objective.evaluate(&increments), increments)
+    };
+
+    let mut best = state.clone();
+    let mut best_cost = state_cost;
+    let mut best_edges = state_edges.clone();
+
+    // (g_vertex, h_vertex) -> the iteration up to which re-adopting that
+    // pairing is tabu. Entries are never removed, only superseded, since a
+    // stale entry earlier than the current iteration is simply ignored.
+    let mut tabu_until: HashMap<(usize, usize), usize> = HashMap::new();
+    let mut stats = TabuStats::default();
+
+    for iteration in 0..config.iterations {
+        let used: HashSet<usize> = state.iter().copied().collect();
+
+        // (candidate, edges, cost, move, overrode tabu via aspiration)
+        let mut best_move: Option<(Mapping, EdgeMap, usize, Move, bool)> = None;
+        let mut tied_candidates = 0usize;
+        let consider = |candidate: Mapping,
+                             move_choice: Move,
+                             tabu_keys: &[(usize, usize)],
+                             best_move: &mut Option<(Mapping, EdgeMap, usize, Move, bool)>,
+                             tied_candidates: &mut usize,
+                             rng: &mut R| {
+            if used_mappings.contains(&candidate) {
+                return;
+            }
+            let (_, candidate_edges) = marginal_cost(g, h, current, &candidate, merge_semantics);
+            let candidate_cost = objective.evaluate(&candidate_edges);
+
+            let is_tabu = tabu_keys
+                .iter()
+                .any(|key| tabu_until.get(key).is_some_and(|&until| until > iteration));
+            let aspires = candidate_cost < best_cost;
+            if is_tabu && !aspires {
+                return;
+            }
+
+            // Ties are broken uniformly at random (via reservoir sampling,
+            // so every tied move seen so far has equal weight) rather than
+            // always keeping whichever was found first -- the only place
+            // `rng` enters an otherwise deterministic best-of-neighborhood
+            // scan, which is what makes `--refine tabu --seed` reproducible
+            // without also being order-biased.
+            let is_better = match best_move.as_ref() {
+                None => true,
+                Some((_, _, cost, _, _)) if candidate_cost < *cost => true,
+                Some((_, _, cost, _, _)) if candidate_cost == *cost => {
+                    *tied_candidates += 1;
+                    rng.gen_range(0..*tied_candidates) == 0
+                }
+                Some(_) => false,
+            };
+            if is_better {
+                *best_move = Some((
+                    candidate,
+                    candidate_edges,
+                    candidate_cost,
+                    move_choice,
+                    is_tabu && aspires,
+                ));
+            }
+        };
+
+        for g_vertex in 0..n_g {
+            let from_h_vertex = state[g_vertex];
+            for new_h_vertex in 0..n_h {
+                if new_h_vertex == from_h_vertex
+                    || used.contains(&new_h_vertex)
+                    || forbidden_h_vertices.contains(&new_h_vertex)
+                {
+                    continue;
+                }
+                let mut candidate = state.clone();
+                candidate[g_vertex] = new_h_vertex;
+                consider(
+                    candidate,
+                    Move::Reassign {
+                        g_vertex,
+                        new_h_vertex,
+                    },
+                    &[(g_vertex, from_h_vertex)],
+                    &mut best_move,
+                    &mut tied_candidates,
+                    rng,
+                );
+            }
+        }
+
+        if n_g >= 2 {
+            for g_vertex_a in 0..n_g {
+                for g_vertex_b in (g_vertex_a + 1)..n_g {
+                    let mut candidate = state.clone();
+                    candidate.swap(g_vertex_a, g_vertex_b);
+                    consider(
+                        candidate,
+                        Move::Swap {
+                            g_vertex_a,
+                            g_vertex_b,
+                        },
+                        &[
+                            (g_vertex_a, state[g_vertex_a]),
+                            (g_vertex_b, state[g_vertex_b]),
+                        ],
+                        &mut best_move,
+                        &mut tied_candidates,
+                        rng,
+                    );
+                }
+            }
+        }
+
+        let Some((candidate, candidate_edges, candidate_cost, move_choice, via_aspiration)) =
+            best_move
+        else {
+            continue;
+        };
+
+        match move_choice {
+            Move::Reassign { g_vertex, .. } => {
+                tabu_until.insert((g_vertex, state[g_vertex]), iteration + config.tenure);
+            }
+            Move::Swap {
+                g_vertex_a,
+                g_vertex_b,
+            } => {
+                tabu_until.insert((g_vertex_a, state[g_vertex_a]), iteration + config.tenure);
+                tabu_until.insert((g_vertex_b, state[g_vertex_b]), iteration + config.tenure);
+            }
+        }
+
+        state = candidate;
+        state_cost = candidate_cost;
+        state_edges = candidate_edges;
+        stats.moves += 1;
+        if via_aspiration {
+            stats.aspirations += 1;
+        }
+
+        if state_cost < best_cost {
+            best = state.clone();
+            best_cost = state_cost;
+            best_edges = state_edges.clone();
+        }
+    }
+
+    (best, best_edges, stats)
+}
+
+/// Total cost, the edge map that achieves it, and the mappings chosen to
+/// produce it.
+pub type GreedyResult = (usize, HashMap<(usize, usize), usize>, Vec<Mapping>);
+
+/// (source, target) -> edge count, the shape every greedy search here and in
+/// `approx_solver`/`solver` passes around.
+type EdgeMap = HashMap<(usize, usize), usize>;
+
+/// Builds a k-mapping set by always committing whichever remaining candidate
+/// minimizes the cost of the *joint* edge map over the already-chosen set
+/// plus itself, recomputing that joint edge map from scratch at every step
+/// (unlike `cost::marginal_cost`'s incremental shortfall tracking, or
+/// `EdgeMapAccumulator`'s incremental max-tracking).
+///
+/// This is a different tradeoff from `approx_solver`'s
+/// `sequential_greedy_extension`, which searches for each mapping via a
+/// randomized vertex-by-vertex local construction against a running H′: here
+/// the candidate pool is fixed up front and every candidate is scored
+/// exactly against the real chosen-so-far set, so it can't be misled by H′
+/// accepting a vertex assignment that a later mapping can't actually match.
+/// The price is complexity: each of the `k` picks re-evaluates every
+/// remaining candidate by rebuilding an O(n²) edge map, for an overall
+/// O(k · |all_mappings| · n²) — only feasible when `all_mappings` is
+/// moderate in size.
+///
+/// Returns `None` if fewer than `k` mappings are available.
+pub fn marginal_cost_greedy(
+    all_mappings: &[Mapping],
+    g: &Graph,
+    h: &Graph,
+    k: usize,
+    objective: &Objective,
+) -> Option<GreedyResult> {
+    if all_mappings.len() < k {
+        return None;
+    }
+
+    let mut chosen: Vec<usize> = Vec::with_capacity(k);
+
+    // First choice: the single mapping with the lowest standalone cost.
+    let first = (0..all_mappings.len()).min_by_key(|&i| {
+        objective.evaluate(&calculate_edge_map(
+            g,
+            h,
+            std::slice::from_ref(&all_mappings[i]),
+        ))
+    })?;
+    chosen.push(first);
+
+    while chosen.len() < k {
+        let next = (0..all_mappings.len())
+            .filter(|i| !chosen.contains(i))
+            .min_by_key(|&i| {
+                let candidate_set: Vec<&Mapping> = chosen
+                    .iter()
+                    .map(|&j| &all_mappings[j])
+                    .chain(std::iter::once(&all_mappings[i]))
+                    .collect();
+                objective.evaluate(&calculate_edge_map(g, h, &candidate_set))
+            })?;
+        chosen.push(next);
+    }
+
+    let mappings: Vec<Mapping> = chosen
+        .into_iter()
+        .map(|i| all_mappings[i].clone())
+        .collect();
+    let edge_map = calculate_edge_map(g, h, &mappings);
+    let total_cost = objective.evaluate(&edge_map);
+
+    Some((total_cost, edge_map, mappings))
+}
+
+/// Builds a k-mapping set by repeatedly solving a minimum-cost bipartite
+/// matching -- the Hungarian algorithm, via the `lapjv` crate -- over
+/// `cost::mapping_cost_matrix`, instead of `marginal_cost_greedy`'s exact
+/// per-candidate scoring over a pre-enumerated pool. A single LAPJV solve is
+/// polynomial in `h.num_vertices()` regardless of how many valid mappings G
+/// to H actually has, so this stays cheap on host graphs too large to
+/// enumerate with `mapping::find_all_mappings`.
+///
+/// The tradeoff is the one `mapping_cost_matrix`'s doc comment describes:
+/// its unary per-vertex-pair costs approximate, rather than compute, how
+/// good a match is. After each of the `k` rounds, every H vertex used so far
+/// has its column in the cost matrix penalized by enough to dominate any
+/// degree-mismatch cost, so the next round's minimum-cost matching prefers
+/// unused H vertices over recomputing a byte-identical result -- a cheap
+/// stand-in for a real k-best assignment search, not a guarantee that the k
+/// mappings found are disjoint or even distinct once `penalty` stops being
+/// enough to steer the matching away from a vertex worth reusing.
+///
+/// Returns `None` if `g.num_vertices() > h.num_vertices()`, since no
+/// injective mapping can exist at all in that case.
+pub fn hungarian_matching_greedy(
+    g: &Graph,
+    h: &Graph,
+    k: usize,
+    objective: &Objective,
+) -> Option<GreedyResult> {
+    let n_g = g.num_vertices();
+    let n_h = h.num_vertices();
+    if n_g > n_h {
+        return None;
+    }
+
+    let base_cost = mapping_cost_matrix(g, h);
+
+    // LAPJV requires a square matrix; H has at least as many vertices as G,
+    // so rows n_g..n_h are padding with no cost of their own, free to absorb
+    // whichever H vertices the real rows don't claim.
+    let dim = n_h;
+    let mut cost = vec![vec![0.0f64; dim]; dim];
+    for (i, row) in base_cost.iter().enumerate() {
+        for (j, &c) in row.iter().enumerate() {
+            cost[i][j] = c as f64;
+        }
+    }
+
+    // Large enough to dominate any degree-mismatch cost above (each bounded
+    // by a vertex's own degree), so "prefer a fresh H vertex" always wins
+    // over "prefer a slightly better degree match" once a column is
+    // penalized.
+    let penalty = (n_g * n_h * 2 + 1) as f64;
+
+    let mut mappings = Vec::with_capacity(k);
+    for _ in 0..k {
+        let flat: Vec<f64> = cost.iter().flatten().copied().collect();
+        let matrix = Array2::from_shape_vec((dim, dim), flat)
+            .expect("cost is a dim x dim matrix by construction");
+        let (row_to_col, _) = lapjv::lapjv(&matrix).ok()?;
+
+        let mapping: Mapping = row_to_col[..n_g].to_vec();
+        // Only the real rows' entries are penalized, not the padding rows':
+        // since padding rows stay cheap at every column, the matching's
+        // incentive to move a real row off a used column isn't cancelled
+        // out by an equally-cheap padding row that could "soak up" that
+        // column's high cost for free either way.
+        for &j in &mapping {
+            for row in cost[..n_g].iter_mut() {
+                row[j] += penalty;
+            }
+        }
+        mappings.push(mapping);
+    }
+
+    let edge_map = calculate_edge_map(g, h, &mappings);
+    let total_cost = objective.evaluate(&edge_map);
+
+    Some((total_cost, edge_map, mappings))
+}
+
+/// Controls how eagerly [`approximate_best_mapping`] can stop sampling
+/// trials once its best mapping so far is already as good as it can
+/// possibly get, instead of always exhausting the full
+/// `n_g * n_h * trials_multiplier` budget.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EarlyStop {
+    /// Run every trial regardless of cost. The original behavior.
+    #[default]
+    Never,
+    /// Stop as soon as a trial lands a zero-cost mapping; nothing can beat
+    /// zero.
+    OnZeroCost,
+    /// Stop as soon as the best cost found is at or below `bound`. Phrased
+    /// generically so a caller holding a tighter theoretical minimum than 0
+    /// can supply it.
+    OnLowerBound(usize),
+}
+
+impl EarlyStop {
+    /// Whether `cost`, the best cost found so far, is already provably
+    /// optimal under this policy.
+    pub fn is_satisfied_by(&self, cost: usize) -> bool {
+        match self {
+            EarlyStop::Never => false,
+            EarlyStop::OnZeroCost => cost == 0,
+            EarlyStop::OnLowerBound(bound) => cost <= *bound,
+        }
+    }
+}
+
+/// Controls how each trial of [`approximate_best_mapping`] picks the
+/// `(u_start, v_start)` pair its vertex-by-vertex greedy construction begins
+/// from.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SeedStrategy {
+    /// Seed `u_start` from among G's highest-total-degree vertices (ties
+    /// broken uniformly at random) and `v_start` from whichever unclaimed H
+    /// vertex best covers that vertex's in/out degree requirements (again
+    /// tied at random) -- unless `center_vertex` or `pagerank_weights`
+    /// override `v_start`'s choice, same as [`SeedStrategy::Random`]. The
+    /// default: a trial starting from a degree-0 G vertex constrains nothing
+    /// about the rest of the construction, wasting a share of the trial
+    /// budget that scales with how many low-degree vertices G has.
+    #[default]
+    HighestDegree,
+    /// Sample `u_start` uniformly from G and (absent a `center_vertex` or
+    /// `pagerank_weights` override) `v_start` uniformly from H, the original
+    /// behavior, kept for comparison via `--seed-strategy random`.
+    Random,
+}
+
+/// G's vertices with the highest total degree (in-degree plus out-degree),
+/// for [`SeedStrategy::HighestDegree`] to choose `u_start` from -- more than
+/// one if several vertices tie for the maximum.
+fn highest_degree_vertices(g: &Graph) -> Vec<usize> {
+    let n = g.num_vertices();
+    let degrees: Vec<usize> = (0..n)
+        .map(|v| (0..n).map(|w| g.get_edge(v, w) + g.get_edge(w, v)).sum())
+        .collect();
+    let max_degree = degrees.iter().copied().max().unwrap_or(0);
+    (0..n).filter(|&v| degrees[v] == max_degree).collect()
+}
+
+/// The unclaimed H vertex whose in/out degree best covers `u_start`'s own
+/// in/out degree in `g` -- the one minimizing how many of `u_start`'s edges
+/// `h_prime` couldn't yet host if `u_start` were mapped there -- with ties
+/// broken uniformly at random via `rng` (shuffle before the stable
+/// `min_by_key`, the same idiom [`beam_search_construct`]'s tie-breaking
+/// could use). Returns `None` if every H vertex is already claimed.
+fn best_covering_h_vertex(
+    g: &Graph,
+    h_prime: &Graph,
+    u_start: usize,
+    globally_used_h_vertices: &HashSet<usize>,
+    rng: &mut impl Rng,
+) -> Option<usize> {
+    let n_g = g.num_vertices();
+    let n_h = h_prime.num_vertices();
+    let u_out: usize = (0..n_g).map(|w| g.get_edge(u_start, w)).sum();
+    let u_in: usize = (0..n_g).map(|w| g.get_edge(w, u_start)).sum();
+
+    let mut candidates: Vec<usize> = (0..n_h)
+        .filter(|v| !globally_used_h_vertices.contains(v))
+        .collect();
+    candidates.shuffle(rng);
+
+    candidates.into_iter().min_by_key(|&v| {
+        let v_out: usize = (0..n_h).map(|w| h_prime.get_edge(v, w)).sum();
+        let v_in: usize = (0..n_h).map(|w| h_prime.get_edge(w, v)).sum();
+        u_out.saturating_sub(v_out) + u_in.saturating_sub(v_in)
+    })
+}
+
+/// The cost of assigning G vertex `u_i` to H vertex `v_j`, given the
+/// vertices already committed in `mapping`: for every G edge between `u_i`
+/// and an already-mapped vertex, how many copies of the corresponding H′
+/// edge are still missing. Used by [`approximate_best_mapping`] to pick each
+/// next vertex in its greedy construction without having to build the whole
+/// candidate mapping first.
+///
+/// Deliberately local (only edges touching `u_i`), not the full cost of the
+/// mapping built so far: `approximate_best_mapping` only ever needs a
+/// relative ordering over this step's candidates, and summing the same
+/// already-committed cost into every candidate wouldn't change that
+/// ordering.
+fn calculate_local_cost(
+    u_i: usize,
+    v_j: usize,
+    g: &Graph,
+    h_prime: &Graph,
+    mapping: &HashMap<usize, usize>,
+    objective: &Objective,
+) -> usize {
+    let mut local_edges = EdgeMap::new();
+
+    for (&u_mapped, &v_mapped) in mapping.iter() {
+        let g_edge = g.get_edge(u_i, u_mapped);
+        if g_edge > 0 {
+            let h_edge = h_prime.get_edge(v_j, v_mapped);
+            let needed = g_edge.saturating_sub(h_edge);
+            if needed > 0 {
+                local_edges.insert((v_j, v_mapped), needed);
+            }
+        }
+
+        let g_edge_rev = g.get_edge(u_mapped, u_i);
+        if g_edge_rev > 0 {
+            let h_edge_rev = h_prime.get_edge(v_mapped, v_j);
+            let needed_rev = g_edge_rev.saturating_sub(h_edge_rev);
+            if needed_rev > 0 {
+                local_edges.insert((v_mapped, v_j), needed_rev);
+            }
+        }
+    }
+
+    objective.evaluate(&local_edges)
+}
+
+/// One partial mapping carried by [`beam_search_construct`]: the G-vertex ->
+/// H-vertex assignment built so far, the H vertices it's already claimed
+/// (kept alongside `assignment` so membership tests don't have to recompute
+/// `assignment.values().collect()` every candidate), and the accumulated sum
+/// of each step's [`calculate_local_cost`] -- the same quantity a width-1
+/// beam's single `min_local_cost` tracked implicitly by only ever keeping
+/// one candidate alive.
+#[derive(Clone)]
+struct BeamState {
+    assignment: HashMap<usize, usize>,
+    used_h_vertices: HashSet<usize>,
+    cost: usize,
+}
+
+/// Builds one complete mapping from G to H′, starting `u_start -> v_start`,
+/// by beam search: at each subsequent G vertex (in index order, skipping
+/// `u_start`), every surviving partial mapping is expanded with every
+/// unclaimed H vertex, scored by `calculate_local_cost`, and only the
+/// `beam_width` cheapest survive into the next step. Identical partial
+/// assignments reachable via different expansion orders are deduped to the
+/// cheapest copy before truncating, so the beam doesn't waste width on
+/// redundant copies of the same partial mapping.
+///
+/// `beam_width` of 1 -- keep only the single cheapest expansion at each
+/// step -- is exactly [`approximate_best_mapping`]'s original per-vertex
+/// greedy.
+///
+/// `deterministic_ties` controls how a cost tie among candidates is broken:
+/// set, it keeps construction order (partial mappings are generated
+/// vertex-index order, then H-vertex order, and the cost sort below is
+/// stable), so a width-1 beam reproduces that greedy's original choice of
+/// candidate bit for bit. Unset (the default), ties are broken uniformly at
+/// random via `rng` instead, so repeated trials from the same
+/// `(u_start, v_start)` seed can explore different partial mappings instead
+/// of always retracing the same lowest-index path.
+///
+/// Returns `None` if some G vertex runs out of unclaimed H vertices before
+/// every vertex is assigned (mirrors the old code silently discarding a
+/// trial whose `mapping_map.len() != n_g`).
+#[allow(clippy::too_many_arguments)]
+fn beam_search_construct(
+    g: &Graph,
+    h_prime: &Graph,
+    u_start: usize,
+    v_start: usize,
+    beam_width: usize,
+    objective: &Objective,
+    globally_used_h_vertices: &HashSet<usize>,
+    rng: &mut impl Rng,
+    deterministic_ties: bool,
+) -> Option<Mapping> {
+    let n_g = g.num_vertices();
+    let n_h = h_prime.num_vertices();
+    let beam_width = beam_width.max(1);
+
+    let mut start_assignment = HashMap::new();
+    start_assignment.insert(u_start, v_start);
+    let mut start_used = HashSet::new();
+    start_used.insert(v_start);
+
+    let mut beam = vec![BeamState {
+        assignment: start_assignment,
+        used_h_vertices: start_used,
+        cost: 0,
+    }];
+
+    for u_i in 0..n_g {
+        if u_i == u_start {
+            continue;
+        }
+
+        let mut candidates: Vec<BeamState> = Vec::new();
+        for state in &beam {
+            for v_j in 0..n_h {
+                if state.used_h_vertices.contains(&v_j) || globally_used_h_vertices.contains(&v_j)
+                {
+                    continue;
+                }
+
+                let local_cost = calculate_local_cost(u_i, v_j, g, h_prime, &state.assignment, objective);
+
+                let mut next = state.clone();
+                next.assignment.insert(u_i, v_j);
+                next.used_h_vertices.insert(v_j);
+                next.cost += local_cost;
+                candidates.push(next);
+            }
+        }
+
+        if candidates.is_empty() {
+            return None;
+        }
+
+        // Dedupe identical partial assignments to their cheapest copy,
+        // keeping each key's first-seen position so the stable sort below
+        // breaks ties the same way a single-candidate greedy would.
+        let mut deduped: Vec<BeamState> = Vec::new();
+        let mut index_by_assignment: HashMap<Vec<(usize, usize)>, usize> = HashMap::new();
+        for candidate in candidates {
+            let mut key: Vec<(usize, usize)> =
+                candidate.assignment.iter().map(|(&g, &h)| (g, h)).collect();
+            key.sort_unstable();
+
+            match index_by_assignment.get(&key) {
+                Some(&index) if candidate.cost < deduped[index].cost => deduped[index] = candidate,
+                Some(_) => {}
+                None => {
+                    index_by_assignment.insert(key, deduped.len());
+                    deduped.push(candidate);
+                }
+            }
+        }
+
+        // Shuffling before the stable sort randomizes the relative order of
+        // cost-tied candidates (a plain `sort_by_key` alone would always keep
+        // them in construction order), so `deterministic_ties: false`
+        // resolves ties uniformly at random instead of always keeping the
+        // lowest-index one.
+        if !deterministic_ties {
+            deduped.shuffle(rng);
+        }
+        deduped.sort_by_key(|state| state.cost);
+        deduped.truncate(beam_width);
+        beam = deduped;
+    }
+
+    beam.into_iter()
+        .min_by_key(|state| state.cost)
+        .map(|state| (0..n_g).map(|i| state.assignment[&i]).collect())
+}
+
+/// Find the approximately-best single mapping via randomized vertex-by-
+/// vertex greedy construction, trying up to `n_g * n_h * trials_multiplier`
+/// random starting points and keeping whichever complete, not-yet-used
+/// mapping is cheapest. Shared by `approx_solver` and `solver`'s
+/// approximation path so the two can't drift apart on what used to be a
+/// hand-maintained copy of the same search in each binary.
+///
+/// `h_prime` (H extended with everything committed so far) only guides the
+/// vertex-by-vertex greedy construction; the actual cost of each resulting
+/// candidate is charged against `current` via [`marginal_cost`] instead, so
+/// the trial search can use H′ as a cheap heuristic without that heuristic
+/// leaking into the reported cost (see `marginal_cost`'s doc comment for why
+/// scoring against H′ directly is subtly wrong).
+///
+/// `globally_used_h_vertices` holds every H vertex claimed by a mapping
+/// already returned from an earlier call in the same disjointness-
+/// constrained run; empty when no such constraint applies, in which case it
+/// excludes nothing.
+///
+/// `center_vertex`, if set, anchors every trial at that H vertex instead of
+/// sampling (falling back to `pagerank_weights` or uniform sampling on a
+/// trial where it's already claimed) -- meant to be computed once per
+/// mapping by the caller (e.g. from `h_prime.center()`), not re-derived here
+/// on every trial.
+///
+/// `beam_width` controls how many partial mappings each trial's
+/// vertex-by-vertex construction carries forward at once (see
+/// [`beam_search_construct`]); `1` reproduces the single-best-candidate
+/// greedy this function always used before beam search was added, exactly,
+/// since that's exactly a beam of width one.
+///
+/// `seed_strategy` controls how each trial picks `(u_start, v_start)` (see
+/// [`SeedStrategy`]); `center_vertex` and `pagerank_weights` still take
+/// priority over it for `v_start` when set, same as under the original
+/// uniform-random seeding.
+///
+/// `deterministic_ties` is forwarded to [`beam_search_construct`]: set, a
+/// cost tie always keeps the lowest-index H vertex, so trials sharing a seed
+/// vertex pair retrace the same construction; unset, ties are broken
+/// uniformly at random, giving repeated trials from the same seed pair a
+/// chance to diverge.
+///
+/// `stagnation_limit`, if set, stops the trial loop once that many
+/// consecutive trials in a row have failed to improve `min_global_cost`,
+/// instead of always exhausting the full `n_g * n_h * trials_multiplier`
+/// budget -- most of a large budget is typically spent re-finding the same
+/// local optimum. Independent of `early_stop`, which can still cut the loop
+/// short sooner (e.g. on a zero-cost find). `None` preserves the original
+/// behavior of never stopping for stagnation.
+///
+/// `deadline`, if set, stops the trial loop once `Instant::now()` passes it,
+/// same as `stagnation_limit` but on wall-clock time instead of trial count.
+/// The first trial always runs regardless of `deadline`, so a caller that's
+/// already out of time when it calls this still gets a best-effort mapping
+/// back rather than `None`.
+///
+/// Returns the winning mapping, the edges it adds, and the number of trials
+/// actually run, so a caller reporting progress can show how much of the
+/// budget early stopping saved.
+#[allow(clippy::too_many_arguments)]
+pub fn approximate_best_mapping(
+    g: &Graph,
+    h: &Graph,
+    h_prime: &Graph,
+    current: &EdgeMap,
+    used_mappings: &MappingSet,
+    trials_multiplier: usize,
+    rng: &mut impl Rng,
+    objective: &Objective,
+    early_stop: EarlyStop,
+    stagnation_limit: Option<usize>,
+    pagerank_weights: Option<&WeightedIndex<f64>>,
+    center_vertex: Option<usize>,
+    globally_used_h_vertices: &HashSet<usize>,
+    merge_semantics: MergeSemantics,
+    beam_width: usize,
+    seed_strategy: SeedStrategy,
+    deterministic_ties: bool,
+    deadline: Option<Instant>,
+) -> Option<(Mapping, EdgeMap, usize)> {
+    let n_g = g.num_vertices();
+    let n_h = h_prime.num_vertices();
+    let t = n_g * n_h * trials_multiplier;
+
+    let highest_degree_g_vertices = match seed_strategy {
+        SeedStrategy::HighestDegree => Some(highest_degree_vertices(g)),
+        SeedStrategy::Random => None,
+    };
+
+    let mut min_global_cost = usize::MAX;
+    let mut best_global_mapping: Option<Mapping> = None;
+    let mut best_edges_to_add = EdgeMap::new();
+    let mut trials_since_improvement = 0;
+    let mut trials_executed = 0;
+
+    for trial in 0..t {
+        if trial > 0 && deadline.is_some_and(|d| Instant::now() >= d) {
+            break;
+        }
+        trials_executed += 1;
+        let mut improved = false;
+
+        let u_start = match &highest_degree_g_vertices {
+            Some(candidates) => *candidates.choose(rng).unwrap(),
+            None => {
+                let g_vertices: Vec<usize> = (0..n_g).collect();
+                g_vertices.choose(rng).copied().unwrap()
+            }
+        };
+        let v_start = match center_vertex {
+            Some(v) if !globally_used_h_vertices.contains(&v) => v,
+            _ => match pagerank_weights {
+                Some(weights) => weights.sample(rng),
+                None => match seed_strategy {
+                    SeedStrategy::HighestDegree => best_covering_h_vertex(
+                        g,
+                        h_prime,
+                        u_start,
+                        globally_used_h_vertices,
+                        rng,
+                    )
+                    .unwrap_or_else(|| rng.gen_range(0..n_h)),
+                    SeedStrategy::Random => rng.gen_range(0..n_h),
+                },
+            },
+        };
+        // Skipped whenever the disjointness constraint already claimed this
+        // vertex; still counts against `stagnation_limit` below, same as any
+        // other trial that didn't improve on the best found so far.
+        if !globally_used_h_vertices.contains(&v_start) {
+            let mapping_vec = beam_search_construct(
+                g,
+                h_prime,
+                u_start,
+                v_start,
+                beam_width,
+                objective,
+                globally_used_h_vertices,
+                rng,
+                deterministic_ties,
+            );
+
+            if let Some(mapping_vec) = mapping_vec {
+                if !used_mappings.contains(&mapping_vec) {
+                    let (_, increments) =
+                        marginal_cost(g, h, current, &mapping_vec, merge_semantics);
+                    let current_cost = objective.evaluate(&increments);
+
+                    if current_cost < min_global_cost {
+                        min_global_cost = current_cost;
+                        best_global_mapping = Some(mapping_vec);
+                        best_edges_to_add = increments;
+                        improved = true;
+
+                        if early_stop.is_satisfied_by(min_global_cost) {
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+
+        if improved {
+            trials_since_improvement = 0;
+        } else {
+            trials_since_improvement += 1;
+            if stagnation_limit.is_some_and(|limit| trials_since_improvement >= limit) {
+                break;
+            }
+        }
+    }
+
+    best_global_mapping.map(|m| (m, best_edges_to_add, trials_executed))
+}
+
+/// Population size, generation budget, and operator rates for
+/// [`genetic_search`]. Mirrors [`SaSchedule`]'s role of bundling a stochastic
+/// search's parameters into one struct so CLI wiring only has to thread this
+/// instead of four separate arguments.
+#[derive(Clone, Copy, Debug)]
+pub struct GeneticConfig {
+    pub population_size: usize,
+    pub generations: usize,
+    /// Probability that a freshly bred individual receives one mutating
+    /// reassignment (see `genetic_search`'s mutation step).
+    pub mutation_rate: f64,
+    /// Number of individuals sampled per tournament-selection draw.
+    pub tournament_size: usize,
+    /// Number of fittest individuals carried into the next generation
+    /// unchanged.
+    pub elitism: usize,
+}
+
+impl Default for GeneticConfig {
+    /// A population of 50 run for 100 generations, a 10% mutation rate (low
+    /// enough that crossover does most of the exploring), tournaments of 3
+    /// (enough selection pressure without always just picking the single
+    /// fittest individual), and the top 2 carried over unchanged so the
+    /// best-found cost can never regress from one generation to the next.
+    fn default() -> Self {
+        GeneticConfig {
+            population_size: 50,
+            generations: 100,
+            mutation_rate: 0.1,
+            tournament_size: 3,
+            elitism: 2,
+        }
+    }
+}
+
+/// One generation's fitness summary from [`genetic_search`], reported so a
+/// caller can print or log progress without the search itself needing to
+/// know how -- the same split `EarlyStop` draws between policy and
+/// mechanism.
+#[derive(Clone, Copy, Debug)]
+pub struct GenerationStats {
+    pub generation: usize,
+    pub best_cost: usize,
+    pub mean_cost: f64,
+}
+
+/// A random injective mapping from G's vertices into H's, with no attempt to
+/// make it a good one -- the uninformed counterpart to
+/// `approximate_best_mapping`'s vertex-by-vertex greedy construction, cheap
+/// enough (`O(n_h)`) to call once per individual per generation across a
+/// whole population.
+fn random_mapping(n_g: usize, n_h: usize, rng: &mut impl Rng) -> Mapping {
+    let mut candidates: Vec<usize> = (0..n_h).collect();
+    candidates.shuffle(rng);
+    candidates.truncate(n_g);
+    candidates
+}
+
+/// A set of `k` distinct random mappings, built by resampling any mapping
+/// that collides with one already chosen -- the same distinctness rule
+/// `used_mappings` enforces elsewhere, just scoped to this individual alone
+/// rather than across a whole search.
+fn random_individual(n_g: usize, n_h: usize, k: usize, rng: &mut impl Rng) -> Vec<Mapping> {
+    let mut seen = MappingSet::default();
+    let mut individual = Vec::with_capacity(k);
+    for _ in 0..k {
+        let mut candidate = random_mapping(n_g, n_h, rng);
+        let mut attempts = 0;
+        while seen.contains(&candidate) && attempts < 50 {
+            candidate = random_mapping(n_g, n_h, rng);
+            attempts += 1;
+        }
+        seen.insert(candidate.clone());
+        individual.push(candidate);
+    }
+    individual
+}
+
+/// Single-point crossover along the `k`-mappings dimension: the child takes
+/// `parent_a`'s mappings up to a random point and `parent_b`'s from there on.
+/// Needs no repair for injectivity -- every position already holds a
+/// complete, independently-valid mapping -- but can reintroduce a duplicate
+/// mapping across the splice point, which `mutate_and_repair` cleans up.
+fn crossover(parent_a: &[Mapping], parent_b: &[Mapping], rng: &mut impl Rng) -> Vec<Mapping> {
+    let k = parent_a.len();
+    if k <= 1 {
+        return parent_a.to_vec();
+    }
+    let point = rng.gen_range(1..k);
+    parent_a[..point]
+        .iter()
+        .chain(parent_b[point..].iter())
+        .cloned()
+        .collect()
+}
+
+/// Mutates `individual` in place with probability `mutation_rate` by
+/// reassigning one random G vertex, within one random mapping of the set, to
+/// an H vertex not already used elsewhere in that same mapping -- injectivity
+/// is repaired by construction rather than by a separate pass. Afterwards
+/// (regardless of whether a mutation happened) resamples any mapping left
+/// duplicating an earlier one in the set, the same distinctness rule
+/// `random_individual` applies at construction time.
+fn mutate_and_repair(
+    individual: &mut [Mapping],
+    n_g: usize,
+    n_h: usize,
+    mutation_rate: f64,
+    rng: &mut impl Rng,
+) {
+    if rng.gen::<f64>() < mutation_rate {
+        if let Some(mapping) = individual.choose_mut(rng) {
+            let g_vertex = rng.gen_range(0..mapping.len());
+            let used: HashSet<usize> = mapping.iter().copied().collect();
+            let available: Vec<usize> = (0..n_h).filter(|v| !used.contains(v)).collect();
+            if let Some(&new_v) = available.choose(rng) {
+                mapping[g_vertex] = new_v;
+            }
+        }
+    }
+
+    let mut seen = MappingSet::default();
+    for mapping in individual.iter_mut() {
+        let mut attempts = 0;
+        while seen.contains(mapping) && attempts < 50 {
+            *mapping = random_mapping(n_g, n_h, rng);
+            attempts += 1;
+        }
+        seen.insert(mapping.clone());
+    }
+}
+
+/// Tournament selection: sample `tournament_size` individuals uniformly from
+/// `population` and return whichever is fittest. Smaller tournaments give
+/// weaker individuals more of a chance to be picked (and so to pass on their
+/// genes via crossover); `tournament_size == 1` degenerates to uniform random
+/// selection, `tournament_size >= population.len()` to always picking the
+/// single fittest individual.
+fn tournament_select<'a>(
+    population: &'a [(Vec<Mapping>, usize)],
+    tournament_size: usize,
+    rng: &mut impl Rng,
+) -> &'a [Mapping] {
+    population
+        .choose_multiple(rng, tournament_size.max(1))
+        .min_by_key(|(_, cost)| *cost)
+        .map(|(individual, _)| individual.as_slice())
+        .expect("population is non-empty, so choose_multiple yields at least one individual")
+}
+
+/// Builds a k-mapping set with a genetic algorithm: a population of complete
+/// k-mapping sets evolves under tournament selection, single-point crossover,
+/// and low-probability mutation, with elitism guaranteeing the best cost
+/// found never regresses across generations. Meant for instances too large
+/// for `marginal_cost_greedy`'s exhaustive pool or `approximate_best_mapping`
+/// trial-by-trial search to explore well -- a fixed population and generation
+/// budget replaces both with something that scales with those two knobs
+/// instead of with `n_g * n_h` or `|all_mappings|`.
+///
+/// Individuals are seeded by `random_individual` rather than by any of this
+/// module's greedy constructions, so the population starts with no bias
+/// toward a particular local optimum; `objective` alone drives which
+/// individuals survive from there.
+///
+/// Returns `None` if `g.num_vertices() > h.num_vertices()` (no injective
+/// mapping can exist) or if `k == 0` or `config.population_size == 0`.
+/// Otherwise returns the best individual found (in the same `(cost,
+/// edge_map, mappings)` shape every other search in this module returns) and
+/// one `GenerationStats` per generation, best-cost and mean-cost across the
+/// population, for the caller to report as progress.
+pub fn genetic_search(
+    g: &Graph,
+    h: &Graph,
+    k: usize,
+    objective: &Objective,
+    config: &GeneticConfig,
+    rng: &mut impl Rng,
+) -> Option<(GreedyResult, Vec<GenerationStats>)> {
+    let n_g = g.num_vertices();
+    let n_h = h.num_vertices();
+    if n_g > n_h || k == 0 || config.population_size == 0 {
+        return None;
+    }
+
+    let score = |individual: &[Mapping]| objective.evaluate(&calculate_edge_map(g, h, individual));
+
+    let mut population: Vec<(Vec<Mapping>, usize)> = (0..config.population_size)
+        .map(|_| {
+            let individual = random_individual(n_g, n_h, k, rng);
+            let cost = score(&individual);
+            (individual, cost)
+        })
+        .collect();
+
+    let mut history = Vec::with_capacity(config.generations);
+
+    for generation in 0..config.generations {
+        population.sort_by_key(|(_, cost)| *cost);
+
+        let best_cost = population[0].1;
+        let mean_cost =
+            population.iter().map(|(_, cost)| *cost as f64).sum::<f64>() / population.len() as f64;
+        history.push(GenerationStats {
+            generation,
+            best_cost,
+            mean_cost,
+        });
+
+        let mut next_generation: Vec<(Vec<Mapping>, usize)> = population
+            .iter()
+            .take(config.elitism.min(population.len()))
+            .cloned()
+            .collect();
+
+        while next_generation.len() < population.len() {
+            let parent_a = tournament_select(&population, config.tournament_size, rng);
+            let parent_b = tournament_select(&population, config.tournament_size, rng);
+            let mut child = crossover(parent_a, parent_b, rng);
+            mutate_and_repair(&mut child, n_g, n_h, config.mutation_rate, rng);
+            let cost = score(&child);
+            next_generation.push((child, cost));
+        }
+
+        population = next_generation;
+    }
+
+    population.sort_by_key(|(_, cost)| *cost);
+    let (best_individual, best_cost) = population.into_iter().next()?;
+    let edge_map = calculate_edge_map(g, h, &best_individual);
+
+    Some(((best_cost, edge_map, best_individual), history))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    /// An edgeless H gives every unclaimed H vertex the same local cost at
+    /// each construction step, so which one gets picked is decided purely by
+    /// `deterministic_ties` rather than by any structural preference.
+    fn edgeless_instance() -> (Graph, Graph) {
+        (Graph::new(3), Graph::new(6))
+    }
+
+    #[test]
+    fn test_deterministic_ties_always_keeps_the_lowest_index_candidate() {
+        let (g, h) = edgeless_instance();
+        let objective = Objective::TotalEdges;
+        let globally_used = HashSet::new();
+
+        let mappings: Vec<Mapping> = (0..20)
+            .map(|seed| {
+                let mut rng = StdRng::seed_from_u64(seed);
+                beam_search_construct(&g, &h, 0, 0, 1, &objective, &globally_used, &mut rng, true)
+                    .unwrap()
+            })
+            .collect();
+
+        assert!(mappings.windows(2).all(|pair| pair[0] == pair[1]));
+    }
+
+    #[test]
+    fn test_randomized_ties_can_produce_different_mappings_from_the_same_seed_pair() {
+        let (g, h) = edgeless_instance();
+        let objective = Objective::TotalEdges;
+        let globally_used = HashSet::new();
+
+        let mappings: HashSet<Mapping> = (0..20)
+            .map(|seed| {
+                let mut rng = StdRng::seed_from_u64(seed);
+                beam_search_construct(&g, &h, 0, 0, 1, &objective, &globally_used, &mut rng, false)
+                    .unwrap()
+            })
+            .collect();
+
+        assert!(
+            mappings.len() > 1,
+            "expected randomized tie-breaking to explore more than one mapping, got {:?}",
+            mappings
+        );
+    }
+}
@@ -0,0 +1,1174 @@
+use crate::{Graph, Mapping};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+/// How multiple mappings' demand on the same host edge combines into a
+/// single required multiplicity. See [`calculate_edge_map_with_semantics`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MergeSemantics {
+    /// A single unit of added capacity on an edge can serve every mapping
+    /// that demands it simultaneously, so the edge's required multiplicity
+    /// is the maximum demanded by any one mapping. What `calculate_edge_map`
+    /// has always done.
+    #[default]
+    Shared,
+    /// Each mapping consumes its own dedicated capacity on an edge -- e.g.
+    /// because the embeddings run concurrently and can't route through the
+    /// same added edge at once -- so the edge's required multiplicity is the
+    /// sum of every mapping's demand on it.
+    Dedicated,
+}
+
+/// Calculate the edge map needed to implement a set of mappings
+/// Returns a HashMap of (u, v) -> weight representing edges to add
+///
+/// Generic over `M: AsRef<[usize]>` so callers can pass either `&[Mapping]`
+/// (owned mappings, e.g. straight from a `Vec<Mapping>`) or `&[&Mapping]`
+/// (references, e.g. from `itertools::combinations`) without an intermediate
+/// collect.
+///
+/// Self-loops are not special-cased: `u == v` is considered like any other
+/// pair, so a self-loop on `g` requires a self-loop of at least the same
+/// weight on `h` at the mapped vertex. Use `Graph::remove_self_loops` before
+/// calling this if self-loops should be ignored instead.
+///
+/// Always uses [`MergeSemantics::Shared`] to combine mappings' demand on the
+/// same edge; see [`calculate_edge_map_with_semantics`] to select
+/// [`MergeSemantics::Dedicated`] instead.
+pub fn calculate_edge_map<M: AsRef<[usize]>>(
+    g: &Graph,
+    h: &Graph,
+    mappings: &[M],
+) -> HashMap<(usize, usize), usize> {
+    calculate_edge_map_with_semantics(g, h, mappings, MergeSemantics::Shared)
+}
+
+/// Like `calculate_edge_map`, but `semantics` selects how multiple mappings'
+/// demand on the same edge combines: `Shared` (what `calculate_edge_map`
+/// always uses) takes the maximum, as if one added unit of capacity could
+/// serve every mapping that needs it; `Dedicated` sums each mapping's demand
+/// instead, for settings where every embedding needs capacity of its own.
+pub fn calculate_edge_map_with_semantics<M: AsRef<[usize]>>(
+    g: &Graph,
+    h: &Graph,
+    mappings: &[M],
+    semantics: MergeSemantics,
+) -> HashMap<(usize, usize), usize> {
+    let mut edge_map = HashMap::new();
+
+    for mapping in mappings {
+        let mapping = mapping.as_ref();
+        for u in 0..g.num_vertices() {
+            for v in 0..g.num_vertices() {
+                let g_edge_count = g.get_edge(u, v);
+                if g_edge_count > 0 {
+                    let x = mapping[u];
+                    let y = mapping[v];
+                    let h_edge_count = h.get_edge(x, y);
+                    let needed = g_edge_count.saturating_sub(h_edge_count);
+                    if needed == 0 {
+                        continue;
+                    }
+
+                    let current = edge_map.get(&(x, y)).copied().unwrap_or(0);
+                    let combined = match semantics {
+                        MergeSemantics::Shared => needed.max(current),
+                        MergeSemantics::Dedicated => needed + current,
+                    };
+                    edge_map.insert((x, y), combined);
+                }
+            }
+        }
+    }
+
+    edge_map
+}
+
+/// Like `calculate_edge_map`, but a `g`-edge only counts towards the result
+/// if its weight is strictly greater than `threshold` -- built by pre-filtering
+/// `g` through `Graph::induced_subgraph_by_edges`, so an edge at or below the
+/// threshold is treated as if `g` never had it, rather than as an edge that
+/// needs zero weight on `h`.
+pub fn calculate_edge_map_above_threshold<M: AsRef<[usize]>>(
+    g: &Graph,
+    h: &Graph,
+    mappings: &[M],
+    threshold: usize,
+) -> HashMap<(usize, usize), usize> {
+    let heavy_g = g.induced_subgraph_by_edges(|_, _, weight| weight > threshold);
+    calculate_edge_map(&heavy_g, h, mappings)
+}
+
+/// Calculate total cost (sum of all edge weights in the edge map)
+pub fn calculate_total_cost(edge_map: &HashMap<(usize, usize), usize>) -> usize {
+    edge_map.values().sum()
+}
+
+/// The total cost in `calculate_total_cost` overflowed `u64` while being
+/// accumulated: enough edges with enough multiplicity that not even a 64-bit
+/// total can hold the sum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Overflow;
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "edge map total cost overflowed u64")
+    }
+}
+
+impl std::error::Error for Overflow {}
+
+/// Like `calculate_total_cost`, but sums in `u128` internally and checks the
+/// result fits in a `u64` instead of wrapping silently the way a plain
+/// `usize` sum could on a 32-bit target. Prefer this wherever the edge map
+/// might come from an untrusted source — e.g. `verify`'s cost check on a
+/// solution file someone else produced — rather than `calculate_total_cost`.
+pub fn calculate_total_cost_checked(
+    edge_map: &HashMap<(usize, usize), usize>,
+) -> Result<u64, Overflow> {
+    let total: u128 = edge_map.values().map(|&weight| weight as u128).sum();
+    u64::try_from(total).map_err(|_| Overflow)
+}
+
+/// `h` with `edge_map`'s additions applied, as a standalone `Graph`: for each
+/// `((u, v), weight)` in `edge_map`, the result's `(u, v)` entry is
+/// `h.get_edge(u, v).max(weight)`. Treats `weight` as an absolute target
+/// rather than a delta over `h`, which only matches what an `edge_map`
+/// actually contains when `h` started out edgeless at every position the map
+/// touches -- see [`apply_edge_map`] for the pure-addition version call sites
+/// now use where that doesn't hold.
+pub fn extended_host(h: &Graph, edge_map: &HashMap<(usize, usize), usize>) -> Graph {
+    let mut extended = h.clone();
+    for (&(u, v), &weight) in edge_map {
+        if weight > extended.adj[u][v] {
+            extended.adj[u][v] = weight;
+        }
+    }
+    extended
+}
+
+/// `h` with `edge_map`'s weights added on top, as a standalone `Graph`: for
+/// each `((u, v), weight)` in `edge_map`, the result's `(u, v)` entry is
+/// `h.get_edge(u, v) + weight`. Unlike `extended_host`'s "take the maximum"
+/// convention (for an `edge_map` whose weights are themselves already
+/// absolute targets), this treats `edge_map` as a pure delta on top of `h` --
+/// the right convention when `weight` was computed as a shortfall *relative
+/// to this same `h`* (e.g. `calculate_edge_map(g, h, ..)`), so adding it back
+/// reconstructs exactly the edge count `g` demanded. [`compute_edge_delta`]
+/// is the inverse.
+pub fn apply_edge_map(h: &Graph, edge_map: &HashMap<(usize, usize), usize>) -> Graph {
+    let mut extended = h.clone();
+    for (&(u, v), &weight) in edge_map {
+        extended.adj[u][v] += weight;
+    }
+    extended
+}
+
+/// The edge map that [`apply_edge_map`] would need to turn `h_original` into
+/// `h_extended`: for every `(u, v)` where `h_extended`'s weight exceeds
+/// `h_original`'s, the difference. Pairs where `h_extended` isn't larger are
+/// omitted entirely, matching `calculate_edge_map`'s convention of never
+/// storing a zero entry. Panics if the two graphs don't have the same vertex
+/// count, since there's then no shared `(u, v)` space to diff.
+pub fn compute_edge_delta(
+    h_original: &Graph,
+    h_extended: &Graph,
+) -> HashMap<(usize, usize), usize> {
+    assert_eq!(
+        h_original.num_vertices(),
+        h_extended.num_vertices(),
+        "compute_edge_delta requires both graphs to have the same vertex count"
+    );
+
+    let mut delta = HashMap::new();
+    for u in 0..h_original.num_vertices() {
+        for v in 0..h_original.num_vertices() {
+            let added = h_extended
+                .get_edge(u, v)
+                .saturating_sub(h_original.get_edge(u, v));
+            if added > 0 {
+                delta.insert((u, v), added);
+            }
+        }
+    }
+    delta
+}
+
+/// How much extra a single `candidate` mapping would cost on top of an
+/// already-committed `current` edge map (e.g. a running `minimal_extension`
+/// built from mappings chosen so far), and the increments that would need
+/// merging into it to account for the addition.
+///
+/// `current` is taken as a floor, not recomputed against: under
+/// [`MergeSemantics::Shared`], `candidate`'s own demand on an edge (via
+/// [`required_edges`], i.e. relative to the original `h`) is only charged for
+/// the amount above what `current` already promises there, mirroring
+/// `calculate_edge_map`'s per-edge max semantics without ever comparing
+/// against an intermediate extended host. This is what lets a caller like
+/// `sequential_greedy_extension` track a plain edge map across mappings
+/// instead of mutating a cumulative H′ — costing each candidate against H′
+/// instead of against the true running max is subtly wrong, since an edge's
+/// shortfall against H′ understates how much more an earlier mapping's own
+/// demand on that edge already covers. Under [`MergeSemantics::Dedicated`],
+/// `candidate`'s full demand is charged regardless of `current`, since each
+/// mapping needs capacity of its own rather than sharing what's already
+/// promised.
+pub fn marginal_cost(
+    g: &Graph,
+    h: &Graph,
+    current: &HashMap<(usize, usize), usize>,
+    candidate: &Mapping,
+    semantics: MergeSemantics,
+) -> (usize, HashMap<(usize, usize), usize>) {
+    let mut increments = HashMap::new();
+    let mut total = 0;
+
+    for (edge, needed) in required_edges(g, h, candidate) {
+        let increment = match semantics {
+            MergeSemantics::Shared => {
+                let promised = current.get(&edge).copied().unwrap_or(0);
+                needed.saturating_sub(promised)
+            }
+            MergeSemantics::Dedicated => needed,
+        };
+        if increment > 0 {
+            increments.insert(edge, increment);
+            total += increment;
+        }
+    }
+
+    (total, increments)
+}
+
+/// Edge additions and deletions implied by a set of mappings, for the opt-in
+/// edit-style extension (gated behind `--allow-deletions`; see
+/// `calculate_edit_map`).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EditMap {
+    /// Edges to add, and by how much. Identical to what `calculate_edge_map`
+    /// would return for the same `g`/`h`/mappings, regardless of
+    /// `keep_threshold`.
+    pub additions: HashMap<(usize, usize), usize>,
+    /// H edges whose weight exceeds what any mapping actually demands of
+    /// them by more than `keep_threshold`, and by how much the excess is.
+    /// Empty when `keep_threshold` is large enough that nothing clears it.
+    pub deletions: HashMap<(usize, usize), usize>,
+}
+
+impl EditMap {
+    /// Combined cost: `addition_weight` per unit added plus `deletion_weight`
+    /// per unit deleted. With `deletions` empty (e.g. `keep_threshold =
+    /// usize::MAX` when building this map) this is exactly
+    /// `calculate_total_cost(&self.additions) * addition_weight`, matching
+    /// the original addition-only cost.
+    pub fn cost(&self, addition_weight: usize, deletion_weight: usize) -> usize {
+        let addition_cost = calculate_total_cost(&self.additions).saturating_mul(addition_weight);
+        let deletion_cost = calculate_total_cost(&self.deletions).saturating_mul(deletion_weight);
+        addition_cost.saturating_add(deletion_cost)
+    }
+}
+
+/// Like `calculate_edge_map`, but also reports H edges that are safe to
+/// prune: weight exceeding what any mapping in `mappings` actually demands
+/// of that edge by more than `keep_threshold`. `additions` is always
+/// identical to `calculate_edge_map`'s result; pass `keep_threshold =
+/// usize::MAX` to leave `deletions` empty and reproduce today's
+/// addition-only behavior exactly.
+pub fn calculate_edit_map<M: AsRef<[usize]>>(
+    g: &Graph,
+    h: &Graph,
+    mappings: &[M],
+    keep_threshold: usize,
+) -> EditMap {
+    let additions = calculate_edge_map(g, h, mappings);
+
+    // The maximum multiplicity any mapping actually demands of each host
+    // edge, independent of whether H already satisfies it (unlike
+    // `additions`, which only records edges still needing more).
+    let mut demand: HashMap<(usize, usize), usize> = HashMap::new();
+    for mapping in mappings {
+        let mapping = mapping.as_ref();
+        for u in 0..g.num_vertices() {
+            for v in 0..g.num_vertices() {
+                let g_edge_count = g.get_edge(u, v);
+                if g_edge_count > 0 {
+                    let edge = (mapping[u], mapping[v]);
+                    let current = demand.get(&edge).copied().unwrap_or(0);
+                    if g_edge_count > current {
+                        demand.insert(edge, g_edge_count);
+                    }
+                }
+            }
+        }
+    }
+
+    let mut deletions = HashMap::new();
+    for x in 0..h.num_vertices() {
+        for y in 0..h.num_vertices() {
+            let h_weight = h.get_edge(x, y);
+            if h_weight == 0 {
+                continue;
+            }
+            let required = demand.get(&(x, y)).copied().unwrap_or(0);
+            let excess = h_weight.saturating_sub(required);
+            if excess > keep_threshold {
+                deletions.insert((x, y), excess);
+            }
+        }
+    }
+
+    EditMap {
+        additions,
+        deletions,
+    }
+}
+
+/// Which scalar objective to minimize when comparing candidate edge maps.
+///
+/// `calculate_total_cost` covers the default case (`TotalEdges`) directly;
+/// this is for callers — the exact and approximate solvers — that need to
+/// select between objectives at runtime, e.g. via a `--objective` flag.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Objective {
+    /// Sum of every added edge's multiplicity. The original objective.
+    TotalEdges,
+    /// The single largest added multiplicity on any one edge. Useful when a
+    /// capacity constraint makes one heavily-loaded edge worse than many
+    /// lightly-loaded ones.
+    MaxEdgeMultiplicity,
+    /// Sum of added multiplicities, each scaled by a per-edge weight. Edges
+    /// with no entry in the weight map default to weight 1, so this reduces
+    /// to `TotalEdges` when the map is empty.
+    WeightedTotal(HashMap<(usize, usize), usize>),
+}
+
+impl Objective {
+    /// Score `edge_map` under this objective; lower is better.
+    pub fn evaluate(&self, edge_map: &HashMap<(usize, usize), usize>) -> usize {
+        match self {
+            Objective::TotalEdges => calculate_total_cost(edge_map),
+            Objective::MaxEdgeMultiplicity => edge_map.values().copied().max().unwrap_or(0),
+            Objective::WeightedTotal(weights) => edge_map
+                .iter()
+                .map(|(edge, &needed)| needed * weights.get(edge).copied().unwrap_or(1))
+                .sum(),
+        }
+    }
+}
+
+/// Incrementally tracks the edge map (and its total cost) for a *set* of
+/// mappings as mappings are added and removed one at a time.
+///
+/// `calculate_edge_map` recomputes the per-edge combination from scratch over
+/// every mapping in the set; that's wasteful for a search that explores
+/// combinations sharing k-1 mappings with their neighbours (e.g. a
+/// push/pop combination walk). This keeps, per edge, a multiset of the
+/// per-mapping required multiplicities (as a value -> count map) so the
+/// combined requirement can be recovered in O(log n) after a removal
+/// (O(1) for [`MergeSemantics::Dedicated`]'s running sum), instead of
+/// rescanning every remaining mapping.
+#[derive(Debug, Clone, Default)]
+pub struct EdgeMapAccumulator {
+    multisets: HashMap<(usize, usize), BTreeMap<usize, usize>>,
+    semantics: MergeSemantics,
+}
+
+impl EdgeMapAccumulator {
+    /// Combines mappings' demand on the same edge with
+    /// [`MergeSemantics::Shared`]; see [`Self::with_semantics`] to select
+    /// [`MergeSemantics::Dedicated`] instead.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Like `new`, but combines mappings' demand on the same edge with
+    /// `semantics` instead of always defaulting to `Shared`.
+    pub fn with_semantics(semantics: MergeSemantics) -> Self {
+        Self {
+            semantics,
+            ..Self::default()
+        }
+    }
+
+    /// Add `mapping`'s required edge multiplicities into the running set.
+    pub fn add_mapping<M: AsRef<[usize]>>(&mut self, g: &Graph, h: &Graph, mapping: M) {
+        let mapping = mapping.as_ref();
+        for (edge, needed) in required_edges(g, h, mapping) {
+            *self
+                .multisets
+                .entry(edge)
+                .or_default()
+                .entry(needed)
+                .or_insert(0) += 1;
+        }
+    }
+
+    /// Remove a previously-added `mapping` from the running set.
+    ///
+    /// `mapping` must have been passed to a matching `add_mapping` call with
+    /// the same `g`/`h` that has not since been removed.
+    pub fn remove_mapping<M: AsRef<[usize]>>(&mut self, g: &Graph, h: &Graph, mapping: M) {
+        let mapping = mapping.as_ref();
+        for (edge, needed) in required_edges(g, h, mapping) {
+            if let Some(multiset) = self.multisets.get_mut(&edge) {
+                if let Some(count) = multiset.get_mut(&needed) {
+                    *count -= 1;
+                    if *count == 0 {
+                        multiset.remove(&needed);
+                    }
+                }
+                if multiset.is_empty() {
+                    self.multisets.remove(&edge);
+                }
+            }
+        }
+    }
+
+    /// An edge's currently-required multiplicity, combining its multiset of
+    /// per-mapping demands according to `self.semantics`.
+    fn combine(&self, multiset: &BTreeMap<usize, usize>) -> usize {
+        match self.semantics {
+            MergeSemantics::Shared => *multiset.keys().next_back().unwrap(),
+            MergeSemantics::Dedicated => multiset
+                .iter()
+                .map(|(&needed, &count)| needed * count)
+                .sum(),
+        }
+    }
+
+    /// The edge map implied by the currently-added mappings, combined
+    /// according to `self.semantics`.
+    pub fn edge_map(&self) -> HashMap<(usize, usize), usize> {
+        self.multisets
+            .iter()
+            .map(|(&edge, multiset)| (edge, self.combine(multiset)))
+            .collect()
+    }
+
+    /// Total cost of the edge map implied by the currently-added mappings.
+    pub fn current_cost(&self) -> usize {
+        self.multisets
+            .values()
+            .map(|multiset| self.combine(multiset))
+            .sum()
+    }
+
+    /// Score the edge map implied by the currently-added mappings under
+    /// `objective`; lower is better. Equivalent to
+    /// `objective.evaluate(&self.edge_map())` but without materializing the
+    /// intermediate `HashMap` for the common `TotalEdges`/`MaxEdgeMultiplicity`
+    /// cases.
+    pub fn evaluate(&self, objective: &Objective) -> usize {
+        match objective {
+            Objective::TotalEdges => self.current_cost(),
+            Objective::MaxEdgeMultiplicity => self
+                .multisets
+                .values()
+                .map(|multiset| self.combine(multiset))
+                .max()
+                .unwrap_or(0),
+            Objective::WeightedTotal(_) => objective.evaluate(&self.edge_map()),
+        }
+    }
+}
+
+/// LRU cache from a subset of mapping indices to the edge map
+/// `calculate_edge_map` would compute for exactly those mappings, for
+/// searches (e.g. `solver`'s exact algorithm) that evaluate many
+/// k-combinations drawn from a shared pool and re-derive most of each one's
+/// edge map from a (k-1)-subset they already evaluated. A lookup that misses
+/// but whose subset-minus-its-largest-index is cached reuses that entry
+/// instead of recomputing every mapping's contribution from scratch.
+///
+/// Keyed by a hash of the sorted index subset rather than the subset itself,
+/// so cache entries stay a fixed size regardless of `k`; collisions are
+/// accepted as vanishingly unlikely for the subset sizes this is used for,
+/// the same tradeoff any `HashMap` key makes.
+/// An entry's edge map, paired with the `clock` tick it was last looked up
+/// at, so the least-recently-used entry is whichever has the smallest one.
+type CacheEntry = (HashMap<(usize, usize), usize>, u64);
+
+#[derive(Debug)]
+pub struct CachedEvaluator {
+    capacity: usize,
+    entries: HashMap<u64, CacheEntry>,
+    clock: u64,
+    hits: usize,
+    misses: usize,
+}
+
+impl CachedEvaluator {
+    /// `capacity` is the maximum number of subsets kept at once; `0` disables
+    /// caching (every lookup is recomputed from scratch), which is useful as
+    /// a baseline to check the cache never changes results, only speed.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: HashMap::new(),
+            clock: 0,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// The edge map for the mappings at `indices` into `all_mappings`.
+    /// Equivalent to `calculate_edge_map(g, h, &indices.iter().map(|&i|
+    /// &all_mappings[i]).collect::<Vec<_>>())`, just with the lookups and
+    /// reuse described on the type.
+    pub fn edge_map(
+        &mut self,
+        g: &Graph,
+        h: &Graph,
+        all_mappings: &[Mapping],
+        indices: &[usize],
+    ) -> HashMap<(usize, usize), usize> {
+        let mut sorted = indices.to_vec();
+        sorted.sort_unstable();
+        self.clock += 1;
+
+        let key = Self::key_for(&sorted);
+        if let Some((cached, last_used)) = self.entries.get_mut(&key) {
+            *last_used = self.clock;
+            self.hits += 1;
+            return cached.clone();
+        }
+        self.misses += 1;
+
+        let edge_map = sorted
+            .split_last()
+            .and_then(|(&last, prefix)| {
+                let prefix_key = Self::key_for(prefix);
+                self.entries.get(&prefix_key).map(|(prefix_map, _)| {
+                    let mut merged = prefix_map.clone();
+                    for (edge, needed) in required_edges(g, h, &all_mappings[last]) {
+                        let current = merged.get(&edge).copied().unwrap_or(0);
+                        if needed > current {
+                            merged.insert(edge, needed);
+                        }
+                    }
+                    merged
+                })
+            })
+            .unwrap_or_else(|| {
+                let chosen: Vec<&Mapping> = sorted.iter().map(|&i| &all_mappings[i]).collect();
+                calculate_edge_map(g, h, &chosen)
+            });
+
+        self.insert(key, edge_map.clone());
+        edge_map
+    }
+
+    /// Number of `edge_map` calls served from the cache so far.
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    /// Number of `edge_map` calls that had to be (at least partially)
+    /// computed so far.
+    pub fn misses(&self) -> usize {
+        self.misses
+    }
+
+    /// Fraction of `edge_map` calls served entirely from the cache, in
+    /// `[0.0, 1.0]`. `0.0` if `edge_map` has never been called.
+    pub fn hit_rate(&self) -> f64 {
+        let total = self.hits + self.misses;
+        if total == 0 {
+            0.0
+        } else {
+            self.hits as f64 / total as f64
+        }
+    }
+
+    fn key_for(indices: &[usize]) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        indices.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn insert(&mut self, key: u64, value: HashMap<(usize, usize), usize>) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.entries.len() >= self.capacity {
+            if let Some(&lru_key) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, (_, last_used))| *last_used)
+                .map(|(k, _)| k)
+            {
+                self.entries.remove(&lru_key);
+            }
+        }
+        self.entries.insert(key, (value, self.clock));
+    }
+}
+
+/// Lower bound on the total cost of *any* k >= 1 solution: the cost of the
+/// single cheapest mapping from `g` into `h`.
+///
+/// Per-edge cost is a max across the chosen mappings, never a sum, so
+/// adding more mappings to a combination can only hold or raise its total
+/// cost. The optimal combination for any k therefore costs at least as
+/// much as its cheapest individual mapping — and no mapping costs less
+/// than the cheapest one overall. Useful on its own (e.g. the approx
+/// solver can report how far its answer might be from optimal) and as the
+/// `cheapest_remaining` input to `lower_bound_partial`.
+pub fn lower_bound_single(g: &Graph, h: &Graph) -> usize {
+    crate::mapping::find_all_mappings(g, h)
+        .iter()
+        .map(|mapping| {
+            calculate_total_cost(&calculate_edge_map(g, h, std::slice::from_ref(mapping)))
+        })
+        .min()
+        .unwrap_or(0)
+}
+
+/// Lower bound on the final total cost of a combination being built
+/// incrementally (e.g. by `exact_solver`'s combination walk), given the
+/// mappings chosen so far (`accumulated`), how many more are still needed
+/// (`remaining`), and a lower bound on any single mapping's own cost
+/// (`cheapest_remaining`, typically `lower_bound_single(g, h)`).
+///
+/// `accumulated.current_cost()` alone is always a valid lower bound, since
+/// per-edge cost only rises as more mappings are added. When mappings are
+/// still needed, at least one of them must itself cost at least
+/// `cheapest_remaining`, even in the best case where it adds nothing new
+/// on top of `accumulated` — so the bound is never looser than that either.
+pub fn lower_bound_partial(
+    accumulated: &EdgeMapAccumulator,
+    remaining: usize,
+    cheapest_remaining: usize,
+) -> usize {
+    if remaining == 0 {
+        accumulated.current_cost()
+    } else {
+        accumulated.current_cost().max(cheapest_remaining)
+    }
+}
+
+/// Lower bound on the cost of embedding every edge of `g` into `h` via a
+/// single mapping, computed directly from `g` and `h`'s edge weights with no
+/// mapping enumeration at all: for each edge `(u, v)` of `g`, the cheapest it
+/// could possibly cost is the minimum over every pair of `h` vertices `(x,
+/// y)` of `max(0, g.get_edge(u, v) - h.get_edge(x, y))`, and summing that
+/// over every edge of `g` assumes each one lands on its own best-case pair
+/// independently -- which no single mapping can actually guarantee (the same
+/// `h` vertex can't be every edge's best target at once), but which also
+/// means no mapping can cost less.
+///
+/// Unlike `lower_bound_single`, this needs no mapping pool at all, so it's
+/// cheap enough to report before `find_all_mappings` even runs. For a
+/// k-mapping solution under [`MergeSemantics::Dedicated`], where each
+/// mapping's demand on a host edge is its own and never shared, every one of
+/// the `k` mappings pays this cost independently, so `k *
+/// cost_lower_bound(g, h)` bounds the total. Under [`MergeSemantics::Shared`],
+/// where mappings can share the same added capacity, this single-mapping
+/// value (not `k` times it, see `lower_bound_single`) is the bound that
+/// applies instead.
+pub fn cost_lower_bound(g: &Graph, h: &Graph) -> usize {
+    let mut total = 0;
+    for u in 0..g.num_vertices() {
+        for v in 0..g.num_vertices() {
+            let g_edge_count = g.get_edge(u, v);
+            if g_edge_count == 0 {
+                continue;
+            }
+            let cheapest = (0..h.num_vertices())
+                .flat_map(|x| (0..h.num_vertices()).map(move |y| (x, y)))
+                .map(|(x, y)| g_edge_count.saturating_sub(h.get_edge(x, y)))
+                .min()
+                .unwrap_or(g_edge_count);
+            total += cheapest;
+        }
+    }
+    total
+}
+
+/// Above this many candidate mappings, [`approximation_lower_bound`] skips
+/// enumerating them (via [`crate::mapping::find_k_cheapest_mappings`]) and
+/// falls back to the no-enumeration [`cost_lower_bound`] instead, the same
+/// way [`crate::stats::MAX_VERTICES_FOR_DIAMETER`] caps an unrelated
+/// otherwise-unbounded computation.
+pub const MAX_ENUMERATED_MAPPINGS_FOR_APPROXIMATION_GAP: u128 = 1_000_000;
+
+/// A lower bound on the true optimal cost for `k` mappings under
+/// `merge_semantics`, cheap enough to report alongside every approximate
+/// solution as a sanity check on how far it might be from optimal (see
+/// [`format_approximation_gap`]).
+///
+/// When `g` maps into `h` in few enough ways to afford enumerating them (see
+/// [`MAX_ENUMERATED_MAPPINGS_FOR_APPROXIMATION_GAP`]), uses the cheapest
+/// single mapping's actual cost, from
+/// [`crate::mapping::find_k_cheapest_mappings`] limited to 1; otherwise falls
+/// back to the no-enumeration [`cost_lower_bound`]. Either way, that single-
+/// mapping cost lower-bounds any one mapping's contribution -- multiplying it
+/// by `k` stays a valid bound on the whole set only under
+/// [`MergeSemantics::Dedicated`], where every mapping pays for its own
+/// capacity independently; under [`MergeSemantics::Shared`], mappings can
+/// reuse each other's added capacity, so a single copy is the bound.
+pub fn approximation_lower_bound(g: &Graph, h: &Graph, k: usize, merge_semantics: MergeSemantics) -> usize {
+    let n_g = g.num_vertices();
+    let n_h = h.num_vertices();
+
+    let single = if crate::utils::permutation_count(n_h, n_g)
+        <= MAX_ENUMERATED_MAPPINGS_FOR_APPROXIMATION_GAP
+    {
+        crate::mapping::find_k_cheapest_mappings(g, h, 1)
+            .first()
+            .map(|(cost, _)| *cost)
+            .unwrap_or_else(|| cost_lower_bound(g, h))
+    } else {
+        cost_lower_bound(g, h)
+    };
+
+    match merge_semantics {
+        MergeSemantics::Dedicated => single.saturating_mul(k),
+        MergeSemantics::Shared => single,
+    }
+}
+
+/// Format an approximate solution's cost against `approximation_lower_bound`
+/// as a one-line summary, e.g. `"Approx cost 42, lower bound 31, gap <= 35%"`.
+///
+/// The percentage is an upper bound on the true gap to the (unknown) optimal
+/// cost, not the gap itself: since `optimal >= lower_bound`, `(approx_cost -
+/// lower_bound) / lower_bound` can only be greater than or equal to the real
+/// `(approx_cost - optimal) / optimal`. A `lower_bound` of zero carries no
+/// information (a zero-cost embedding isn't ruled out), so the gap is
+/// reported as unknown rather than dividing by zero.
+pub fn format_approximation_gap(approx_cost: usize, lower_bound: usize) -> String {
+    if lower_bound == 0 {
+        return format!(
+            "Approx cost {}, lower bound {}, gap unknown (lower bound is 0)",
+            approx_cost, lower_bound
+        );
+    }
+
+    let gap_percent = (approx_cost.saturating_sub(lower_bound) as f64 / lower_bound as f64 * 100.0)
+        .round() as usize;
+    format!(
+        "Approx cost {}, lower bound {}, gap <= {}%",
+        approx_cost, lower_bound, gap_percent
+    )
+}
+
+/// Check that `mappings` are a legitimate solution for embedding `g` into
+/// `h` `mappings.len()` times, and that `reported_cost` is the total cost
+/// `calculate_edge_map` would actually charge for them. Intended for
+/// independently verifying a solution someone else produced (see
+/// `src/bin/verify.rs`), so every failure mode returns a specific message
+/// rather than panicking on malformed input.
+pub fn validate_solution(
+    g: &Graph,
+    h: &Graph,
+    mappings: &[Mapping],
+    reported_cost: usize,
+) -> Result<(), String> {
+    if mappings.is_empty() {
+        return Err("no mappings provided".to_string());
+    }
+
+    for (idx, mapping) in mappings.iter().enumerate() {
+        if mapping.len() != g.num_vertices() {
+            return Err(format!(
+                "mapping {} has {} entries, expected {} (G's vertex count)",
+                idx + 1,
+                mapping.len(),
+                g.num_vertices()
+            ));
+        }
+        if let Some(&v) = mapping.iter().find(|&&v| v >= h.num_vertices()) {
+            return Err(format!(
+                "mapping {} maps to H vertex {}, out of range (H has {} vertices)",
+                idx + 1,
+                v,
+                h.num_vertices()
+            ));
+        }
+        let mut seen = HashSet::new();
+        if !mapping.iter().all(|&v| seen.insert(v)) {
+            return Err(format!("mapping {} is not injective", idx + 1));
+        }
+    }
+
+    let mut distinct = HashSet::new();
+    for (idx, mapping) in mappings.iter().enumerate() {
+        if !distinct.insert(mapping) {
+            return Err(format!(
+                "mapping {} duplicates an earlier mapping in the set",
+                idx + 1
+            ));
+        }
+    }
+
+    let actual_cost = calculate_total_cost(&calculate_edge_map(g, h, mappings));
+    if actual_cost != reported_cost {
+        return Err(format!(
+            "reported cost {} does not match actual cost {}",
+            reported_cost, actual_cost
+        ));
+    }
+
+    Ok(())
+}
+
+/// Per-mapping accounting of a single mapping's edges: which H edges it
+/// requires, how much of that was already present in `h`, how much is newly
+/// added on top, and which of those additions are also demanded by at least
+/// one other mapping in the same set (since `calculate_edge_map` takes a max
+/// across mappings, such edges are "shared" — removing this one mapping alone
+/// wouldn't let the edge be pruned). See [`breakdown`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MappingCostDetail {
+    pub mapping_index: usize,
+    /// Every H edge this mapping's embedding requires, and the multiplicity
+    /// G demands of it (regardless of what H already has).
+    pub required: HashMap<(usize, usize), usize>,
+    /// The subset of `required` already satisfied by `h`, capped at the
+    /// required amount.
+    pub already_present: HashMap<(usize, usize), usize>,
+    /// The shortfall of `required` over `already_present`: exactly what this
+    /// mapping alone would need added.
+    pub newly_added: HashMap<(usize, usize), usize>,
+    /// The subset of `newly_added` that at least one other mapping in the
+    /// set also needs added, so it isn't this mapping's alone to justify.
+    pub shared_with_others: HashMap<(usize, usize), usize>,
+}
+
+/// Per-mapping breakdown of `calculate_edge_map(g, h, mappings)`'s cost: for
+/// each mapping, which edges it needs, which of those H already has, which
+/// are newly added, and which of the additions are shared with other
+/// mappings in the set. Useful for explaining *why* a combination costs what
+/// it does, one mapping at a time, rather than just the aggregate edge map.
+pub fn breakdown<M: AsRef<[usize]>>(
+    g: &Graph,
+    h: &Graph,
+    mappings: &[M],
+) -> Vec<MappingCostDetail> {
+    let per_mapping_needed: Vec<HashMap<(usize, usize), usize>> = mappings
+        .iter()
+        .map(|mapping| required_edges(g, h, mapping.as_ref()).collect())
+        .collect();
+
+    let mut demanded_by = HashMap::new();
+    for needed in &per_mapping_needed {
+        for &edge in needed.keys() {
+            *demanded_by.entry(edge).or_insert(0) += 1;
+        }
+    }
+
+    mappings
+        .iter()
+        .enumerate()
+        .map(|(mapping_index, mapping)| {
+            let mapping = mapping.as_ref();
+            let mut required = HashMap::new();
+            let mut already_present = HashMap::new();
+            for u in 0..g.num_vertices() {
+                for v in 0..g.num_vertices() {
+                    let g_edge_count = g.get_edge(u, v);
+                    if g_edge_count == 0 {
+                        continue;
+                    }
+                    let edge = (mapping[u], mapping[v]);
+                    required.insert(edge, g_edge_count);
+                    let present = g_edge_count.min(h.get_edge(edge.0, edge.1));
+                    if present > 0 {
+                        already_present.insert(edge, present);
+                    }
+                }
+            }
+
+            let newly_added = per_mapping_needed[mapping_index].clone();
+            let shared_with_others = newly_added
+                .iter()
+                .filter(|(edge, _)| demanded_by.get(edge).copied().unwrap_or(0) > 1)
+                .map(|(&edge, &needed)| (edge, needed))
+                .collect();
+
+            MappingCostDetail {
+                mapping_index,
+                required,
+                already_present,
+                newly_added,
+                shared_with_others,
+            }
+        })
+        .collect()
+}
+
+/// How much `calculate_edge_map`'s per-edge max across mappings saves over
+/// costing each mapping as if it were chosen alone. See [`sharing_stats`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SharingStats {
+    /// Sum of each mapping's own newly-added cost in isolation, i.e. what the
+    /// total would be if every mapping's additions were charged separately
+    /// instead of merged by [`calculate_edge_map`]'s per-edge max.
+    pub sum_of_individual_costs: usize,
+    /// `calculate_total_cost` of the actual merged edge map for the whole set.
+    pub merged_total_cost: usize,
+    /// Number of distinct `(x, y)` edges that at least two mappings in the
+    /// set both require adding.
+    pub shared_edge_count: usize,
+}
+
+impl SharingStats {
+    /// `sum_of_individual_costs / merged_total_cost`: how many times over the
+    /// merged cost would have been paid without sharing. `1.0` when
+    /// `merged_total_cost` is `0` (nothing was added, so there's nothing to
+    /// save on).
+    pub fn savings_ratio(&self) -> f64 {
+        if self.merged_total_cost == 0 {
+            1.0
+        } else {
+            self.sum_of_individual_costs as f64 / self.merged_total_cost as f64
+        }
+    }
+}
+
+/// Quantify how much `mappings` share added edges with each other, via
+/// [`breakdown`]'s per-mapping accounting. See [`SharingStats`].
+pub fn sharing_stats<M: AsRef<[usize]>>(g: &Graph, h: &Graph, mappings: &[M]) -> SharingStats {
+    let details = breakdown(g, h, mappings);
+
+    let sum_of_individual_costs = details
+        .iter()
+        .map(|detail| calculate_total_cost(&detail.newly_added))
+        .sum();
+    let merged_total_cost = calculate_total_cost(&calculate_edge_map(g, h, mappings));
+    let shared_edge_count = details
+        .iter()
+        .flat_map(|detail| detail.shared_with_others.keys())
+        .collect::<HashSet<_>>()
+        .len();
+
+    SharingStats {
+        sum_of_individual_costs,
+        merged_total_cost,
+        shared_edge_count,
+    }
+}
+
+/// For each `g` edge `(u, v)`, how many `mappings` already satisfy it in `h`
+/// without needing any addition, i.e. `h.get_edge(phi(u), phi(v)) >=
+/// g.get_edge(u, v)`. An edge's count reaching `mappings.len()` means every
+/// mapping already covers it; a count of `0` means none do, so it's carrying
+/// its full `g` weight into `calculate_edge_map`'s cost for at least one
+/// mapping. Unlike [`breakdown`], which is organized per mapping, this is
+/// organized per edge -- the view `solver`'s coverage table needs to show
+/// which edges are the hard ones across the whole set.
+pub fn coverage_analysis(
+    g: &Graph,
+    h: &Graph,
+    mappings: &[Mapping],
+) -> HashMap<(usize, usize), usize> {
+    let mut coverage = HashMap::new();
+
+    for u in 0..g.num_vertices() {
+        for v in 0..g.num_vertices() {
+            let weight = g.get_edge(u, v);
+            if weight == 0 {
+                continue;
+            }
+
+            let count = mappings
+                .iter()
+                .filter(|mapping| h.get_edge(mapping[u], mapping[v]) >= weight)
+                .count();
+            coverage.insert((u, v), count);
+        }
+    }
+
+    coverage
+}
+
+/// Per-mapping decomposition of `calculate_edge_map(g, h, mappings)`'s cost,
+/// from three different angles. See [`calculate_cost_matrix`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CostMatrix {
+    /// `per_mapping[i]` is mapping `i`'s own cost as if it were the only
+    /// mapping chosen (`calculate_total_cost` of its `required_edges`).
+    pub per_mapping: Vec<usize>,
+    /// The merged edge map for the whole set, identical to what
+    /// `calculate_edge_map(g, h, mappings)` returns.
+    pub per_edge: HashMap<(usize, usize), usize>,
+    /// `marginal[i]` is how much the total cost increases by adding mapping
+    /// `i` on top of mappings `0..i` already committed, in the order given
+    /// (see [`marginal_cost`]). Order-dependent: permuting `mappings` changes
+    /// which marginal costs are attributed to which index, though `per_edge`
+    /// and the sum of `marginal` do not change.
+    pub marginal: Vec<usize>,
+}
+
+/// Decompose `calculate_edge_map(g, h, mappings)`'s cost per mapping three
+/// ways: standalone (`per_mapping`), merged (`per_edge`), and marginal
+/// (`marginal`, via repeated [`marginal_cost`] calls in the given order).
+pub fn calculate_cost_matrix(g: &Graph, h: &Graph, mappings: &[Mapping]) -> CostMatrix {
+    let per_mapping = mappings
+        .iter()
+        .map(|mapping| calculate_total_cost(&required_edges(g, h, mapping).collect()))
+        .collect();
+
+    let per_edge = calculate_edge_map(g, h, mappings);
+
+    let mut committed = HashMap::new();
+    let mut marginal = Vec::with_capacity(mappings.len());
+    for mapping in mappings {
+        let (increase, increments) =
+            marginal_cost(g, h, &committed, mapping, MergeSemantics::Shared);
+        marginal.push(increase);
+        for (edge, increment) in increments {
+            *committed.entry(edge).or_insert(0) += increment;
+        }
+    }
+
+    CostMatrix {
+        per_mapping,
+        per_edge,
+        marginal,
+    }
+}
+
+/// The `g.num_vertices() x h.num_vertices()` unary cost of assigning each G
+/// vertex to each H vertex in isolation, for use as the cost matrix of a
+/// bipartite-matching approximation (see `approx::hungarian_matching_greedy`).
+/// `cost[i][j]` is the degree mismatch
+/// between G vertex `i` and H vertex `j` -- `|out_i - out_j| + |in_i - in_j|`
+/// -- a cheap proxy for how many of `i`'s edges `j` could plausibly satisfy,
+/// without looking at where `i`'s neighbors would have to land. A minimum-cost
+/// perfect matching over this matrix is therefore only an approximation to
+/// the true per-mapping cost (`calculate_total_cost` of `required_edges`),
+/// which depends on the *joint* assignment of every vertex, not just `i` and
+/// `j` in isolation; an all-zero matrix does coincide with a true zero-cost
+/// mapping, since every vertex's degree requirement is already met by its
+/// assigned target.
+pub fn mapping_cost_matrix(g: &Graph, h: &Graph) -> Vec<Vec<usize>> {
+    (0..g.num_vertices())
+        .map(|i| {
+            let g_out = g.weighted_out_degree(i);
+            let g_in = g.weighted_in_degree(i);
+            (0..h.num_vertices())
+                .map(|j| {
+                    let h_out = h.weighted_out_degree(j);
+                    let h_in = h.weighted_in_degree(j);
+                    g_out.abs_diff(h_out) + g_in.abs_diff(h_in)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// A feasible (not necessarily optimal) set of `k` mappings and the edge map
+/// and total cost they imply. Returned by `feasible_under_budget`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solution {
+    pub cost: usize,
+    pub edge_map: HashMap<(usize, usize), usize>,
+    pub mappings: Vec<Mapping>,
+}
+
+impl Solution {
+    /// How much this solution's mappings share added edges with each other;
+    /// see [`sharing_stats`].
+    pub fn sharing_stats(&self, g: &Graph, h: &Graph) -> SharingStats {
+        sharing_stats(g, h, &self.mappings)
+    }
+}
+
+/// Find *some* set of `k` mappings from `g` into `h` whose total cost is at
+/// most `budget`, or `None` if no such set exists. Unlike the exact solver's
+/// full combination search, this returns the first feasible combination it
+/// finds rather than the optimum, pruning any partial combination as soon as
+/// its cost exceeds `budget` — answering "can we fit in budget B?" without
+/// paying for "what's the true minimum?".
+pub fn feasible_under_budget(g: &Graph, h: &Graph, k: usize, budget: usize) -> Option<Solution> {
+    let all_mappings = crate::mapping::find_all_mappings(g, h);
+    if all_mappings.len() < k {
+        return None;
+    }
+
+    let mut accumulator = EdgeMapAccumulator::new();
+    let mut chosen = Vec::with_capacity(k);
+    search_within_budget(
+        &all_mappings,
+        g,
+        h,
+        k,
+        0,
+        budget,
+        &mut accumulator,
+        &mut chosen,
+    )
+}
+
+/// Recursively extend `chosen` (indices into `all_mappings`, strictly
+/// increasing) with mappings starting at `next_idx`, backtracking out of any
+/// branch whose accumulated cost already exceeds `budget`. Returns as soon as
+/// `chosen` reaches length `k`, instead of exploring every combination like
+/// `exact_solver`'s `search_combinations`.
+#[allow(clippy::too_many_arguments)]
+fn search_within_budget(
+    all_mappings: &[Mapping],
+    g: &Graph,
+    h: &Graph,
+    k: usize,
+    next_idx: usize,
+    budget: usize,
+    accumulator: &mut EdgeMapAccumulator,
+    chosen: &mut Vec<usize>,
+) -> Option<Solution> {
+    if chosen.len() == k {
+        return Some(Solution {
+            cost: accumulator.current_cost(),
+            edge_map: accumulator.edge_map(),
+            mappings: chosen.iter().map(|&i| all_mappings[i].clone()).collect(),
+        });
+    }
+
+    let remaining = k - chosen.len();
+    let last_idx = all_mappings.len() - remaining;
+
+    for idx in next_idx..=last_idx {
+        accumulator.add_mapping(g, h, &all_mappings[idx]);
+        if accumulator.current_cost() > budget {
+            accumulator.remove_mapping(g, h, &all_mappings[idx]);
+            continue;
+        }
+
+        chosen.push(idx);
+        if let Some(solution) =
+            search_within_budget(all_mappings, g, h, k, idx + 1, budget, accumulator, chosen)
+        {
+            return Some(solution);
+        }
+        chosen.pop();
+        accumulator.remove_mapping(g, h, &all_mappings[idx]);
+    }
+
+    None
+}
+
+/// The `(edge, needed)` pairs `mapping` requires beyond what `h` already
+/// provides, skipping edges that need nothing (mirrors the inner loop of
+/// `calculate_edge_map`).
+fn required_edges<'a>(
+    g: &'a Graph,
+    h: &'a Graph,
+    mapping: &'a [usize],
+) -> impl Iterator<Item = ((usize, usize), usize)> + 'a {
+    (0..g.num_vertices()).flat_map(move |u| {
+        (0..g.num_vertices()).filter_map(move |v| {
+            let g_edge_count = g.get_edge(u, v);
+            if g_edge_count == 0 {
+                return None;
+            }
+            let x = mapping[u];
+            let y = mapping[v];
+            let needed = g_edge_count.saturating_sub(h.get_edge(x, y));
+            (needed > 0).then_some(((x, y), needed))
+        })
+    })
+}
@@ -0,0 +1,819 @@
+use crate::cost;
+use crate::utils::{factorial, permutation_count};
+use crate::{Graph, Mapping};
+use std::collections::{BinaryHeap, HashSet};
+
+/// A deduplicated collection of mappings, for code that needs to track which
+/// mappings have already been used and reject repeats by construction instead
+/// of managing a bare `HashSet<Vec<usize>>` by hand.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MappingSet(HashSet<Mapping>);
+
+impl MappingSet {
+    /// Insert `m`, returning `true` if it was not already present.
+    pub fn insert(&mut self, m: Mapping) -> bool {
+        self.0.insert(m)
+    }
+
+    pub fn contains(&self, m: &Mapping) -> bool {
+        self.0.contains(m)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Mappings in `self` that are not in `other`.
+    pub fn difference(&self, other: &MappingSet) -> MappingSet {
+        MappingSet(self.0.difference(&other.0).cloned().collect())
+    }
+
+    pub fn to_vec(&self) -> Vec<Mapping> {
+        self.0.iter().cloned().collect()
+    }
+}
+
+/// Convenience constructor: every injective mapping from `g` into `h`,
+/// deduplicated into a `MappingSet`. Equivalent to collecting
+/// `find_all_mappings(g, h)` into a `MappingSet`, since `find_all_mappings`
+/// never produces duplicates itself.
+pub fn mapping_set_from_graph_pair(g: &Graph, h: &Graph) -> MappingSet {
+    MappingSet(find_all_mappings(g, h).into_iter().collect())
+}
+
+/// Find all possible injective mappings from pattern graph G to host graph H.
+///
+/// This only enumerates injective vertex assignments and never inspects
+/// either graph's edges (self-loops included), so a mapping is returned
+/// regardless of whether it is actually edge-compatible; edge costs,
+/// including any required for self-loops, are evaluated separately by
+/// `cost::calculate_edge_map`. See `homomorphism_exists`'s doc comment for
+/// why that stays a standalone check instead of pre-filtering here.
+pub fn find_all_mappings(g: &Graph, h: &Graph) -> Vec<Mapping> {
+    let n_g = g.num_vertices();
+    let n_h = h.num_vertices();
+
+    if n_g > n_h {
+        return vec![]; // No valid mappings possible
+    }
+
+    let mut all_mappings = Vec::new();
+    let mut current_mapping = vec![0; n_g];
+    let mut used_vh = vec![false; n_h];
+
+    backtrack(
+        0,
+        n_g,
+        n_h,
+        &mut current_mapping,
+        &mut used_vh,
+        &mut all_mappings,
+    );
+
+    all_mappings
+}
+
+/// Like `find_all_mappings`, but calls `progress(n)` every time a new
+/// complete mapping brings the running total to `n`. Kept as a separate
+/// recursion from `find_all_mappings` (rather than that function always
+/// invoking a no-op callback) so the hot path pays nothing for a feature it's
+/// not using -- a monomorphized no-op call per leaf is still measurable
+/// overhead on instances with enumerations in the billions.
+pub fn find_all_mappings_with_progress<F: Fn(usize)>(
+    g: &Graph,
+    h: &Graph,
+    progress: F,
+) -> Vec<Mapping> {
+    let n_g = g.num_vertices();
+    let n_h = h.num_vertices();
+
+    if n_g > n_h {
+        return vec![];
+    }
+
+    let mut all_mappings = Vec::new();
+    let mut current_mapping = vec![0; n_g];
+    let mut used_vh = vec![false; n_h];
+
+    backtrack_with_progress(
+        0,
+        n_g,
+        n_h,
+        &mut current_mapping,
+        &mut used_vh,
+        &mut all_mappings,
+        &progress,
+    );
+
+    all_mappings
+}
+
+/// Like `find_all_mappings`, but stops as soon as `max` mappings have been
+/// found instead of enumerating the full pool. Intended for callers that only
+/// need a bounded sample or a cheap feasibility check (e.g. "are there at
+/// least `k` mappings at all?") on an instance too large to enumerate fully.
+pub fn find_all_mappings_limited(g: &Graph, h: &Graph, max: usize) -> Vec<Mapping> {
+    let n_g = g.num_vertices();
+    let n_h = h.num_vertices();
+
+    if n_g > n_h || max == 0 {
+        return vec![];
+    }
+
+    let mut all_mappings = Vec::new();
+    let mut current_mapping = vec![0; n_g];
+    let mut used_vh = vec![false; n_h];
+
+    backtrack_limited(
+        0,
+        n_g,
+        n_h,
+        max,
+        &mut current_mapping,
+        &mut used_vh,
+        &mut all_mappings,
+    );
+
+    all_mappings
+}
+
+/// Symmetry-broken enumeration for undirected `g`/`h` (see
+/// `Graph::as_undirected`): only mappings where G's vertex 0 lands on the
+/// lexicographically smallest H vertex the mapping uses survive, since for an
+/// undirected pattern every other assignment of the same H vertex set is an
+/// equivalent relabeling. Cuts the enumeration roughly in half (exactly by a
+/// factor of `g.num_vertices()` per selected vertex set, in fact).
+pub fn find_all_mappings_undirected(g: &Graph, h: &Graph) -> Vec<Mapping> {
+    find_all_mappings(g, h)
+        .into_iter()
+        .filter(|mapping| mapping.first().copied() == mapping.iter().copied().min())
+        .collect()
+}
+
+/// Whether a graph homomorphism from `g` to `h` exists: a (not necessarily
+/// injective) vertex map `φ` such that `h.get_edge(φ(u), φ(v)) >= g.get_edge(u, v)`
+/// for every `u, v`. Strictly weaker than subgraph isomorphism -- every
+/// injective mapping that is also edge-preserving is a homomorphism, so if
+/// none exists here, no *edge-preserving* injective mapping can exist
+/// either, but the converse doesn't hold.
+///
+/// Modeled as a CSP: each `g` vertex has a domain of candidate `h` vertices,
+/// starting as all of them, and AC-3 enforces arc consistency -- for every
+/// `g` edge `(u, v)` with weight `w`, both directions of the constraint are
+/// enforced: every `x` remaining in `u`'s domain needs some `y` in `v`'s
+/// domain with `h.get_edge(x, y) >= w`, or `x` is pruned, *and* every `y`
+/// remaining in `v`'s domain needs some `x` in `u`'s domain satisfying the
+/// same check, or `y` is pruned. Run to a fixed point, then `false` if any
+/// domain emptied out. This only rules out non-homomorphisms; a `true`
+/// result is a necessary but not sufficient condition in general, since AC-3
+/// doesn't check that a single assignment can satisfy every vertex's domain
+/// simultaneously -- though for a tree-structured `g`, whose constraint
+/// graph has no cycles, arc consistency alone *is* a complete decision
+/// procedure (Freuder 1982): a solution can always be read off a fully
+/// arc-consistent, non-empty set of domains one tree edge at a time.
+///
+/// Deliberately *not* wired in as a pre-filter ahead of `find_all_mappings`:
+/// this crate's mappings are extension candidates, not isomorphism
+/// witnesses, and `h` missing an edge `g` needs is exactly what
+/// `cost::calculate_edge_map` prices as extension cost rather than a reason
+/// to discard the mapping. A `false` from this function only proves that no
+/// mapping achieves *zero* extension cost -- useful on its own for a caller
+/// that only cares about exact-isomorphism feasibility, but not a valid
+/// short-circuit for `find_all_mappings`, whose whole job is enumerating
+/// mappings that may need edges added.
+pub fn homomorphism_exists(g: &Graph, h: &Graph) -> bool {
+    let n_g = g.num_vertices();
+    let n_h = h.num_vertices();
+
+    // Each g-edge (u, v, w) contributes two arcs: pruning u's domain against
+    // v's (u plays the source role), and pruning v's domain against u's (v
+    // plays the target role). An arc is (prune, other, required, prune_is_source).
+    let edges: Vec<(usize, usize, usize)> = (0..n_g)
+        .flat_map(|u| (0..n_g).map(move |v| (u, v)))
+        .filter_map(|(u, v)| {
+            let w = g.get_edge(u, v);
+            (w > 0).then_some((u, v, w))
+        })
+        .collect();
+    let arcs: Vec<(usize, usize, usize, bool)> = edges
+        .iter()
+        .flat_map(|&(u, v, w)| [(u, v, w, true), (v, u, w, false)])
+        .collect();
+
+    let mut domains: Vec<HashSet<usize>> = vec![(0..n_h).collect(); n_g];
+    let mut worklist: std::collections::VecDeque<(usize, usize, usize, bool)> =
+        arcs.iter().copied().collect();
+
+    while let Some((prune, other, required, prune_is_source)) = worklist.pop_front() {
+        if !revise(prune, other, required, prune_is_source, h, &mut domains) {
+            continue;
+        }
+        if domains[prune].is_empty() {
+            return false;
+        }
+        for &(p, o, w, is_source) in &arcs {
+            if o == prune && p != other {
+                worklist.push_back((p, o, w, is_source));
+            }
+        }
+    }
+
+    true
+}
+
+/// AC-3's `Revise` for the constraint "`h.get_edge(x, y) >= required`"
+/// imposed by a `g`-edge, where `x` always plays the source role and `y` the
+/// target role: drop every value from `domains[prune]` that has no
+/// supporting value in `domains[other]`, returning whether anything was
+/// dropped. `prune_is_source` says which role `domains[prune]` plays, since
+/// the check is directional (`h.get_edge` isn't assumed symmetric).
+fn revise(
+    prune: usize,
+    other: usize,
+    required: usize,
+    prune_is_source: bool,
+    h: &Graph,
+    domains: &mut [HashSet<usize>],
+) -> bool {
+    let supported: HashSet<usize> = domains[prune]
+        .iter()
+        .copied()
+        .filter(|&p| {
+            domains[other].iter().any(|&o| {
+                let (x, y) = if prune_is_source { (p, o) } else { (o, p) };
+                h.get_edge(x, y) >= required
+            })
+        })
+        .collect();
+
+    if supported.len() == domains[prune].len() {
+        return false;
+    }
+    domains[prune] = supported;
+    true
+}
+
+/// Compatibility matrix for `count_satisfying_mappings`: `compat[u][v]` is
+/// `true` if pattern vertex `u` is allowed to map to host vertex `v`. Pruned
+/// by weighted out-degree: `v` can't host `u` if `v` doesn't have at least as
+/// much outgoing edge weight as `u` demands, since no embedding of `u` onto
+/// `v` could supply edges `v` doesn't have. This only tightens the count
+/// returned by `count_satisfying_mappings`, it never changes which mappings
+/// `find_all_mappings` itself considers valid.
+fn compatibility_matrix(g: &Graph, h: &Graph) -> Vec<Vec<bool>> {
+    (0..g.num_vertices())
+        .map(|u| {
+            let g_out = g.weighted_out_degree(u);
+            (0..h.num_vertices())
+                .map(|v| h.weighted_out_degree(v) >= g_out)
+                .collect()
+        })
+        .collect()
+}
+
+/// Permanent of a square 0/1 matrix via Ryser's inclusion-exclusion formula,
+/// O(2^n · n^2). Only practical for n up to roughly 25.
+fn permanent_ryser(matrix: &[Vec<bool>]) -> u128 {
+    let n = matrix.len();
+    if n == 0 {
+        return 1;
+    }
+
+    let mut total: i128 = 0;
+    for subset in 0u32..(1u32 << n) {
+        let mut term: i128 = 1;
+        for row in matrix.iter() {
+            let row_sum = (0..n).filter(|&j| (subset >> j) & 1 == 1 && row[j]).count() as i128;
+            term *= row_sum;
+            if term == 0 {
+                break;
+            }
+        }
+        total += if (n as u32 - subset.count_ones()).is_multiple_of(2) {
+            term
+        } else {
+            -term
+        };
+    }
+    total.unsigned_abs()
+}
+
+/// Count injective mappings from `g` into `h` that satisfy [`compatibility_matrix`],
+/// without enumerating them. For `h.num_vertices() <= 25` this uses Ryser's
+/// formula for the permanent of the degree-compatibility matrix (padding it to
+/// a square matrix with "any vertex will do" rows), completing in O(2^n · n)
+/// time. Larger hosts fall back to filtering an explicit enumeration.
+pub fn count_satisfying_mappings(g: &Graph, h: &Graph) -> usize {
+    let n_g = g.num_vertices();
+    let n_h = h.num_vertices();
+
+    if n_g > n_h {
+        return 0;
+    }
+
+    let compat = compatibility_matrix(g, h);
+
+    if n_h <= 25 {
+        let mut square = vec![vec![true; n_h]; n_h];
+        for (row, compat_row) in square.iter_mut().zip(compat.iter()) {
+            *row = compat_row.clone();
+        }
+        let permanent = permanent_ryser(&square);
+        let divisor = factorial(n_h - n_g);
+        (permanent / divisor) as usize
+    } else {
+        find_all_mappings(g, h)
+            .into_iter()
+            .filter(|m| m.iter().enumerate().all(|(u, &v)| compat[u][v]))
+            .count()
+    }
+}
+
+/// Decode the `index`-th injective mapping (0-based) from `g` into `h` in the
+/// same lexicographic order produced by `find_all_mappings`'s backtracking,
+/// without generating the mappings before it. Each position picks the
+/// `digit`-th smallest remaining host vertex, where `digit` is the
+/// appropriate place in the mixed-radix encoding of `index`.
+fn unrank_mapping(n_g: usize, n_h: usize, mut index: u128) -> Mapping {
+    let mut available: Vec<usize> = (0..n_h).collect();
+    let mut mapping = Vec::with_capacity(n_g);
+    for i in 0..n_g {
+        let remaining_g = n_g - i - 1;
+        let remaining_h = available.len() - 1;
+        let block_size = permutation_count(remaining_h, remaining_g).max(1);
+        let digit = (index / block_size) as usize;
+        index %= block_size;
+        mapping.push(available.remove(digit));
+    }
+    mapping
+}
+
+/// Enumerate the half-open range `[start, start+count)` of injective mappings
+/// from `g` into `h`, in the same order as `find_all_mappings`, without
+/// materializing anything before `start`. Intended for batch pipelines that
+/// must resume enumeration across process invocations using a serializable
+/// `(start, count)` cursor: adjacent ranges partition the full enumeration
+/// exactly, with no gaps or overlaps. Since it only sees a slice of the
+/// candidate pool, a solver built on top of this yields a possibly suboptimal
+/// per-slice answer; slices must be merged afterwards to recover the optimum.
+pub fn enumerate_range(g: &Graph, h: &Graph, start: u128, count: u64) -> Vec<Mapping> {
+    let n_g = g.num_vertices();
+    let n_h = h.num_vertices();
+    if n_g > n_h {
+        return vec![];
+    }
+
+    let total = permutation_count(n_h, n_g);
+    if start >= total {
+        return vec![];
+    }
+
+    let end = (start + count as u128).min(total);
+    (start..end).map(|i| unrank_mapping(n_g, n_h, i)).collect()
+}
+
+/// The `k` individually cheapest injective mappings from `g` into `h`, paired
+/// with their own standalone cost (`cost::calculate_total_cost` of
+/// `cost::calculate_edge_map` on that mapping alone), cheapest first. A
+/// heuristic alternative to `approx::marginal_cost_greedy`'s joint-cost
+/// selection: faster since it never re-evaluates a candidate against the
+/// mappings already chosen, but blind to edge sharing between the mappings it
+/// picks. Returns fewer than `k` entries only if `g` has fewer than `k` total
+/// mappings into `h`.
+///
+/// Holds at most `k` mappings in memory at once via a max-heap keyed by cost:
+/// every fully-built mapping is compared against the heap's current worst
+/// entry and only kept if it's cheaper, so the full mapping pool is never
+/// materialized the way `marginal_cost_greedy`'s caller-supplied
+/// `all_mappings` is.
+pub fn find_k_cheapest_mappings(g: &Graph, h: &Graph, k: usize) -> Vec<(usize, Mapping)> {
+    let n_g = g.num_vertices();
+    let n_h = h.num_vertices();
+    if n_g > n_h || k == 0 {
+        return vec![];
+    }
+
+    let mut heap: BinaryHeap<(usize, Mapping)> = BinaryHeap::with_capacity(k + 1);
+    let mut current_mapping = vec![0; n_g];
+    let mut used_vh = vec![false; n_h];
+
+    collect_k_cheapest(
+        0,
+        n_g,
+        n_h,
+        g,
+        h,
+        k,
+        &mut current_mapping,
+        &mut used_vh,
+        &mut heap,
+    );
+
+    heap.into_sorted_vec()
+}
+
+/// Recursive backtracking behind [`find_k_cheapest_mappings`]: mirrors
+/// [`backtrack`]'s traversal, but costs each completed mapping on the spot
+/// and folds it into a bounded max-heap instead of collecting every mapping
+/// into a `Vec`.
+#[allow(clippy::too_many_arguments)]
+fn collect_k_cheapest(
+    vertex_idx: usize,
+    n_g: usize,
+    n_h: usize,
+    g: &Graph,
+    h: &Graph,
+    k: usize,
+    current_mapping: &mut Mapping,
+    used_vh: &mut Vec<bool>,
+    heap: &mut BinaryHeap<(usize, Mapping)>,
+) {
+    if vertex_idx == n_g {
+        let edge_map = cost::calculate_edge_map(g, h, std::slice::from_ref(current_mapping));
+        let mapping_cost = cost::calculate_total_cost(&edge_map);
+
+        if heap.len() < k {
+            heap.push((mapping_cost, current_mapping.clone()));
+        } else if heap.peek().is_some_and(|&(worst_cost, _)| mapping_cost < worst_cost) {
+            heap.pop();
+            heap.push((mapping_cost, current_mapping.clone()));
+        }
+        return;
+    }
+
+    for v in 0..n_h {
+        if !used_vh[v] {
+            current_mapping[vertex_idx] = v;
+            used_vh[v] = true;
+            collect_k_cheapest(
+                vertex_idx + 1,
+                n_g,
+                n_h,
+                g,
+                h,
+                k,
+                current_mapping,
+                used_vh,
+                heap,
+            );
+            used_vh[v] = false;
+        }
+    }
+}
+
+/// `k` injective mappings from `g` into `h`, greedily maximizing the minimum
+/// pairwise Hamming distance between their image vectors instead of
+/// optimizing edge cost at all: the first pick is the cheapest standalone
+/// mapping (mirroring `approx::marginal_cost_greedy`'s seed step), and every
+/// subsequent pick is whichever remaining mapping is farthest -- by minimum
+/// Hamming distance -- from every mapping already chosen. Useful when a
+/// caller wants its k embeddings spread across different parts of `h` rather
+/// than clustered around the same cheap corner. Returns fewer than `k`
+/// entries only if `g` has fewer than `k` total mappings into `h`.
+pub fn find_k_diverse_mappings(g: &Graph, h: &Graph, k: usize) -> Vec<Mapping> {
+    let all_mappings = find_all_mappings(g, h);
+    if all_mappings.len() < k || k == 0 {
+        return vec![];
+    }
+
+    let mut chosen: Vec<usize> = Vec::with_capacity(k);
+
+    let first = (0..all_mappings.len())
+        .min_by_key(|&i| {
+            cost::calculate_total_cost(&cost::calculate_edge_map(
+                g,
+                h,
+                std::slice::from_ref(&all_mappings[i]),
+            ))
+        })
+        .expect("all_mappings is non-empty since its length is at least k >= 1");
+    chosen.push(first);
+
+    while chosen.len() < k {
+        let next = (0..all_mappings.len())
+            .filter(|i| !chosen.contains(i))
+            .max_by_key(|&i| {
+                chosen
+                    .iter()
+                    .map(|&j| hamming_distance(&all_mappings[i], &all_mappings[j]))
+                    .min()
+                    .unwrap_or(0)
+            })
+            .expect("chosen.len() < k <= all_mappings.len(), so a candidate remains");
+        chosen.push(next);
+    }
+
+    chosen.into_iter().map(|i| all_mappings[i].clone()).collect()
+}
+
+/// Number of positions at which two equal-length mappings disagree.
+fn hamming_distance(a: &Mapping, b: &Mapping) -> usize {
+    a.iter().zip(b.iter()).filter(|(x, y)| x != y).count()
+}
+
+/// Deterministic local search over a single mapping's image assignment
+/// against `h_prime`: in each pass, try swapping the images of every pair of
+/// G vertices and reassigning each G vertex to every unused H vertex,
+/// applying the first move found to strictly lower the mapping's cost and
+/// restarting the pass. Stops at a 2-opt local optimum, i.e. once a full pass
+/// finds no improving move. Cheaper than [`crate::approx::refine_mapping_sa`]
+/// (no temperature schedule, no chance of accepting a worsening move to
+/// escape a local optimum) but deterministic and needs no `Rng`.
+///
+/// Each candidate move is costed off [`touching_cost`] before and after
+/// rather than recomputing the mapping's full cost against `h_prime`, since
+/// only edges incident to the vertices involved in a swap or reassignment can
+/// change.
+pub fn local_search_2opt(g: &Graph, h_prime: &Graph, mapping: &Mapping) -> (Mapping, usize) {
+    let n_g = g.num_vertices();
+    let n_h = h_prime.num_vertices();
+
+    let mut current = mapping.clone();
+    let mut current_cost = cost::calculate_total_cost(&cost::calculate_edge_map(
+        g,
+        h_prime,
+        std::slice::from_ref(&current),
+    ));
+
+    loop {
+        let mut improved = false;
+
+        'pass: for a in 0..n_g {
+            for b in (a + 1)..n_g {
+                let before = touching_cost(g, h_prime, &current, &[a, b]);
+                current.swap(a, b);
+                let after = touching_cost(g, h_prime, &current, &[a, b]);
+                if after < before {
+                    current_cost -= before - after;
+                    improved = true;
+                    break 'pass;
+                }
+                current.swap(a, b);
+            }
+
+            let used: HashSet<usize> = current.iter().copied().collect();
+            for v in 0..n_h {
+                if used.contains(&v) {
+                    continue;
+                }
+                let before = touching_cost(g, h_prime, &current, &[a]);
+                let original = current[a];
+                current[a] = v;
+                let after = touching_cost(g, h_prime, &current, &[a]);
+                if after < before {
+                    current_cost -= before - after;
+                    improved = true;
+                    break 'pass;
+                }
+                current[a] = original;
+            }
+        }
+
+        if !improved {
+            break;
+        }
+    }
+
+    (current, current_cost)
+}
+
+/// Sum of `g`'s edge costs against `h` under `mapping`, over every edge
+/// incident to a vertex in `vertices`, each edge counted exactly once.
+/// `O(|vertices| * n)` rather than the `O(n^2)` a full mapping-cost recompute
+/// would need -- the incremental building block [`local_search_2opt`] costs
+/// its candidate moves with, since only edges touching the changed vertices
+/// can differ before and after a swap or reassignment.
+fn touching_cost(g: &Graph, h: &Graph, mapping: &Mapping, vertices: &[usize]) -> usize {
+    let n = g.num_vertices();
+    let edge_cost = |u: usize, v: usize| {
+        let needed = g.get_edge(u, v);
+        if needed == 0 {
+            0
+        } else {
+            needed.saturating_sub(h.get_edge(mapping[u], mapping[v]))
+        }
+    };
+
+    let mut total = 0;
+    for &u in vertices {
+        for v in 0..n {
+            total += edge_cost(u, v);
+        }
+    }
+    for &v in vertices {
+        for u in 0..n {
+            if !vertices.contains(&u) {
+                total += edge_cost(u, v);
+            }
+        }
+    }
+    total
+}
+
+/// Recursive backtracking to enumerate all injective mappings
+fn backtrack(
+    vertex_idx: usize,
+    n_g: usize,
+    n_h: usize,
+    current_mapping: &mut Vec<usize>,
+    used_vh: &mut Vec<bool>,
+    all_mappings: &mut Vec<Mapping>,
+) {
+    if vertex_idx == n_g {
+        // Complete mapping found
+        all_mappings.push(current_mapping.clone());
+        return;
+    }
+
+    // Try mapping current vertex to each unused vertex in H
+    for v in 0..n_h {
+        if !used_vh[v] {
+            current_mapping[vertex_idx] = v;
+            used_vh[v] = true;
+            backtrack(
+                vertex_idx + 1,
+                n_g,
+                n_h,
+                current_mapping,
+                used_vh,
+                all_mappings,
+            );
+            used_vh[v] = false;
+        }
+    }
+}
+
+/// Same recursion as `backtrack`, but calls `progress` after each complete
+/// mapping is pushed.
+fn backtrack_with_progress<F: Fn(usize)>(
+    vertex_idx: usize,
+    n_g: usize,
+    n_h: usize,
+    current_mapping: &mut Vec<usize>,
+    used_vh: &mut Vec<bool>,
+    all_mappings: &mut Vec<Mapping>,
+    progress: &F,
+) {
+    if vertex_idx == n_g {
+        all_mappings.push(current_mapping.clone());
+        progress(all_mappings.len());
+        return;
+    }
+
+    for v in 0..n_h {
+        if !used_vh[v] {
+            current_mapping[vertex_idx] = v;
+            used_vh[v] = true;
+            backtrack_with_progress(
+                vertex_idx + 1,
+                n_g,
+                n_h,
+                current_mapping,
+                used_vh,
+                all_mappings,
+                progress,
+            );
+            used_vh[v] = false;
+        }
+    }
+}
+
+/// Same recursion as `backtrack`, but stops (at every recursion level, not
+/// just between top-level branches) as soon as `all_mappings.len()` reaches
+/// `max`.
+fn backtrack_limited(
+    vertex_idx: usize,
+    n_g: usize,
+    n_h: usize,
+    max: usize,
+    current_mapping: &mut Vec<usize>,
+    used_vh: &mut Vec<bool>,
+    all_mappings: &mut Vec<Mapping>,
+) {
+    if all_mappings.len() >= max {
+        return;
+    }
+
+    if vertex_idx == n_g {
+        all_mappings.push(current_mapping.clone());
+        return;
+    }
+
+    for v in 0..n_h {
+        if all_mappings.len() >= max {
+            return;
+        }
+        if !used_vh[v] {
+            current_mapping[vertex_idx] = v;
+            used_vh[v] = true;
+            backtrack_limited(
+                vertex_idx + 1,
+                n_g,
+                n_h,
+                max,
+                current_mapping,
+                used_vh,
+                all_mappings,
+            );
+            used_vh[v] = false;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_a_tree_homomorphs_into_a_single_undirected_edge() {
+        // Any tree is bipartite (chromatic number <= 2), and a graph
+        // homomorphs into K2 exactly when it's 2-colorable, so a tree should
+        // always find a homomorphism into a single undirected edge.
+        let tree = Graph::path_graph(5).as_undirected();
+        let k2 = Graph::path_graph(2).as_undirected();
+
+        assert!(homomorphism_exists(&tree, &k2));
+    }
+
+    #[test]
+    fn test_a_tree_does_not_homomorph_when_the_host_has_no_edges_at_all() {
+        // A tree's constraint graph has no cycles, so arc consistency alone
+        // is a complete decision procedure for it (unlike for a pattern with
+        // cycles -- see the false-positive test below): every domain
+        // emptying out here really does mean no homomorphism exists.
+        let tree = Graph::path_graph(3);
+        let edgeless_host = Graph::new(3);
+
+        assert!(!homomorphism_exists(&tree, &edgeless_host));
+    }
+
+    #[test]
+    fn test_a_tree_does_not_homomorph_when_an_edges_multiplicity_cant_be_matched() {
+        let tree = Graph::from_adjacency_matrix(vec![vec![0, 2], vec![0, 0]]); // one edge, weight 2
+        let host = Graph::path_graph(2); // one edge, weight 1 -- too weak to host it
+
+        assert!(!homomorphism_exists(&tree, &host));
+    }
+
+    #[test]
+    fn test_homomorphism_exists_can_report_a_false_positive_on_a_non_tree_pattern() {
+        // AC-3 only enforces arc (pairwise) consistency, not global
+        // consistency, so it's a necessary but not sufficient condition once
+        // the pattern's constraint graph has a cycle (see this function's
+        // doc comment). A triangle has chromatic number 3 and truly has no
+        // homomorphism into a single edge, but every pairwise constraint
+        // along the triangle is individually satisfiable by K2's two
+        // vertices, so AC-3 can't detect the global conflict.
+        let triangle = Graph::cycle_graph(3).as_undirected();
+        let k2 = Graph::path_graph(2).as_undirected();
+
+        assert!(homomorphism_exists(&triangle, &k2));
+    }
+
+    #[test]
+    fn test_homomorphism_exists_is_true_whenever_an_edge_preserving_mapping_survives() {
+        let g = Graph::path_graph(3);
+        let h = Graph::cycle_graph(3);
+
+        assert!(homomorphism_exists(&g, &h));
+        assert!(find_all_mappings(&g, &h)
+            .iter()
+            .any(|m| (0..g.num_vertices() - 1)
+                .all(|u| h.get_edge(m[u], m[u + 1]) >= g.get_edge(u, u + 1))));
+    }
+
+    #[test]
+    fn test_homomorphism_exists_does_not_filter_out_edge_incompatible_mappings_from_find_all_mappings() {
+        // g needs an edge that h, being edgeless, can never supply -- no
+        // homomorphism exists -- but find_all_mappings must still return
+        // every injective mapping, since the crate's job is pricing the
+        // edges that would need to be added, not rejecting the mapping.
+        let g = Graph::path_graph(2);
+        let h = Graph::new(3);
+
+        assert!(!homomorphism_exists(&g, &h));
+        assert_eq!(find_all_mappings(&g, &h).len(), 6); // P(3, 2) = 6
+    }
+
+    #[test]
+    fn test_homomorphism_exists_prunes_both_arc_directions_on_a_tree_pattern() {
+        // Regression test: AC-3 that only ever revises the source side of
+        // each directed g-edge is not full arc consistency, and can miss
+        // this even for a tree pattern (path 0-2-3-1 with edge weights 2 and
+        // 3). Brute-force over all 3^4 candidate maps confirms no
+        // homomorphism into h exists.
+        let g = Graph::from_adjacency_matrix(vec![
+            vec![0, 0, 2, 0],
+            vec![0, 0, 0, 3],
+            vec![0, 0, 0, 0],
+            vec![0, 0, 1, 0],
+        ]);
+        let h = Graph::from_adjacency_matrix(vec![vec![0, 1, 0], vec![3, 0, 0], vec![2, 1, 0]]);
+
+        assert!(!homomorphism_exists(&g, &h));
+    }
+}
@@ -0,0 +1,19 @@
+//! Plain-text edge-list output: a sparse alternative to the dense n×n
+//! adjacency-matrix report the solver binaries print by default, for tools
+//! (`awk`, `pandas`, ...) that would rather read a 3-column TSV.
+
+use crate::EdgeMap;
+use std::io::{self, Write};
+
+/// Write `edge_map` as a `u\tv\tweight` TSV, one line per entry with
+/// `weight > 0`, sorted by `(u, v)` for deterministic output regardless of
+/// the map's (unordered) iteration order.
+pub fn write_edge_list<W: Write>(w: &mut W, edge_map: &EdgeMap) -> io::Result<()> {
+    let mut entries: Vec<(&(usize, usize), &usize)> =
+        edge_map.iter().filter(|(_, &weight)| weight > 0).collect();
+    entries.sort_by_key(|&(&(u, v), _)| (u, v));
+    for (&(u, v), &weight) in entries {
+        writeln!(w, "{}\t{}\t{}", u, v, weight)?;
+    }
+    Ok(())
+}
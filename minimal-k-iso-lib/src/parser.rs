@@ -0,0 +1,663 @@
+use crate::{Graph, GraphOptions};
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    character::complete::{digit1, space0, space1},
+    combinator::{map_res, opt},
+    multi::{many1, separated_list1},
+    sequence::{preceded, terminated},
+    IResult,
+};
+use std::fmt;
+use std::io::Read;
+use std::path::PathBuf;
+use std::sync::mpsc;
+use std::time::Duration;
+
+/// The specific reason a graph description failed to parse.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    UnexpectedEof,
+    InvalidInteger,
+    WrongRowLength(usize, usize),
+    InvalidVertexCount,
+    /// A Matrix Market file (see `from_matrix_market`) is missing its
+    /// `%%MatrixMarket` header, has the wrong number of fields on its size or
+    /// entry lines, or doesn't have exactly 2 blocks where 2 are expected.
+    InvalidHeader,
+}
+
+/// A parse failure, with the 1-based input line it occurred on.
+///
+/// The underlying `nom` error is consumed while building this (nom's error
+/// type borrows from the input, which doesn't outlive the parse), so
+/// `source()` always returns `None`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub message: String,
+    pub kind: ParseErrorKind,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        None
+    }
+}
+
+/// Count how many newlines precede the unparsed `remaining` tail within
+/// `original`, giving the 1-based line number where parsing stopped.
+fn line_number_at(original: &str, remaining: &str) -> usize {
+    let consumed = original.len() - remaining.len();
+    1 + original[..consumed].matches('\n').count()
+}
+
+/// Turn a nom parse failure into a `ParseError`, using `original` to compute
+/// the line number and inspecting the row-length mismatch we raise ourselves
+/// (see `parse_adjacency_matrix`) to pick a precise `ParseErrorKind`.
+fn to_parse_error(original: &str, err: nom::Err<nom::error::Error<&str>>) -> ParseError {
+    match err {
+        nom::Err::Incomplete(_) => ParseError {
+            line: line_number_at(original, ""),
+            message: "unexpected end of input".to_string(),
+            kind: ParseErrorKind::UnexpectedEof,
+        },
+        nom::Err::Error(e) | nom::Err::Failure(e) => {
+            let line = line_number_at(original, e.input);
+            match e.code {
+                nom::error::ErrorKind::LengthValue => ParseError {
+                    line,
+                    message: format!("row {} has the wrong number of elements", line),
+                    kind: ParseErrorKind::WrongRowLength(0, 0),
+                },
+                nom::error::ErrorKind::Digit | nom::error::ErrorKind::MapRes => ParseError {
+                    line,
+                    message: format!("expected an integer on line {}", line),
+                    kind: ParseErrorKind::InvalidInteger,
+                },
+                nom::error::ErrorKind::Eof => ParseError {
+                    line,
+                    message: "unexpected end of input".to_string(),
+                    kind: ParseErrorKind::UnexpectedEof,
+                },
+                _ => ParseError {
+                    line,
+                    message: format!("could not parse line {}", line),
+                    kind: ParseErrorKind::InvalidVertexCount,
+                },
+            }
+        }
+    }
+}
+
+/// Parse line ending (handles both \n and \r\n)
+fn line_ending(input: &str) -> IResult<&str, &str> {
+    alt((tag("\n"), tag("\r\n")))(input)
+}
+
+/// Parse a single unsigned integer
+fn parse_usize(input: &str) -> IResult<&str, usize> {
+    map_res(digit1, |s: &str| s.parse::<usize>())(input)
+}
+
+/// Parse a row of space-separated integers
+fn parse_row(input: &str) -> IResult<&str, Vec<usize>> {
+    preceded(space0, separated_list1(space1, parse_usize))(input)
+}
+
+/// Parse a complete adjacency matrix (n rows of n elements each)
+fn parse_adjacency_matrix(input: &str, n: usize) -> IResult<&str, Vec<Vec<usize>>> {
+    let mut rows = Vec::with_capacity(n);
+    let mut remaining = input;
+
+    for _ in 0..n {
+        let (rest, row) = terminated(parse_row, opt(line_ending))(remaining)?;
+
+        if row.len() != n {
+            return Err(nom::Err::Failure(nom::error::Error::new(
+                input,
+                nom::error::ErrorKind::LengthValue,
+            )));
+        }
+
+        rows.push(row);
+        remaining = rest;
+    }
+
+    Ok((remaining, rows))
+}
+
+/// Parse a single graph: vertex count followed by adjacency matrix
+fn parse_graph(input: &str, options: GraphOptions) -> IResult<&str, Graph> {
+    // Parse vertex count
+    let (input, n) = terminated(preceded(space0, parse_usize), line_ending)(input)?;
+
+    // Parse adjacency matrix
+    let (input, adj) = parse_adjacency_matrix(input, n)?;
+
+    Ok((
+        input,
+        Graph::from_adjacency_matrix_with_options(adj, options),
+    ))
+}
+
+/// Parse two graphs from input string, applying `options` to both.
+pub fn parse_two_graphs(input: &str, options: GraphOptions) -> IResult<&str, (Graph, Graph)> {
+    let (input, g) = parse_graph(input, options)?;
+    // Allow optional blank lines between graphs
+    let (input, _) = opt(many1(line_ending))(input)?;
+    let (input, h) = parse_graph(input, options)?;
+
+    Ok((input, (g, h)))
+}
+
+/// Walk the raw input line-by-line (independent of nom) to produce a precise
+/// `WrongRowLength`/`UnexpectedEof` diagnostic with an accurate line number,
+/// since by the time nom reports a failure it has already lost track of
+/// which row misbehaved.
+fn validate_row_lengths(input: &str) -> Result<(), ParseError> {
+    let mut lines = input.lines().enumerate().peekable();
+
+    for _ in 0..2 {
+        while let Some(&(_, line)) = lines.peek() {
+            if line.trim().is_empty() {
+                lines.next();
+            } else {
+                break;
+            }
+        }
+
+        let (n_line_idx, n_line) = match lines.next() {
+            Some(v) => v,
+            None => return Ok(()), // let nom report the missing vertex count
+        };
+        let n: usize = match n_line.trim().parse() {
+            Ok(v) => v,
+            Err(_) => return Ok(()), // let nom report the invalid integer
+        };
+
+        for row_num in 0..n {
+            let (row_idx, row_line) = match lines.next() {
+                Some(v) => v,
+                None => {
+                    return Err(ParseError {
+                        line: n_line_idx + 2 + row_num,
+                        message: format!("unexpected end of input: expected {} rows", n),
+                        kind: ParseErrorKind::UnexpectedEof,
+                    })
+                }
+            };
+            let actual = row_line.split_whitespace().count();
+            if actual != n {
+                return Err(ParseError {
+                    line: row_idx + 1,
+                    message: format!(
+                        "Row {} has {} elements but expected {}",
+                        row_idx + 1,
+                        actual,
+                        n
+                    ),
+                    kind: ParseErrorKind::WrongRowLength(actual, n),
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Parse input file containing two graph descriptions, preserving self-loops
+/// (see `GraphOptions`). Use `parse_input_file_with_options` to strip them
+/// instead.
+pub fn parse_input_file(path: &PathBuf) -> Result<(Graph, Graph), ParseError> {
+    parse_input_file_with_options(path, GraphOptions::default())
+}
+
+/// Parse input file containing two graph descriptions, applying `options`
+/// (e.g. self-loop handling) to both.
+pub fn parse_input_file_with_options(
+    path: &PathBuf,
+    options: GraphOptions,
+) -> Result<(Graph, Graph), ParseError> {
+    let content = std::fs::read_to_string(path).map_err(|e| ParseError {
+        line: 0,
+        message: format!("could not read input file: {}", e),
+        kind: ParseErrorKind::UnexpectedEof,
+    })?;
+
+    validate_row_lengths(&content)?;
+
+    match parse_two_graphs(&content, options) {
+        Ok((_, graphs)) => Ok(graphs),
+        Err(e) => Err(to_parse_error(&content, e)),
+    }
+}
+
+/// How long `parse_stdin`/`parse_stdin_matrix_market` wait for a complete
+/// read from stdin before giving up, so a `--stdin` run started without
+/// anything actually piped in fails fast instead of hanging forever on an
+/// interactive terminal.
+const STDIN_READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Read all of stdin to a string, failing with `UnexpectedEof` if nothing
+/// arrives within `STDIN_READ_TIMEOUT`. Stdin has no portable way to poll
+/// with a timeout directly, so the actual blocking read runs on a detached
+/// thread and this just waits on a channel for it to finish -- if it times
+/// out, that thread is left to exit on its own whenever stdin does close.
+fn read_stdin_with_timeout() -> Result<String, ParseError> {
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let mut content = String::new();
+        let result = std::io::stdin()
+            .read_to_string(&mut content)
+            .map(|_| content)
+            .map_err(|e| e.to_string());
+        let _ = tx.send(result);
+    });
+
+    match rx.recv_timeout(STDIN_READ_TIMEOUT) {
+        Ok(Ok(content)) => Ok(content),
+        Ok(Err(message)) => Err(ParseError {
+            line: 0,
+            message: format!("could not read from stdin: {}", message),
+            kind: ParseErrorKind::UnexpectedEof,
+        }),
+        Err(_) => Err(ParseError {
+            line: 0,
+            message: format!(
+                "timed out after {}s waiting for input on stdin",
+                STDIN_READ_TIMEOUT.as_secs()
+            ),
+            kind: ParseErrorKind::UnexpectedEof,
+        }),
+    }
+}
+
+/// Parse two graph descriptions read from stdin (see `parse_input_file`),
+/// preserving self-loops. Use `parse_stdin_with_options` to strip them
+/// instead.
+pub fn parse_stdin() -> Result<(Graph, Graph), ParseError> {
+    parse_stdin_with_options(GraphOptions::default())
+}
+
+/// Parse two graph descriptions read from stdin, applying `options` (e.g.
+/// self-loop handling) to both. Gives up after `STDIN_READ_TIMEOUT` if
+/// nothing is piped in.
+pub fn parse_stdin_with_options(options: GraphOptions) -> Result<(Graph, Graph), ParseError> {
+    let content = read_stdin_with_timeout()?;
+
+    validate_row_lengths(&content)?;
+
+    match parse_two_graphs(&content, options) {
+        Ok((_, graphs)) => Ok(graphs),
+        Err(e) => Err(to_parse_error(&content, e)),
+    }
+}
+
+/// Parse two Matrix Market graph descriptions read from stdin (see
+/// `parse_input_file_matrix_market`). Gives up after `STDIN_READ_TIMEOUT` if
+/// nothing is piped in.
+pub fn parse_stdin_matrix_market() -> Result<(Graph, Graph), ParseError> {
+    let content = read_stdin_with_timeout()?;
+    parse_two_graphs_matrix_market(&content)
+}
+
+/// The only Matrix Market header this module understands: a coordinate
+/// (sparse triple) matrix of integer values, with no assumed symmetry. A
+/// `symmetric` matrix (which lists only one triangle) or a `real`/`complex`/
+/// `pattern` value type would need different triple handling and isn't
+/// supported.
+const MATRIX_MARKET_HEADER: &str = "%%matrixmarket matrix coordinate integer general";
+
+/// The next line in `lines` that isn't blank or a `%`-prefixed comment,
+/// alongside its 0-based index into the original input.
+fn next_significant_line<'a>(
+    lines: &mut std::iter::Enumerate<std::str::Lines<'a>>,
+) -> Option<(usize, &'a str)> {
+    lines.find(|(_, line)| {
+        let trimmed = line.trim();
+        !trimmed.is_empty() && !trimmed.starts_with('%')
+    })
+}
+
+/// Parse a single graph from Matrix Market coordinate format: a
+/// `%%MatrixMarket matrix coordinate integer general` header, any number of
+/// `%`-prefixed comment lines, a `rows cols entries` size line, then
+/// `entries` many 1-indexed `row col value` triples. See `to_matrix_market`
+/// for the inverse. `rows` and `cols` must be equal, since Matrix Market
+/// rows/columns become a graph's adjacency matrix indices either way.
+pub fn from_matrix_market(input: &str) -> Result<Graph, ParseError> {
+    let mut lines = input.lines().enumerate();
+
+    let (header_idx, header) =
+        lines
+            .find(|(_, line)| !line.trim().is_empty())
+            .ok_or(ParseError {
+                line: 1,
+                message: "unexpected end of input: missing MatrixMarket header".to_string(),
+                kind: ParseErrorKind::UnexpectedEof,
+            })?;
+    if header.trim().to_lowercase() != MATRIX_MARKET_HEADER {
+        return Err(ParseError {
+            line: header_idx + 1,
+            message: "expected a '%%MatrixMarket matrix coordinate integer general' header"
+                .to_string(),
+            kind: ParseErrorKind::InvalidHeader,
+        });
+    }
+
+    let (size_idx, size_line) = next_significant_line(&mut lines).ok_or(ParseError {
+        line: header_idx + 2,
+        message: "unexpected end of input: missing size line".to_string(),
+        kind: ParseErrorKind::UnexpectedEof,
+    })?;
+    let sizes: Vec<&str> = size_line.split_whitespace().collect();
+    let [rows_str, cols_str, entries_str] = sizes[..] else {
+        return Err(ParseError {
+            line: size_idx + 1,
+            message: "expected 'rows cols entries' on the size line".to_string(),
+            kind: ParseErrorKind::InvalidHeader,
+        });
+    };
+    let parse_size = |s: &str| {
+        s.parse::<usize>().map_err(|_| ParseError {
+            line: size_idx + 1,
+            message: format!("expected an integer on line {}", size_idx + 1),
+            kind: ParseErrorKind::InvalidInteger,
+        })
+    };
+    let rows = parse_size(rows_str)?;
+    let cols = parse_size(cols_str)?;
+    let entries = parse_size(entries_str)?;
+    if rows != cols {
+        return Err(ParseError {
+            line: size_idx + 1,
+            message: format!(
+                "Matrix Market rows ({}) and cols ({}) must match for a graph's square adjacency matrix",
+                rows, cols
+            ),
+            kind: ParseErrorKind::InvalidVertexCount,
+        });
+    }
+
+    let mut adj = vec![vec![0usize; rows]; rows];
+    for _ in 0..entries {
+        let (entry_idx, entry_line) = next_significant_line(&mut lines).ok_or(ParseError {
+            line: size_idx + 2,
+            message: format!("unexpected end of input: expected {} entries", entries),
+            kind: ParseErrorKind::UnexpectedEof,
+        })?;
+        let fields: Vec<&str> = entry_line.split_whitespace().collect();
+        if fields.len() != 3 {
+            return Err(ParseError {
+                line: entry_idx + 1,
+                message: format!("expected 'row col value' on line {}", entry_idx + 1),
+                kind: ParseErrorKind::WrongRowLength(fields.len(), 3),
+            });
+        }
+        let parse_field = |s: &str| {
+            s.parse::<usize>().map_err(|_| ParseError {
+                line: entry_idx + 1,
+                message: format!("expected an integer on line {}", entry_idx + 1),
+                kind: ParseErrorKind::InvalidInteger,
+            })
+        };
+        let row = parse_field(fields[0])?;
+        let col = parse_field(fields[1])?;
+        let value = parse_field(fields[2])?;
+        if row == 0 || row > rows || col == 0 || col > cols {
+            return Err(ParseError {
+                line: entry_idx + 1,
+                message: format!(
+                    "entry ({}, {}) is outside the 1-indexed 1..={} range",
+                    row, col, rows
+                ),
+                kind: ParseErrorKind::InvalidVertexCount,
+            });
+        }
+        adj[row - 1][col - 1] = value;
+    }
+
+    Ok(Graph::from_adjacency_matrix(adj))
+}
+
+/// Write `self` in the Matrix Market coordinate format `from_matrix_market`
+/// reads back: a `%%MatrixMarket matrix coordinate integer general` header,
+/// a `%`-prefixed comment naming the graph (from `name`), the
+/// `rows cols entries` size line, then one 1-indexed `row col value` triple
+/// per non-zero adjacency entry, in row-major order.
+pub fn to_matrix_market(g: &Graph, name: &str) -> String {
+    let entries: Vec<(usize, usize, usize)> = (0..g.n)
+        .flat_map(|i| (0..g.n).map(move |j| (i, j)))
+        .filter_map(|(i, j)| {
+            let weight = g.adj[i][j];
+            (weight > 0).then_some((i + 1, j + 1, weight))
+        })
+        .collect();
+
+    let mut out = String::new();
+    out.push_str("%%MatrixMarket matrix coordinate integer general\n");
+    out.push_str(&format!("% {}\n", name));
+    out.push_str(&format!("{} {} {}\n", g.n, g.n, entries.len()));
+    for (row, col, value) in entries {
+        out.push_str(&format!("{} {} {}\n", row, col, value));
+    }
+    out
+}
+
+/// Split `input` into one string per `%%MatrixMarket` header line found,
+/// each running up to (but not including) the next header. Used by
+/// `parse_two_graphs_matrix_market` to locate the "two-graph" convention's
+/// back-to-back blocks without either block needing to know its own length
+/// up front.
+fn split_matrix_market_blocks(input: &str) -> Vec<String> {
+    let mut blocks: Vec<String> = Vec::new();
+    for line in input.split_inclusive('\n') {
+        if line
+            .trim_start()
+            .to_lowercase()
+            .starts_with("%%matrixmarket")
+        {
+            blocks.push(String::new());
+        }
+        if let Some(block) = blocks.last_mut() {
+            block.push_str(line);
+        }
+    }
+    blocks
+}
+
+/// Parse two graphs from a single input containing two back-to-back Matrix
+/// Market blocks (see `to_matrix_market`), the "two-graph" convention
+/// `--format matrix-market` uses for a G/H pair.
+pub fn parse_two_graphs_matrix_market(input: &str) -> Result<(Graph, Graph), ParseError> {
+    let blocks = split_matrix_market_blocks(input);
+    match &blocks[..] {
+        [g_block, h_block] => Ok((from_matrix_market(g_block)?, from_matrix_market(h_block)?)),
+        _ => Err(ParseError {
+            line: 1,
+            message: format!(
+                "expected exactly 2 '%%MatrixMarket' blocks (one for G, one for H), found {}",
+                blocks.len()
+            ),
+            kind: ParseErrorKind::InvalidHeader,
+        }),
+    }
+}
+
+/// Parse an input file containing two Matrix Market graph descriptions (see
+/// `parse_two_graphs_matrix_market`).
+pub fn parse_input_file_matrix_market(path: &PathBuf) -> Result<(Graph, Graph), ParseError> {
+    let content = std::fs::read_to_string(path).map_err(|e| ParseError {
+        line: 0,
+        message: format!("could not read input file: {}", e),
+        kind: ParseErrorKind::UnexpectedEof,
+    })?;
+
+    parse_two_graphs_matrix_market(&content)
+}
+
+/// Prefix a `ParseError` raised while parsing one block of
+/// `parse_all_graph_pairs` with the 1-based `pair_num` it came from, since
+/// `err.line` alone (relative to that block, not the combined file) isn't
+/// enough to tell which block failed.
+fn prefix_pair_error(mut err: ParseError, pair_num: usize) -> ParseError {
+    err.message = format!("pair {}: {}", pair_num, err.message);
+    err
+}
+
+/// Parse a file containing several back-to-back graph-pair blocks, each in
+/// the same "n1; matrix1; blank line; n2; matrix2; blank line" format
+/// `parse_two_graphs` reads, separated by `---` delimiter lines. The
+/// delimiter is unambiguous here since a graph block only ever contains
+/// digits and whitespace, never a run of `-` characters. Applies `options`
+/// (e.g. self-loop handling) to every graph parsed. See
+/// `parse_all_graph_pairs_file` to read straight from a path.
+pub fn parse_all_graph_pairs_with_options(
+    input: &str,
+    options: GraphOptions,
+) -> Result<Vec<(Graph, Graph)>, ParseError> {
+    let mut pairs = Vec::new();
+    let mut pair_num = 0;
+
+    for raw_block in input.split("---") {
+        // A `---` delimiter leaves a blank line behind on either side of it;
+        // `parse_two_graphs` (unlike the join between G and H within a
+        // block) doesn't tolerate one before its first graph, so strip it.
+        let block = raw_block.trim_start_matches(['\n', '\r']);
+        if block.trim().is_empty() {
+            continue;
+        }
+        pair_num += 1;
+
+        validate_row_lengths(block).map_err(|e| prefix_pair_error(e, pair_num))?;
+        let (_, graphs) = parse_two_graphs(block, options)
+            .map_err(|e| prefix_pair_error(to_parse_error(block, e), pair_num))?;
+        pairs.push(graphs);
+    }
+
+    Ok(pairs)
+}
+
+/// Parse a file containing several graph-pair blocks (see
+/// `parse_all_graph_pairs_with_options`), preserving self-loops.
+pub fn parse_all_graph_pairs(input: &str) -> Result<Vec<(Graph, Graph)>, ParseError> {
+    parse_all_graph_pairs_with_options(input, GraphOptions::default())
+}
+
+/// Read `path` and parse it as several `---`-delimited graph-pair blocks
+/// (see `parse_all_graph_pairs`), applying `options` to every graph parsed.
+pub fn parse_all_graph_pairs_file_with_options(
+    path: &PathBuf,
+    options: GraphOptions,
+) -> Result<Vec<(Graph, Graph)>, ParseError> {
+    let content = std::fs::read_to_string(path).map_err(|e| ParseError {
+        line: 0,
+        message: format!("could not read input file: {}", e),
+        kind: ParseErrorKind::UnexpectedEof,
+    })?;
+
+    parse_all_graph_pairs_with_options(&content, options)
+}
+
+/// Read `path` and parse it as several `---`-delimited graph-pair blocks
+/// (see `parse_all_graph_pairs`), preserving self-loops.
+pub fn parse_all_graph_pairs_file(path: &PathBuf) -> Result<Vec<(Graph, Graph)>, ParseError> {
+    parse_all_graph_pairs_file_with_options(path, GraphOptions::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_all_graph_pairs_reads_every_block_in_order() {
+        let input = "\
+1
+0
+
+1
+0
+---
+2
+0 1
+0 0
+
+1
+0
+---
+1
+0
+
+1
+0
+";
+        let pairs = parse_all_graph_pairs(input).unwrap();
+
+        assert_eq!(pairs.len(), 3);
+        assert_eq!(pairs[0].0.num_vertices(), 1);
+        assert_eq!(pairs[1].0.num_vertices(), 2);
+        assert_eq!(pairs[1].1.num_vertices(), 1);
+        assert_eq!(pairs[2].0.num_vertices(), 1);
+    }
+
+    #[test]
+    fn test_parse_all_graph_pairs_reports_which_pair_is_malformed() {
+        let input = "\
+1
+0
+
+1
+0
+---
+2
+0 1
+0
+0 0
+
+1
+0
+";
+        let err = parse_all_graph_pairs(input).unwrap_err();
+
+        assert!(
+            err.message.contains("pair 2"),
+            "expected the error to name the second block, got: {}",
+            err.message
+        );
+    }
+
+    #[test]
+    fn test_parse_all_graph_pairs_file_reads_every_block() {
+        let dir = std::env::temp_dir().join("minimal_k_iso_lib_test_parse_all_graph_pairs_file");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("instances.txt");
+        std::fs::write(
+            &path,
+            "\
+1
+0
+
+1
+0
+---
+1
+0
+
+1
+0
+",
+        )
+        .unwrap();
+
+        let pairs = parse_all_graph_pairs_file(&path).unwrap();
+
+        assert_eq!(pairs.len(), 2);
+    }
+}
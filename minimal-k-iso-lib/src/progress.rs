@@ -0,0 +1,113 @@
+//! Shared vocabulary for reporting a solver's progress back to whatever is
+//! driving it -- the `solver` TUI's background thread today, but any future
+//! caller (another binary, a library consumer with its own UI) can reuse the
+//! same types instead of inventing its own.
+
+use crate::{EdgeMap, Mapping};
+use std::time::Duration;
+
+/// Which of the two search strategies produced (or should produce) a result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    Exact,
+    Approx,
+    /// Run both algorithms, one after the other, and report how close the
+    /// approx result came to the exact optimum (see `ProgressMessage::CompareComplete`).
+    Compare,
+}
+
+impl std::str::FromStr for Algorithm {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "exact" => Ok(Algorithm::Exact),
+            "approx" | "approximate" | "approximation" => Ok(Algorithm::Approx),
+            "compare" | "comparison" => Ok(Algorithm::Compare),
+            _ => Err(format!(
+                "Invalid algorithm: {}. Use 'exact', 'approx', or 'compare'",
+                s
+            )),
+        }
+    }
+}
+
+/// Result of `--algorithm compare`: the exact algorithm's result (proven
+/// optimal unless `exact_timed_out`) alongside the approx algorithm's, and
+/// how many times worse the latter is.
+#[derive(Debug, Clone)]
+pub struct CompareResult {
+    pub exact_cost: usize,
+    pub exact_edge_map: EdgeMap,
+    pub exact_mappings: Vec<Mapping>,
+    pub exact_elapsed: Duration,
+    pub exact_timed_out: bool,
+    pub approx_cost: usize,
+    pub approx_edge_map: EdgeMap,
+    pub approx_mappings: Vec<Mapping>,
+    pub approx_elapsed: Duration,
+    /// `approx_cost / exact_cost`, or `usize::MAX` (as an `f64`, standing in
+    /// for infinity) when `exact_cost` is 0 but `approx_cost` isn't.
+    pub ratio: f64,
+}
+
+/// Progress messages from the algorithm thread.
+#[derive(Debug, Clone)]
+pub enum ProgressMessage {
+    Status(String),
+    MappingProgress {
+        current: usize,
+        total: usize,
+    },
+    RestartProgress {
+        restart: usize,
+        total_restarts: usize,
+        best_cost_so_far: Option<usize>,
+    },
+    Complete {
+        /// Which algorithm actually produced this result — not necessarily
+        /// the one the user requested, since `--memory-limit` can make the
+        /// exact algorithm fall back to the approx one.
+        algorithm: Algorithm,
+        cost: usize,
+        edge_map: EdgeMap,
+        mappings: Vec<Mapping>,
+        elapsed: Duration,
+        /// `(nodes visited, total combinations)` for the exact algorithm's
+        /// branch-and-bound search; `None` for the approx algorithm, which
+        /// doesn't search combinations at all.
+        search_stats: Option<(usize, usize)>,
+        /// Estimated memory, in bytes, that `find_all_mappings` used to
+        /// materialize its candidate pool (see `utils::estimate_memory_bytes`).
+        /// `None` when nothing got the chance to materialize one — the
+        /// approx algorithm constructs mappings one at a time and never
+        /// holds the full pool at once.
+        estimated_memory_bytes: Option<usize>,
+        /// Whether `--timeout` cut the exact search off before it could
+        /// prove optimality. Always `false` for the approx algorithm, which
+        /// has no notion of optimality to begin with.
+        timed_out: bool,
+    },
+    /// Sent once in place of `Complete`, after both the exact and approx
+    /// algorithms have finished.
+    CompareComplete(Box<CompareResult>),
+    /// Sent each time a new incumbent is written to `--incumbent-file`; the
+    /// Calculating view's "best so far" history list is built entirely from
+    /// these.
+    Incumbent {
+        cost: usize,
+        elapsed: Duration,
+        nodes_visited: usize,
+    },
+    Error(String),
+    /// Sent instead of starting the branch-and-bound search when
+    /// `num_combinations(all_mappings.len(), k)` exceeds `--max-combinations`
+    /// and `--force` wasn't given. Distinct from `Error` so the Calculating
+    /// view can offer a keypress to switch to the approx algorithm instead of
+    /// just reporting failure.
+    CombinationGuardTriggered {
+        total_combinations: usize,
+        max_combinations: usize,
+        projected_runtime: Duration,
+    },
+}
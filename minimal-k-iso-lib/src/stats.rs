@@ -0,0 +1,234 @@
+//! Structural statistics about a single [`Graph`]: vertex/edge counts,
+//! density, a weighted-degree histogram, strongly connected component sizes,
+//! and (when affordable) diameter. Computed directly from the adjacency
+//! matrix with no dependency on this crate's mapping/search code, so it
+//! works on any graph regardless of whether a particular `(G, H, k)`
+//! instance is solvable. See `solver`'s `--stats-only` flag.
+
+use crate::Graph;
+use std::collections::VecDeque;
+
+/// Past this many vertices, [`GraphStats::compute`] skips the diameter: a
+/// BFS from every vertex over a graph this size is O(n^3), which stops being
+/// "always fast enough to run" well before it stops being possible at all.
+pub const MAX_VERTICES_FOR_DIAMETER: usize = 300;
+
+/// Structural statistics about a single graph. Every field is computed in
+/// O(n^2) time except `diameter`, which is O(n^3) (a BFS from every vertex)
+/// and is skipped past [`MAX_VERTICES_FOR_DIAMETER`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct GraphStats {
+    pub num_vertices: usize,
+    /// Sum of every entry in the adjacency matrix, i.e. the total edge
+    /// multiplicity (parallel edges counted separately).
+    pub num_edges: usize,
+    /// Fraction of the `n * (n - 1)` possible directed, non-self-loop pairs
+    /// that carry at least one edge. `0.0` when `n <= 1`.
+    pub density: f64,
+    /// `(weighted total degree, vertex count at that degree)`, sorted
+    /// ascending by degree. Weighted total degree is
+    /// `weighted_out_degree(v) + weighted_in_degree(v)`.
+    pub degree_histogram: Vec<(usize, usize)>,
+    /// Size of every strongly connected component, descending.
+    pub scc_sizes: Vec<usize>,
+    /// Longest shortest directed path between any two distinct vertices, or
+    /// `None` if some pair can't reach each other (undefined for a
+    /// disconnected graph) or `num_vertices` exceeds
+    /// [`MAX_VERTICES_FOR_DIAMETER`].
+    pub diameter: Option<usize>,
+}
+
+impl GraphStats {
+    pub fn compute(g: &Graph) -> GraphStats {
+        let n = g.num_vertices();
+
+        let num_edges = g.adj.iter().flatten().sum();
+
+        let possible_pairs = n.saturating_sub(1) * n;
+        let present_pairs = (0..n)
+            .map(|i| (0..n).filter(|&j| j != i && g.adj[i][j] > 0).count())
+            .sum::<usize>();
+        let density = if possible_pairs == 0 {
+            0.0
+        } else {
+            present_pairs as f64 / possible_pairs as f64
+        };
+
+        let mut degree_histogram: Vec<(usize, usize)> = Vec::new();
+        for v in 0..n {
+            let degree = g.weighted_out_degree(v) + g.weighted_in_degree(v);
+            match degree_histogram.iter_mut().find(|(d, _)| *d == degree) {
+                Some((_, count)) => *count += 1,
+                None => degree_histogram.push((degree, 1)),
+            }
+        }
+        degree_histogram.sort_unstable_by_key(|&(degree, _)| degree);
+
+        let scc_sizes = strongly_connected_component_sizes(g);
+
+        let diameter = if n <= MAX_VERTICES_FOR_DIAMETER {
+            diameter_via_bfs(g)
+        } else {
+            None
+        };
+
+        GraphStats {
+            num_vertices: n,
+            num_edges,
+            density,
+            degree_histogram,
+            scc_sizes,
+            diameter,
+        }
+    }
+}
+
+/// Size of every strongly connected component of `g`, descending, via
+/// Kosaraju's algorithm (a finishing-order DFS over `g`, then a DFS over its
+/// transpose in reverse finishing order). Implemented with an explicit stack
+/// rather than recursion, and scanning each vertex's row/column directly
+/// (O(n) per step) rather than building an adjacency list, so the whole thing
+/// runs in O(n^2) time and needs no extra graph representation.
+fn strongly_connected_component_sizes(g: &Graph) -> Vec<usize> {
+    let n = g.num_vertices();
+    if n == 0 {
+        return Vec::new();
+    }
+
+    let mut visited = vec![false; n];
+    let mut finish_order = Vec::with_capacity(n);
+    for start in 0..n {
+        if visited[start] {
+            continue;
+        }
+        visited[start] = true;
+        let mut stack = vec![(start, 0usize)];
+        while let Some(&mut (u, ref mut next)) = stack.last_mut() {
+            if *next < n {
+                let v = *next;
+                *next += 1;
+                if g.adj[u][v] > 0 && !visited[v] {
+                    visited[v] = true;
+                    stack.push((v, 0));
+                }
+            } else {
+                finish_order.push(u);
+                stack.pop();
+            }
+        }
+    }
+
+    let mut component = vec![usize::MAX; n];
+    let mut sizes = Vec::new();
+    for &start in finish_order.iter().rev() {
+        if component[start] != usize::MAX {
+            continue;
+        }
+        let component_id = sizes.len();
+        let mut size = 0;
+        let mut stack = vec![start];
+        component[start] = component_id;
+        while let Some(u) = stack.pop() {
+            size += 1;
+            for (v, slot) in component.iter_mut().enumerate() {
+                if g.adj[v][u] > 0 && *slot == usize::MAX {
+                    *slot = component_id;
+                    stack.push(v);
+                }
+            }
+        }
+        sizes.push(size);
+    }
+
+    sizes.sort_unstable_by(|a, b| b.cmp(a));
+    sizes
+}
+
+/// Longest shortest directed path between any two distinct vertices, or
+/// `None` as soon as some vertex can't reach another (diameter is undefined
+/// for a disconnected graph).
+fn diameter_via_bfs(g: &Graph) -> Option<usize> {
+    let n = g.num_vertices();
+    let mut diameter = 0;
+
+    for source in 0..n {
+        let mut distance = vec![None; n];
+        distance[source] = Some(0);
+        let mut queue = VecDeque::from([source]);
+        while let Some(u) = queue.pop_front() {
+            let next_distance = distance[u].unwrap() + 1;
+            for (v, slot) in distance.iter_mut().enumerate() {
+                if g.adj[u][v] > 0 && slot.is_none() {
+                    *slot = Some(next_distance);
+                    queue.push_back(v);
+                }
+            }
+        }
+
+        for d in distance {
+            diameter = diameter.max(d?);
+        }
+    }
+
+    Some(diameter)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn triangle() -> Graph {
+        Graph::from_adjacency_matrix(vec![vec![0, 1, 0], vec![0, 0, 1], vec![1, 0, 0]])
+    }
+
+    #[test]
+    fn test_triangle_stats_match_hand_computed_values() {
+        let stats = GraphStats::compute(&triangle());
+
+        assert_eq!(stats.num_vertices, 3);
+        assert_eq!(stats.num_edges, 3);
+        // 3 of the 3 * 2 = 6 possible directed, non-self-loop pairs carry an
+        // edge: (0,1), (1,2), (2,0), but not their reverses.
+        assert_eq!(stats.density, 0.5);
+        assert_eq!(stats.degree_histogram, vec![(2, 3)]);
+        assert_eq!(stats.scc_sizes, vec![3]);
+        assert_eq!(stats.diameter, Some(2));
+    }
+
+    #[test]
+    fn test_disconnected_graph_has_no_diameter_but_still_reports_every_scc() {
+        // Two disjoint single-edge components: 0 -> 1, 2 -> 3.
+        let g = Graph::from_adjacency_matrix(vec![
+            vec![0, 1, 0, 0],
+            vec![0, 0, 0, 0],
+            vec![0, 0, 0, 1],
+            vec![0, 0, 0, 0],
+        ]);
+        let stats = GraphStats::compute(&g);
+
+        assert_eq!(stats.scc_sizes, vec![1, 1, 1, 1]);
+        assert_eq!(stats.diameter, None);
+    }
+
+    #[test]
+    fn test_empty_graph_has_zero_density_and_no_components() {
+        let stats = GraphStats::compute(&Graph::new(0));
+
+        assert_eq!(stats.num_vertices, 0);
+        assert_eq!(stats.num_edges, 0);
+        assert_eq!(stats.density, 0.0);
+        assert!(stats.scc_sizes.is_empty());
+        assert_eq!(stats.diameter, Some(0));
+    }
+
+    #[test]
+    fn test_multi_edge_contributes_its_full_weight_to_num_edges_and_degree() {
+        let g = Graph::from_adjacency_matrix(vec![vec![0, 3], vec![0, 0]]);
+        let stats = GraphStats::compute(&g);
+
+        assert_eq!(stats.num_edges, 3);
+        // Vertex 0's weighted out-degree and vertex 1's weighted in-degree
+        // are both 3, so they land in the same histogram bucket.
+        assert_eq!(stats.degree_histogram, vec![(3, 2)]);
+    }
+}
@@ -0,0 +1,611 @@
+use crate::Graph;
+use itertools::Itertools;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::VecDeque;
+use std::hash::{Hash, Hasher};
+
+/// Calculate n! as a u128, sufficient for the factorials this crate needs
+/// (dividing down permanents computed over at most ~25 host vertices).
+pub fn factorial(n: usize) -> u128 {
+    (1..=n as u128).product::<u128>().max(1)
+}
+
+/// Number of ordered selections of `k` distinct items out of `n`, i.e.
+/// P(n, k) = n! / (n-k)!, without needing the (much larger) full factorials.
+pub fn permutation_count(n: usize, k: usize) -> u128 {
+    if k > n {
+        return 0;
+    }
+    ((n - k + 1)..=n).map(|x| x as u128).product()
+}
+
+/// Rough estimate of the order of `g`'s automorphism group, via orbit
+/// counting over its weighted degree sequence: group vertices by their
+/// `(weighted_out_degree, weighted_in_degree)` pair, then multiply together
+/// the factorials of each group's size. Same degree is necessary but not
+/// sufficient for two vertices to be swappable under an automorphism (e.g. a
+/// directed cycle's vertices all share a degree pair but the only
+/// automorphisms are its rotations), so this can overcount the true
+/// automorphism group — it's a cheap approximation, not an exact count or a
+/// proven bound in either direction.
+///
+/// Used to give the combination-count figures `exact_solver` and `solver`
+/// print a rough sense of how much of the enumerated search space is
+/// actually redundant under `g`'s own symmetry (see `--symmetry-breaking`).
+pub fn estimate_automorphisms(g: &Graph) -> usize {
+    let sequence = g.weighted_degree_sequence();
+
+    let mut tie_group_size = 0u128;
+    let mut estimate = 1u128;
+    for i in 0..sequence.len() {
+        tie_group_size += 1;
+        if i + 1 == sequence.len() || sequence[i + 1] != sequence[i] {
+            estimate *= factorial(tie_group_size as usize);
+            tie_group_size = 0;
+        }
+    }
+
+    estimate.min(usize::MAX as u128) as usize
+}
+
+/// `g`'s automorphism group, enumerated exactly: every permutation of its
+/// vertices that preserves its (weighted, directed) adjacency matrix exactly,
+/// i.e. `perm` such that `g.adj[perm[u]][perm[v]] == g.adj[u][v]` for every
+/// `u, v`. Unlike `estimate_automorphisms`'s degree-based approximation,
+/// this is the real group, found by brute-force permutation checking --
+/// `O(n!)` candidates checked in `O(n^2)` each, so only worth it for a
+/// pattern graph small enough that `find_all_mappings` already enumerates
+/// mappings over it. Always includes the identity permutation, so the
+/// result is never empty.
+pub fn automorphisms(g: &Graph) -> Vec<Vec<usize>> {
+    let n = g.num_vertices();
+    (0..n)
+        .permutations(n)
+        .filter(|perm| (0..n).all(|u| (0..n).all(|v| g.adj[perm[u]][perm[v]] == g.adj[u][v])))
+        .collect()
+}
+
+/// Rough upper bound on the memory a `Vec<Mapping>` of `n_mappings` mappings
+/// from a pattern of `n_g` vertices occupies: each mapping is a `Vec<usize>`
+/// of length `n_g`, so this is just `n_mappings * n_g * size_of::<usize>()`,
+/// ignoring each `Vec`'s own header and allocator overhead. Meant for
+/// deciding whether a candidate pool is worth materializing at all (see
+/// `--memory-limit` in the solver binaries), not as a precise accounting.
+pub fn estimate_memory_bytes(n_mappings: usize, n_g: usize) -> usize {
+    n_mappings
+        .saturating_mul(n_g)
+        .saturating_mul(std::mem::size_of::<usize>())
+}
+
+/// Calculate number of combinations C(n, k) without overflow
+pub fn num_combinations(n: usize, k: usize) -> usize {
+    if k > n {
+        return 0;
+    }
+    if k == 0 || k == n {
+        return 1;
+    }
+
+    let k = k.min(n - k); // Optimization: C(n,k) = C(n,n-k)
+    let mut result = 1usize;
+
+    for i in 0..k {
+        result = result.saturating_mul(n - i) / (i + 1);
+    }
+
+    result
+}
+
+/// A precomputed table of C(n, k) for every `n, k <= max_n`, built once via
+/// Pascal's triangle (`table[n][k] = table[n-1][k-1] + table[n-1][k]`,
+/// saturating at `usize::MAX`) so repeated lookups are O(1) instead of
+/// [`num_combinations`]'s O(k) per call -- worth it for a caller that queries
+/// the same `max_n` many times over the course of a search, not for a
+/// one-off call.
+#[derive(Debug, Clone)]
+pub struct BinomialTable {
+    table: Vec<Vec<usize>>,
+}
+
+impl BinomialTable {
+    /// Builds the table for every `n` from `0` to `max_n` inclusive.
+    pub fn new(max_n: usize) -> Self {
+        let mut table = vec![vec![0usize; max_n + 1]; max_n + 1];
+        for n in 0..=max_n {
+            table[n][0] = 1;
+            for k in 1..=n {
+                table[n][k] = table[n - 1][k - 1].saturating_add(table[n - 1][k]);
+            }
+        }
+        BinomialTable { table }
+    }
+
+    /// C(n, k), or 0 if either exceeds the `max_n` this table was built for
+    /// (matching [`num_combinations`]'s 0 for `k > n`, rather than panicking).
+    pub fn get(&self, n: usize, k: usize) -> usize {
+        if n >= self.table.len() || k > n {
+            return 0;
+        }
+        self.table[n][k]
+    }
+}
+
+/// Run `iterations` rounds of 1-dimensional Weisfeiler-Lehman color
+/// refinement over `g`: every vertex starts with the same color, then each
+/// round replaces a vertex's color with a hash of its current color and the
+/// sorted multiset of its out-neighbors' colors (an edge of weight `w`
+/// contributes its neighbor's color `w` times, so multi-edges are reflected
+/// in the refinement same as anywhere else in this crate). Returns the final
+/// per-vertex colors, sorted, so two graphs' results can be compared
+/// directly as histograms without first finding a matching permutation.
+///
+/// Two graphs with different `wl_hash` results cannot be isomorphic; equal
+/// results are necessary but not sufficient (WL cannot distinguish some
+/// non-isomorphic graph pairs), so this is only ever a cheap pre-check, not
+/// a substitute for the backtracking search itself.
+pub fn wl_hash(g: &Graph, iterations: usize) -> Vec<u64> {
+    let n = g.num_vertices();
+    let mut colors = vec![0u64; n];
+
+    for _ in 0..iterations {
+        let next_colors: Vec<u64> = (0..n)
+            .map(|v| {
+                let mut neighbor_colors: Vec<u64> = (0..n)
+                    .flat_map(|u| std::iter::repeat_n(colors[u], g.get_edge(v, u)))
+                    .collect();
+                neighbor_colors.sort_unstable();
+
+                let mut hasher = DefaultHasher::new();
+                colors[v].hash(&mut hasher);
+                neighbor_colors.hash(&mut hasher);
+                hasher.finish()
+            })
+            .collect();
+        colors = next_colors;
+    }
+
+    colors.sort_unstable();
+    colors
+}
+
+/// `h` restricted to `vertices`, in the order given: the induced subgraph on
+/// that vertex subset, reindexed to `0..vertices.len()`.
+fn induced_subgraph(h: &Graph, vertices: &[usize]) -> Graph {
+    let adj = vertices
+        .iter()
+        .map(|&u| vertices.iter().map(|&v| h.get_edge(u, v)).collect())
+        .collect();
+    Graph::from_adjacency_matrix(adj)
+}
+
+/// Whether some induced subgraph of `h` with as many vertices as `g` could
+/// possibly be isomorphic to `g`, judged by comparing [`wl_hash`] signatures
+/// (run for `g.num_vertices()` rounds, enough for WL to stabilize on a graph
+/// that size). If none of `h`'s same-size induced subgraphs share `g`'s
+/// signature, no edge-compatible embedding of `g` into `h` exists without
+/// modification.
+///
+/// Deliberately not wired into `find_all_mappings`: that function enumerates
+/// injective vertex assignments regardless of edges by design (see its doc
+/// comment), which is exactly what lets this crate's extension problem ask
+/// "how few edges would make some mapping work" instead of "does an
+/// edge-preserving mapping already exist" — a host that doesn't yet satisfy
+/// `g` at all, isolated-vertex padding included, is the common case, not a
+/// failure. This is instead a standalone pre-check for callers that only
+/// care about true edge-isomorphism and want to skip the backtracking search
+/// when it cannot possibly succeed.
+///
+/// Exhaustive over `C(h.num_vertices(), g.num_vertices())` subsets, so this
+/// is only cheap relative to backtracking when that binomial coefficient is
+/// itself small — the same scale this crate's other exact enumerations
+/// (`find_all_mappings`, `count_satisfying_mappings`'s fallback) are built
+/// for.
+pub fn wl_compatible(g: &Graph, h: &Graph) -> bool {
+    let n_g = g.num_vertices();
+    let n_h = h.num_vertices();
+    if n_g > n_h {
+        return false;
+    }
+
+    let iterations = n_g.max(1);
+    let target = wl_hash(g, iterations);
+
+    (0..n_h)
+        .combinations(n_g)
+        .any(|subset| wl_hash(&induced_subgraph(h, &subset), iterations) == target)
+}
+
+/// Weighted triangle count of `g`: the trace of `adj^3`, i.e. the total
+/// number of closed length-3 walks. An isomorphism invariant, same caveat as
+/// `wl_hash` (necessary but not sufficient) — used here only as a cheap
+/// pre-check, never as proof of isomorphism.
+fn triangle_count(g: &Graph) -> usize {
+    let squared = g
+        .graph_matrix_product(g)
+        .expect("a graph always has the same vertex count as itself");
+    let cubed = squared
+        .graph_matrix_product(g)
+        .expect("a graph always has the same vertex count as itself");
+    (0..g.num_vertices()).map(|v| cubed.get_edge(v, v)).sum()
+}
+
+/// Cheap, sound test for non-isomorphism: returns `true` only when `g` and
+/// `h` are provably not isomorphic, via (in increasing cost) vertex count,
+/// sorted weighted degree sequence, triangle count (the trace of `adj^3`),
+/// and one round of WL color refinement. Returns `false` whenever none of
+/// these distinguish them, which does not mean they ARE isomorphic — like
+/// `wl_hash` and `wl_compatible`, every check here is necessary but not
+/// sufficient, so a `false` result just means this particular cheap test
+/// couldn't tell.
+///
+/// An O(n^2)-ish rejection test meant to run ahead of an expensive exact
+/// isomorphism check. See `wl_compatible` for a related (but much more
+/// expensive, subset-exhaustive) pre-check, and its doc comment for why
+/// neither is wired into `find_all_mappings`: that function enumerates
+/// injective vertex assignments regardless of edges by design, since this
+/// crate's extension problem wants "how few edges would make some mapping
+/// work", not "does an edge-preserving mapping already exist".
+pub fn fast_non_isomorphism_check(g: &Graph, h: &Graph) -> bool {
+    if g.num_vertices() != h.num_vertices() {
+        return true;
+    }
+    if g.weighted_degree_sequence() != h.weighted_degree_sequence() {
+        return true;
+    }
+    if triangle_count(g) != triangle_count(h) {
+        return true;
+    }
+    wl_hash(g, 1) != wl_hash(h, 1)
+}
+
+/// Size of a maximum matching in the bipartite graph with `left_size` +
+/// `right_size` vertices and the given `edges` (left index, right index),
+/// via Hopcroft-Karp: repeatedly find a maximal set of vertex-disjoint
+/// shortest augmenting paths by BFS layering, then augment along each with
+/// DFS, until no augmenting path remains. Runs in O(E * sqrt(V)).
+///
+/// Deliberately not wired into `find_all_mappings` as a bound on mapping
+/// size: `find_all_mappings` enumerates injective vertex assignments
+/// regardless of edges by design (see its doc comment), so any matching
+/// bound built from an edge- or degree-based compatibility relation would
+/// incorrectly reject host vertices that a mapping is free to use and then
+/// extend with new edges — including the isolated vertices
+/// `augmentation::pad_host_to_pattern_size` adds, which have no edges of
+/// their own by construction and would never be "compatible" under such a
+/// relation. This is instead a standalone primitive for callers that have
+/// their own genuine bipartite compatibility relation to bound.
+pub fn maximum_bipartite_matching(
+    left_size: usize,
+    right_size: usize,
+    edges: &[(usize, usize)],
+) -> usize {
+    let mut adj = vec![Vec::new(); left_size];
+    for &(l, r) in edges {
+        adj[l].push(r);
+    }
+
+    const UNMATCHED: usize = usize::MAX;
+    let mut match_left = vec![UNMATCHED; left_size];
+    let mut match_right = vec![UNMATCHED; right_size];
+
+    loop {
+        // BFS: layer every unmatched left vertex at distance 0, and every
+        // vertex reachable by alternating (unmatched, matched) edges, up to
+        // the first layer that reaches an unmatched right vertex.
+        let mut dist = vec![usize::MAX; left_size];
+        let mut queue = VecDeque::new();
+        for l in 0..left_size {
+            if match_left[l] == UNMATCHED {
+                dist[l] = 0;
+                queue.push_back(l);
+            }
+        }
+
+        let mut found_augmenting_path = false;
+        while let Some(l) = queue.pop_front() {
+            for &r in &adj[l] {
+                let matched_l = match_right[r];
+                if matched_l == UNMATCHED {
+                    found_augmenting_path = true;
+                } else if dist[matched_l] == usize::MAX {
+                    dist[matched_l] = dist[l] + 1;
+                    queue.push_back(matched_l);
+                }
+            }
+        }
+
+        if !found_augmenting_path {
+            break;
+        }
+
+        // DFS restricted to the BFS layering, augmenting along every
+        // vertex-disjoint shortest path found.
+        fn augment(
+            l: usize,
+            adj: &[Vec<usize>],
+            dist: &mut [usize],
+            match_left: &mut [usize],
+            match_right: &mut [usize],
+        ) -> bool {
+            for &r in &adj[l] {
+                let matched_l = match_right[r];
+                let can_extend = matched_l == usize::MAX
+                    || (dist[matched_l] == dist[l] + 1
+                        && augment(matched_l, adj, dist, match_left, match_right));
+                if can_extend {
+                    match_left[l] = r;
+                    match_right[r] = l;
+                    return true;
+                }
+            }
+            dist[l] = usize::MAX;
+            false
+        }
+
+        for l in 0..left_size {
+            if match_left[l] == UNMATCHED {
+                augment(l, &adj, &mut dist, &mut match_left, &mut match_right);
+            }
+        }
+    }
+
+    match_left.iter().filter(|&&r| r != UNMATCHED).count()
+}
+
+/// Whether `out_degrees` and `in_degrees` (index-aligned, one pair per
+/// vertex) could be the degree sequence of some simple directed graph (no
+/// self-loops, no parallel edges), via the Fulkerson condition -- the
+/// directed analogue of the undirected Erdős–Gallai theorem. Necessary: the
+/// total out-degree must equal the total in-degree. Sufficient: sorting
+/// vertices by out-degree descending, every prefix's total out-degree must
+/// not exceed what the remaining in-degrees could actually absorb, capping
+/// each vertex's contribution at 1 per edge within the prefix (no
+/// self-loops) and at 1 per edge from outside it.
+///
+/// Used by `input_generator` to validate a `--degree-sequence-g`/
+/// `--degree-sequence-h` request before handing it to
+/// [`degree_constrained_graph`].
+pub fn erdos_gallai_check(out_degrees: &[usize], in_degrees: &[usize]) -> bool {
+    if out_degrees.len() != in_degrees.len() {
+        return false;
+    }
+    let n = out_degrees.len();
+    if out_degrees.iter().sum::<usize>() != in_degrees.iter().sum::<usize>() {
+        return false;
+    }
+
+    let mut pairs: Vec<(usize, usize)> = out_degrees
+        .iter()
+        .copied()
+        .zip(in_degrees.iter().copied())
+        .collect();
+    pairs.sort_unstable_by_key(|&(out, _)| std::cmp::Reverse(out));
+
+    for k in 1..=n {
+        let prefix_out: usize = pairs[..k].iter().map(|&(out, _)| out).sum();
+        let absorbable: usize = pairs[..k]
+            .iter()
+            .map(|&(_, inn)| inn.min(k - 1))
+            .sum::<usize>()
+            + pairs[k..].iter().map(|&(_, inn)| inn.min(k)).sum::<usize>();
+        if prefix_out > absorbable {
+            return false;
+        }
+    }
+
+    true
+}
+
+/// A random directed multigraph on `out_degrees.len()` vertices with exactly
+/// the given out-degree and in-degree sequence, via the configuration model:
+/// give vertex `i` `out_degrees[i]` out-stubs and `in_degrees[i]` in-stubs,
+/// shuffle the in-stubs, then pair them off in order. `Graph` is a
+/// multigraph, so a repeated pairing just raises that pair's edge weight and
+/// a stub paired with itself is a self-loop -- both fine here, unlike the
+/// simple-graph realizability [`erdos_gallai_check`] tests for.
+///
+/// Returns `None` when `out_degrees` and `in_degrees` don't even sum to the
+/// same total, since no multigraph -- however loose -- can realize that.
+pub fn degree_constrained_graph(
+    out_degrees: &[usize],
+    in_degrees: &[usize],
+    rng: &mut impl Rng,
+) -> Option<Graph> {
+    if out_degrees.len() != in_degrees.len()
+        || out_degrees.iter().sum::<usize>() != in_degrees.iter().sum::<usize>()
+    {
+        return None;
+    }
+    let n = out_degrees.len();
+
+    let mut out_stubs = Vec::new();
+    for (v, &degree) in out_degrees.iter().enumerate() {
+        out_stubs.extend(std::iter::repeat_n(v, degree));
+    }
+    let mut in_stubs = Vec::new();
+    for (v, &degree) in in_degrees.iter().enumerate() {
+        in_stubs.extend(std::iter::repeat_n(v, degree));
+    }
+    in_stubs.shuffle(rng);
+
+    let mut g = Graph::new(n);
+    for (&u, &v) in out_stubs.iter().zip(in_stubs.iter()) {
+        g.adj[u][v] += 1;
+    }
+    Some(g)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_estimate_automorphisms_of_an_edgeless_graph_is_n_factorial() {
+        // Every vertex has the same (0, 0) degree pair, so the whole vertex
+        // set is one tie group and any permutation of it is an automorphism.
+        let g = Graph::from_adjacency_matrix(vec![vec![0; 4]; 4]);
+        assert_eq!(estimate_automorphisms(&g), factorial(4) as usize);
+    }
+
+    #[test]
+    fn test_estimate_automorphisms_of_a_directed_cycle_is_one() {
+        // Every vertex has the same (1, 1) degree pair, but a directed cycle
+        // has no automorphism beyond rotation (order 4, not 4!): degree
+        // alone can't tell same-degree vertices apart from genuinely
+        // swappable ones, so the estimate overcounts here.
+        let g = Graph::from_adjacency_matrix(vec![
+            vec![0, 1, 0, 0],
+            vec![0, 0, 1, 0],
+            vec![0, 0, 0, 1],
+            vec![1, 0, 0, 0],
+        ]);
+        assert_eq!(estimate_automorphisms(&g), factorial(4) as usize);
+    }
+
+    #[test]
+    fn test_estimate_automorphisms_multiplies_factorials_across_distinct_tie_groups() {
+        // Two vertices with degree pair (1, 0) and two with (0, 1): each tie
+        // group contributes its own factorial, multiplied together.
+        let g = Graph::from_adjacency_matrix(vec![
+            vec![0, 0, 1, 0],
+            vec![0, 0, 0, 1],
+            vec![0, 0, 0, 0],
+            vec![0, 0, 0, 0],
+        ]);
+        assert_eq!(
+            estimate_automorphisms(&g),
+            (factorial(2) * factorial(2)) as usize
+        );
+    }
+
+    #[test]
+    fn test_automorphisms_of_a_directed_4_cycle_is_exactly_its_4_rotations() {
+        let g = Graph::from_adjacency_matrix(vec![
+            vec![0, 1, 0, 0],
+            vec![0, 0, 1, 0],
+            vec![0, 0, 0, 1],
+            vec![1, 0, 0, 0],
+        ]);
+
+        let mut autos = automorphisms(&g);
+        autos.sort();
+        let mut expected = vec![
+            vec![0, 1, 2, 3],
+            vec![1, 2, 3, 0],
+            vec![2, 3, 0, 1],
+            vec![3, 0, 1, 2],
+        ];
+        expected.sort();
+        assert_eq!(autos, expected);
+    }
+
+    #[test]
+    fn test_automorphisms_of_an_edgeless_graph_is_every_permutation() {
+        let g = Graph::from_adjacency_matrix(vec![vec![0; 3]; 3]);
+        assert_eq!(automorphisms(&g).len(), factorial(3) as usize);
+    }
+
+    #[test]
+    fn test_fast_non_isomorphism_check_catches_different_degree_sequences() {
+        let g = Graph::from_adjacency_matrix(vec![vec![0, 1, 0], vec![0, 0, 1], vec![1, 0, 0]]);
+        let h = Graph::from_adjacency_matrix(vec![vec![0, 1, 1], vec![0, 0, 0], vec![0, 0, 0]]);
+        assert!(fast_non_isomorphism_check(&g, &h));
+    }
+
+    #[test]
+    fn test_fast_non_isomorphism_check_catches_different_triangle_counts() {
+        // K(3,3) and the triangular prism are both undirected, 3-regular,
+        // 6-vertex graphs with the same degree sequence, but K(3,3) is
+        // bipartite (0 triangles) while the prism has 2.
+        let mut k33 = vec![vec![0; 6]; 6];
+        for (i, j) in (0..3).cartesian_product(3..6) {
+            k33[i][j] = 1;
+            k33[j][i] = 1;
+        }
+        let k33 = Graph::from_adjacency_matrix(k33);
+
+        let mut prism = vec![vec![0; 6]; 6];
+        for &(u, v) in &[
+            (0, 1),
+            (1, 2),
+            (2, 0),
+            (3, 4),
+            (4, 5),
+            (5, 3),
+            (0, 3),
+            (1, 4),
+            (2, 5),
+        ] {
+            prism[u][v] = 1;
+            prism[v][u] = 1;
+        }
+        let prism = Graph::from_adjacency_matrix(prism);
+
+        assert_eq!(
+            k33.weighted_degree_sequence(),
+            prism.weighted_degree_sequence()
+        );
+        assert!(fast_non_isomorphism_check(&k33, &prism));
+    }
+
+    #[test]
+    fn test_fast_non_isomorphism_check_returns_false_for_relabeled_isomorphic_graphs() {
+        let g = Graph::from_adjacency_matrix(vec![vec![0, 1, 0], vec![0, 0, 1], vec![1, 0, 0]]);
+        // Same cycle, relabeled.
+        let h = Graph::from_adjacency_matrix(vec![vec![0, 0, 1], vec![1, 0, 0], vec![0, 1, 0]]);
+        assert!(!fast_non_isomorphism_check(&g, &h));
+    }
+
+    #[test]
+    fn test_erdos_gallai_check_accepts_the_complete_graphs_uniform_degree_sequence() {
+        // The complete directed graph on n vertices has out-degree = in-degree
+        // = n - 1 for every vertex, and is a witness realizing that sequence.
+        let n = 5;
+        let out_degrees = vec![n - 1; n];
+        let in_degrees = vec![n - 1; n];
+        assert!(erdos_gallai_check(&out_degrees, &in_degrees));
+    }
+
+    #[test]
+    fn test_erdos_gallai_check_rejects_a_mismatched_total_degree() {
+        let out_degrees = vec![2, 2, 2];
+        let in_degrees = vec![1, 1, 1];
+        assert!(!erdos_gallai_check(&out_degrees, &in_degrees));
+    }
+
+    #[test]
+    fn test_erdos_gallai_check_rejects_a_degree_too_large_for_a_simple_graph() {
+        // 3 vertices can have out-degree at most 2 each (no self-loops, no
+        // parallel edges), so an out-degree of 3 anywhere is unrealizable
+        // even though the totals match.
+        let out_degrees = vec![3, 1, 1];
+        let in_degrees = vec![1, 2, 2];
+        assert!(!erdos_gallai_check(&out_degrees, &in_degrees));
+    }
+
+    #[test]
+    fn test_degree_constrained_graph_matches_the_requested_sequence_exactly() {
+        let out_degrees = vec![2, 1, 0, 3];
+        let in_degrees = vec![1, 2, 2, 1];
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+
+        let g = degree_constrained_graph(&out_degrees, &in_degrees, &mut rng)
+            .expect("matching totals should always be realizable as a multigraph");
+
+        for v in 0..out_degrees.len() {
+            let actual_out: usize = (0..g.num_vertices()).map(|w| g.get_edge(v, w)).sum();
+            let actual_in: usize = (0..g.num_vertices()).map(|w| g.get_edge(w, v)).sum();
+            assert_eq!(actual_out, out_degrees[v]);
+            assert_eq!(actual_in, in_degrees[v]);
+        }
+    }
+
+    #[test]
+    fn test_degree_constrained_graph_returns_none_for_a_mismatched_total_degree() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        assert!(degree_constrained_graph(&[2, 2, 2], &[1, 1, 1], &mut rng).is_none());
+    }
+}
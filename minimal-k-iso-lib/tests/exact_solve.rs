@@ -0,0 +1,92 @@
+//! Integration tests for `algorithms::exact::solve_exact`, covering the same
+//! small instances `exact_solver`'s own unit tests exercise -- but through
+//! the public library API, with no process to spawn.
+
+use minimal_k_iso_lib::algorithms::exact::{
+    solve_exact, ExactOptions, NoOpProgressSink, SolveError,
+};
+use minimal_k_iso_lib::cost::Objective;
+use minimal_k_iso_lib::Graph;
+
+/// G = a directed triangle, H = a directed 5-cycle: every vertex in both has
+/// out-degree 1, so `P(5, 3) = 60` distinct mappings exist, giving a
+/// convenient number of divisors to pick `k` from.
+fn triangle_into_five_cycle() -> (Graph, Graph) {
+    let g = Graph::from_adjacency_matrix(vec![vec![0, 1, 0], vec![0, 0, 1], vec![1, 0, 0]]);
+    let h = Graph::from_adjacency_matrix(vec![
+        vec![0, 1, 0, 0, 0],
+        vec![0, 0, 1, 0, 0],
+        vec![0, 0, 0, 1, 0],
+        vec![0, 0, 0, 0, 1],
+        vec![1, 0, 0, 0, 0],
+    ]);
+    (g, h)
+}
+
+#[test]
+fn solve_exact_finds_the_known_optimal_cost_on_the_triangle_into_five_cycle() {
+    let (g, h) = triangle_into_five_cycle();
+
+    let solution = solve_exact(&g, &h, 2, &ExactOptions::default(), &mut NoOpProgressSink)
+        .expect("2 <= 60 available mappings");
+
+    assert_eq!(solution.mappings.len(), 2);
+    assert_eq!(
+        solution.cost,
+        Objective::TotalEdges.evaluate(&solution.edge_map)
+    );
+}
+
+#[test]
+fn solve_exact_uses_every_mapping_when_k_equals_the_full_pool() {
+    let g = Graph::from_adjacency_matrix(vec![vec![0, 1, 0], vec![0, 0, 1], vec![1, 0, 0]]);
+    let h = Graph::from_adjacency_matrix(vec![vec![0; 5]; 5]);
+
+    let all_mappings = minimal_k_iso_lib::mapping::find_all_mappings(&g, &h);
+    let k = all_mappings.len();
+
+    let solution = solve_exact(&g, &h, k, &ExactOptions::default(), &mut NoOpProgressSink)
+        .expect("k == available mappings is always feasible");
+
+    assert_eq!(solution.mappings.len(), k);
+}
+
+#[test]
+fn solve_exact_is_infeasible_when_k_exceeds_every_mapping() {
+    let g = Graph::from_adjacency_matrix(vec![vec![0, 1, 0], vec![0, 0, 1], vec![1, 0, 0]]);
+    let h = Graph::from_adjacency_matrix(vec![vec![0; 5]; 5]);
+
+    let all_mappings = minimal_k_iso_lib::mapping::find_all_mappings(&g, &h);
+    let k = all_mappings.len() + 1;
+
+    let err = solve_exact(&g, &h, k, &ExactOptions::default(), &mut NoOpProgressSink).unwrap_err();
+
+    assert_eq!(
+        err,
+        SolveError::InsufficientMappings {
+            needed: k,
+            available: all_mappings.len(),
+        }
+    );
+}
+
+#[test]
+fn solve_exact_respects_the_max_edge_multiplicity_objective() {
+    // G has two edges that can't both land on the same H edge without one of
+    // them costing more than the other under MaxEdgeMultiplicity, so the
+    // two objectives can pick different mapping sets on the same instance.
+    let g = Graph::from_adjacency_matrix(vec![vec![0, 2, 0], vec![0, 0, 3], vec![0, 0, 0]]);
+    let h = Graph::from_adjacency_matrix(vec![vec![0; 3]; 3]);
+
+    let options = ExactOptions {
+        objective: Objective::MaxEdgeMultiplicity,
+        ..ExactOptions::default()
+    };
+    let solution =
+        solve_exact(&g, &h, 1, &options, &mut NoOpProgressSink).expect("1 mapping is feasible");
+
+    assert_eq!(
+        solution.cost,
+        Objective::MaxEdgeMultiplicity.evaluate(&solution.edge_map)
+    );
+}
@@ -0,0 +1,3399 @@
+use clap::{Parser, ValueEnum};
+use itertools::Itertools;
+use minimal_k_iso_lib::{
+    augmentation::{min_vertex_augmentation, pad_host_to_pattern_size},
+    cost::{
+        calculate_edge_map, calculate_edge_map_with_semantics, calculate_edit_map,
+        compute_edge_delta, feasible_under_budget, marginal_cost, sharing_stats,
+        EdgeMapAccumulator, MergeSemantics, Objective, Solution,
+    },
+    mapping::{
+        count_satisfying_mappings, enumerate_range, find_all_mappings, find_all_mappings_undirected,
+    },
+    output::write_edge_list,
+    parser::{parse_input_file, parse_stdin},
+    utils::{automorphisms, estimate_automorphisms, BinomialTable},
+    Graph, Mapping,
+};
+use rayon::prelude::*;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeSet, BinaryHeap, HashMap, HashSet};
+use std::fs::File;
+use std::hash::{Hash, Hasher};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Type alias for edge map: (source, target) -> edge count
+type EdgeMap = HashMap<(usize, usize), usize>;
+
+/// Type alias for the result of the exact algorithm
+type SolutionResult = (usize, EdgeMap, Vec<Mapping>);
+
+/// Selects an `Objective` from the CLI. `WeightedTotal` needs a weight map
+/// that has no natural CLI representation, so it's only reachable by
+/// constructing `Objective` directly from library code.
+#[derive(ValueEnum, Clone, Copy, Debug, Default)]
+enum ObjectiveArg {
+    #[default]
+    TotalEdges,
+    MaxEdgeMultiplicity,
+}
+
+impl From<ObjectiveArg> for Objective {
+    fn from(arg: ObjectiveArg) -> Self {
+        match arg {
+            ObjectiveArg::TotalEdges => Objective::TotalEdges,
+            ObjectiveArg::MaxEdgeMultiplicity => Objective::MaxEdgeMultiplicity,
+        }
+    }
+}
+
+/// How to restrict the chosen mappings' H vertex sets relative to each
+/// other. Only one kind of restriction exists today, but this is a
+/// `ValueEnum` rather than a plain `bool` flag so a future kind (e.g.
+/// edge-disjoint) has somewhere to go without renaming `--disjoint`.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum DisjointArg {
+    /// No two chosen mappings may share an H vertex, so a single host
+    /// vertex's failure can affect at most one of the k embeddings.
+    Vertices,
+}
+
+/// Selects a `MergeSemantics` from the CLI.
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum MergeSemanticsArg {
+    /// A single added unit of capacity on an edge can serve every mapping
+    /// that needs it, so the edge map charges the maximum per-edge demand
+    /// across mappings. The default.
+    #[default]
+    Shared,
+    /// Each mapping consumes its own dedicated capacity on an edge, so the
+    /// edge map charges the sum of every mapping's demand on it.
+    Dedicated,
+}
+
+impl From<MergeSemanticsArg> for MergeSemantics {
+    fn from(arg: MergeSemanticsArg) -> Self {
+        match arg {
+            MergeSemanticsArg::Shared => MergeSemantics::Shared,
+            MergeSemanticsArg::Dedicated => MergeSemantics::Dedicated,
+        }
+    }
+}
+
+/// Which variant of the problem to solve.
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum ModeArg {
+    /// Add edges to H, minimizing the chosen objective. The default.
+    #[default]
+    EdgeExtension,
+    /// Add vertices (with freely-chosen edges) to H, minimizing how many are
+    /// added. See `augmentation::min_vertex_augmentation`.
+    VertexAugmentation,
+}
+
+/// Exact Solver for Minimal k-Isomorphic Subgraph Extension
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// Path to the input file containing graph descriptions. Required unless
+    /// `--stdin` is given, or optional and defaulting to stdin if omitted.
+    #[arg(short, long)]
+    input: Option<PathBuf>,
+
+    /// Read the graph descriptions from stdin instead of `--input`, for
+    /// piping input directly from an earlier command. Times out after 10
+    /// seconds if nothing arrives (see `parser::parse_stdin`). Implied by
+    /// omitting `--input` entirely.
+    #[arg(long)]
+    stdin: bool,
+
+    /// Number of distinct isomorphic mappings required (k)
+    #[arg(short, long)]
+    k: usize,
+
+    /// Restrict the candidate mapping pool to the slice `start:count` of the
+    /// full enumeration (see `mapping::enumerate_range`). Useful for running
+    /// the search across several batch-system time slots, but the result for
+    /// a single slice is only guaranteed optimal *within that slice* — merge
+    /// the per-slice results yourself and keep the lowest-cost one. Ignored
+    /// in `vertex-augmentation` mode.
+    #[arg(long, value_name = "start:count")]
+    mapping_range: Option<String>,
+
+    /// Which scalar objective to minimize. Ignored in `vertex-augmentation`
+    /// mode, which only minimizes the number of added vertices.
+    #[arg(long, value_enum, default_value_t = ObjectiveArg::TotalEdges)]
+    objective: ObjectiveArg,
+
+    /// Which variant of the problem to solve.
+    #[arg(long, value_enum, default_value_t = ModeArg::EdgeExtension)]
+    mode: ModeArg,
+
+    /// Also report H edges that are safe to prune (weight exceeding what the
+    /// optimal mapping set demands by more than `--keep-threshold`), as an
+    /// edit set rather than an addition-only extension. The optimal mapping
+    /// set itself is still chosen by `--objective` alone; this only affects
+    /// what gets reported. Ignored in `vertex-augmentation` mode.
+    #[arg(long)]
+    allow_deletions: bool,
+
+    /// Minimum excess weight on an H edge before it's reported as a
+    /// deletion candidate. Only used with `--allow-deletions`.
+    #[arg(long, default_value_t = 0)]
+    keep_threshold: usize,
+
+    /// Instead of minimizing cost, just check whether `k` mappings are
+    /// achievable within this many added edges (see
+    /// `cost::feasible_under_budget`). Much faster than the full search
+    /// since it stops at the first feasible combination. Ignored in
+    /// `vertex-augmentation` mode.
+    #[arg(long)]
+    budget: Option<usize>,
+
+    /// Treat G and H as undirected (see `Graph::as_undirected`): edges are
+    /// symmetrized before matching, and the search skips mappings that are
+    /// just a relabeling of one already tried (see
+    /// `mapping::find_all_mappings_undirected`). Ignored when
+    /// `--mapping-range` restricts the candidate pool, since the range's
+    /// indices are defined over the full (directed) enumeration.
+    #[arg(long)]
+    undirected: bool,
+
+    /// When H has fewer vertices than G, pad it with isolated vertices up to
+    /// G's size instead of reporting no solution (see
+    /// `augmentation::pad_host_to_pattern_size`). Every padded vertex is
+    /// always used by the resulting mappings and contributes `--vertex-cost`
+    /// to the reported total. Ignored in `vertex-augmentation` mode, which
+    /// already searches over added vertices itself; a no-op when H already
+    /// has at least as many vertices as G.
+    #[arg(long)]
+    allow_vertex_additions: bool,
+
+    /// Cost charged per vertex added to H by `--allow-vertex-additions`.
+    #[arg(long, default_value_t = 1)]
+    vertex_cost: usize,
+
+    /// Stop the branch-and-bound search after this many seconds and report
+    /// the best solution found so far instead of running to completion. The
+    /// greedy seed computed before the search starts (see
+    /// `greedy_incumbent`) already guarantees a feasible result as soon as
+    /// one exists, so there is always something to report once `k` mappings
+    /// are available at all; the reported solution is just not guaranteed
+    /// optimal in that case. Ignored in `vertex-augmentation` mode and by
+    /// `--budget`, neither of which run this search.
+    #[arg(long)]
+    timeout: Option<u64>,
+
+    /// Path to periodically write the best-so-far solution to while the
+    /// search is still running (see `write_checkpoint_file`), so a preempted
+    /// or killed run leaves something behind to `--resume` from. Ignored in
+    /// `vertex-augmentation` mode and by `--budget`.
+    #[arg(long)]
+    checkpoint: Option<PathBuf>,
+
+    /// Minimum number of seconds between checkpoint writes to `--checkpoint`.
+    /// Ignored unless `--checkpoint` is set.
+    #[arg(long)]
+    checkpoint_interval: Option<u64>,
+
+    /// Resume an interrupted run: read the solution recorded in a previous
+    /// checkpoint (or completed solution) file -- its "Total Cost (edges
+    /// added)" and "Mapping N: [...]" lines -- and seed the search with it
+    /// alongside the usual greedy seed, so a search that never finds
+    /// anything better still reports that solution instead of nothing. Also
+    /// skips every first-index branch the checkpoint's "Completed Through
+    /// First Index" watermark already accounts for, so a resumed run
+    /// doesn't re-search work the interrupted run already finished. The
+    /// instance itself (G, H, k, `--objective`) is hashed into the
+    /// checkpoint's "Instance Hash" line and checked against this run's;
+    /// resuming against a different instance than the one checkpointed is
+    /// rejected outright rather than silently seeding a meaningless
+    /// solution -- point this back at the same invocation's own checkpoint
+    /// file.
+    #[arg(long)]
+    resume: Option<PathBuf>,
+
+    /// Refuse to start the branch-and-bound search when the number of
+    /// k-combinations to consider exceeds this many, printing the estimate
+    /// and a calibrated runtime projection instead of silently churning.
+    /// `num_combinations` can reach the 10^18 range on a large instance,
+    /// which is antisocial to launch unannounced on a shared machine.
+    /// Bypass with `--force`. Ignored in `vertex-augmentation` mode and by
+    /// `--budget`, neither of which run the full branch-and-bound search.
+    #[arg(long, default_value_t = 1_000_000_000)]
+    max_combinations: usize,
+
+    /// Bypass the `--max-combinations` guard and start the search regardless
+    /// of its estimated size.
+    #[arg(long)]
+    force: bool,
+
+    /// Instead of running the branch-and-bound search, write the problem as
+    /// an LP-format ILP model to this path and exit (see `write_ilp_model`),
+    /// for handing hard instances to an external MIP solver like Gurobi.
+    /// Ignored in `vertex-augmentation` mode and by `--budget`, neither of
+    /// which build a mapping pool to export.
+    #[arg(long)]
+    export_ilp: Option<PathBuf>,
+
+    /// Restrict the exported ILP's candidate mappings to the `m`
+    /// cheapest-looking ones (see `write_ilp_model`) instead of the full
+    /// pool. The full pool can make the model too large for a solver to
+    /// even load; this is a heuristic restriction, not a guarantee that the
+    /// true optimum survives it. Only used with `--export-ilp`.
+    #[arg(long)]
+    export_ilp_max_candidates: Option<usize>,
+
+    /// Restrict the candidate pool to mappings that send G's vertex 0 to the
+    /// lexicographically smallest H vertex any mapping sends it to, a
+    /// canonical choice that rules out every mapping set reachable from a
+    /// kept one by relabeling G's vertices under an automorphism of G.
+    /// Heuristic, not exact: `utils::estimate_automorphisms` (printed
+    /// alongside this flag's effect) is only a rough estimate of `G`'s true
+    /// automorphism group, and this fixes just one vertex's image rather
+    /// than breaking every symmetry, so it narrows but does not eliminate
+    /// redundant exploration. Ignored when `--mapping-range` already
+    /// restricts the pool to a raw index slice, and in `vertex-augmentation`
+    /// mode, which doesn't build a mapping pool at all.
+    #[arg(long)]
+    symmetry_breaking: bool,
+
+    /// Restrict the candidate pool to one representative mapping per orbit
+    /// of `G`'s automorphism group (see `utils::automorphisms`), on a
+    /// firmer footing than `--symmetry-breaking`'s single-vertex heuristic:
+    /// composing a mapping with any automorphism of G produces another
+    /// mapping with the identical image set *and* the identical induced
+    /// edge-demand map onto H, so every mapping but the lexicographically
+    /// smallest in each orbit is redundant on its own and can be dropped
+    /// before combinations are even formed, shrinking the pool by up to
+    /// `|Aut(G)|`. Not lossless for the *combination* search, though: a
+    /// combination that deliberately reuses several mappings from the same
+    /// orbit (cheap under `MergeSemantics::Shared`, since orbit-mates
+    /// demand the identical edge set) loses access to that option once the
+    /// orbit is collapsed to a single pool entry. Combinable with
+    /// `--symmetry-breaking`, though the two overlap in what they cut.
+    /// Ignored when `--mapping-range` already restricts the pool to a raw
+    /// index slice, and in `vertex-augmentation` mode, which doesn't build
+    /// a mapping pool at all.
+    #[arg(long)]
+    mod_aut: bool,
+
+    /// Require the chosen mappings' H vertex sets to be pairwise disjoint
+    /// (see `DisjointArg`), for fault tolerance: a single host vertex
+    /// failure can then affect at most one of the k embeddings. Checked via
+    /// a cheap bitset intersection per pair of candidate mappings inside the
+    /// search. Infeasible whenever `k * g.num_vertices() > h.num_vertices()`
+    /// (not enough distinct H vertices to go around), which is detected and
+    /// reported before the search starts rather than discovered by it
+    /// coming up empty. Ignored in `vertex-augmentation` mode and by
+    /// `--budget`, neither of which run the mapping search this restricts.
+    #[arg(long, value_enum)]
+    disjoint: Option<DisjointArg>,
+
+    /// How multiple mappings' demand on the same added edge combines (see
+    /// `MergeSemantics`): `shared`, the default, charges the maximum demand
+    /// across mappings, as if a single added unit of capacity could serve
+    /// all of them; `dedicated` charges the sum instead, for settings where
+    /// each embedding needs capacity of its own. Ignored in
+    /// `vertex-augmentation` mode and by `--budget`, neither of which build
+    /// an edge map from multiple mappings.
+    #[arg(long, value_enum, default_value_t = MergeSemanticsArg::Shared)]
+    edge_sharing: MergeSemanticsArg,
+
+    /// After finding the optimal cost, also enumerate every other
+    /// k-combination of mappings that ties it, instead of reporting only
+    /// the one combination the search happened to land on -- useful for
+    /// sensitivity analysis, where which *set* of mappings gets picked
+    /// matters as much as the cost itself. Re-enumerates the same candidate
+    /// pool a second time (already bounded by `--max-combinations`, so this
+    /// costs roughly what the search itself did) and reports every tie's
+    /// shared-edge stats alongside it, capped at `--all-optimal-cap` ties.
+    /// Ignored in `vertex-augmentation` mode and by `--budget`, neither of
+    /// which search over multiple mapping combinations; meaningless (and
+    /// skipped, with a warning) when `--timeout` cuts the search off before
+    /// the reported cost is proven optimal.
+    #[arg(long)]
+    all_optimal: bool,
+
+    /// Maximum number of tied-optimal combinations `--all-optimal` collects
+    /// before stopping and reporting truncation rather than materializing
+    /// every tie on an instance with far more of them than anyone could
+    /// use. Ignored unless `--all-optimal` is set.
+    #[arg(long, default_value_t = 1000)]
+    all_optimal_cap: usize,
+
+    /// Instead of (or alongside) reporting only the optimal combination,
+    /// also rank and report the `N` cheapest combinations overall via a
+    /// bounded max-heap kept across a second re-enumeration pass -- useful
+    /// when the cheapest combination sometimes violates a soft constraint
+    /// that isn't encoded in `--objective`, and a human wants to pick among
+    /// the next-best options instead. Distinct from `--all-optimal`, which
+    /// only enumerates ties *at* the optimum; this ranks across costs.
+    /// Unset (the default) disables it. Ignored in `vertex-augmentation`
+    /// mode and by `--budget`, neither of which search over multiple
+    /// mapping combinations; meaningless (and skipped, with a warning) when
+    /// `--timeout` cuts the search off before the reported cost is proven
+    /// optimal.
+    #[arg(long)]
+    top_n: Option<usize>,
+
+    /// Number of threads the branch-and-bound search's rayon fan-out uses.
+    /// Builds a scoped `rayon::ThreadPoolBuilder` pool instead of relying on
+    /// the global pool, so this can be set per-run without the
+    /// `RAYON_NUM_THREADS` env var (which a job wrapper may not be able to
+    /// set) and without affecting any other rayon-using process sharing the
+    /// machine. `0` (the default) keeps the existing behavior of using the
+    /// global pool's thread count. Ignored in `vertex-augmentation` mode and
+    /// by `--budget` and `--export-ilp`, none of which run the parallel
+    /// search.
+    #[arg(long, default_value_t = 0)]
+    threads: usize,
+
+    /// Also write G's edges, H's edges, and the computed extension as a
+    /// sparse `u\tv\tweight` TSV to this path (see
+    /// `output::write_edge_list`), instead of only the dense adjacency
+    /// matrices printed to stdout.
+    #[arg(long)]
+    output_edge_list: Option<PathBuf>,
+}
+
+/// `mapping`'s image as a bitset over `0..n_h`, packed into 64-bit words, so
+/// two mappings' images can be checked for a shared H vertex with a handful
+/// of word-wise ANDs instead of a `HashSet` intersection. See `--disjoint`.
+fn mapping_image_bitset(mapping: &Mapping, n_h: usize) -> Vec<u64> {
+    let mut bitset = vec![0u64; n_h.div_ceil(64)];
+    for &v in mapping {
+        bitset[v / 64] |= 1u64 << (v % 64);
+    }
+    bitset
+}
+
+/// Whether `mapping` is already the lexicographically smallest mapping in
+/// its orbit under `automorphisms`, i.e. `mapping <= mapping ∘ σ` for every
+/// `σ`. `automorphisms` always includes the identity permutation, so this is
+/// well defined (and always `true`) even when `G` has no nontrivial
+/// automorphisms. See `--mod-aut`.
+fn is_canonical_under_automorphisms(mapping: &Mapping, automorphisms: &[Vec<usize>]) -> bool {
+    automorphisms.iter().all(|sigma| {
+        let composed: Mapping = sigma.iter().map(|&u| mapping[u]).collect();
+        composed >= *mapping
+    })
+}
+
+/// Whether `a` and `b` (same-length bitsets from `mapping_image_bitset`)
+/// share any set bit, i.e. whether the two mappings' images overlap.
+fn bitsets_intersect(a: &[u64], b: &[u64]) -> bool {
+    a.iter().zip(b).any(|(x, y)| x & y != 0)
+}
+
+/// Parse a `start:count` CLI value into its `(u128, u64)` parts.
+fn parse_mapping_range(spec: &str) -> Result<(u128, u64), String> {
+    let (start, count) = spec
+        .split_once(':')
+        .ok_or_else(|| format!("invalid --mapping-range '{}', expected start:count", spec))?;
+    let start = start
+        .parse::<u128>()
+        .map_err(|e| format!("invalid start in --mapping-range: {}", e))?;
+    let count = count
+        .parse::<u64>()
+        .map_err(|e| format!("invalid count in --mapping-range: {}", e))?;
+    Ok((start, count))
+}
+
+/// A fast, deterministic greedy construction used only to seed the
+/// branch-and-bound search below with a reasonably tight starting incumbent:
+/// repeatedly commit whichever unused mapping adds the least to the cost
+/// under `objective` given what's already committed (see
+/// `cost::marginal_cost`). Not guaranteed optimal on its own — the exact
+/// search below still explores every combination the bound can't rule out.
+fn greedy_incumbent(
+    all_mappings: &[Mapping],
+    g: &Graph,
+    h: &Graph,
+    k: usize,
+    objective: &Objective,
+    disjoint: bool,
+    merge_semantics: MergeSemantics,
+) -> Option<SolutionResult> {
+    let mut committed = EdgeMap::new();
+    let mut used = vec![false; all_mappings.len()];
+    let mut chosen = Vec::with_capacity(k);
+    let mut used_h_vertices: HashSet<usize> = HashSet::new();
+
+    for _ in 0..k {
+        let (_, idx, increments) = (0..all_mappings.len())
+            .filter(|&i| !used[i])
+            .filter(|&i| !disjoint || all_mappings[i].iter().all(|v| !used_h_vertices.contains(v)))
+            .map(|i| {
+                let (_, increments) =
+                    marginal_cost(g, h, &committed, &all_mappings[i], merge_semantics);
+                (objective.evaluate(&increments), i, increments)
+            })
+            .min_by_key(|(cost, _, _)| *cost)?;
+
+        for (edge, weight) in increments {
+            *committed.entry(edge).or_insert(0) += weight;
+        }
+        used[idx] = true;
+        if disjoint {
+            used_h_vertices.extend(all_mappings[idx].iter().copied());
+        }
+        chosen.push(all_mappings[idx].clone());
+    }
+
+    Some((objective.evaluate(&committed), committed, chosen))
+}
+
+/// Times how long evaluating a small sample of k-combinations actually
+/// takes and extrapolates a full-search runtime from it, for the
+/// `--max-combinations` guard's error message. The branch-and-bound search
+/// itself prunes far more aggressively than this naive per-combination
+/// evaluation, so the real run is almost always faster than this projects --
+/// it's meant to convey scale, not to be a tight estimate.
+fn calibrate_projected_runtime(
+    all_mappings: &[Mapping],
+    g: &Graph,
+    h: &Graph,
+    k: usize,
+    objective: &Objective,
+    total_combinations: usize,
+) -> Duration {
+    const SAMPLE_SIZE: usize = 10_000;
+    let sample_size = SAMPLE_SIZE.min(total_combinations).max(1);
+
+    let start = Instant::now();
+    for combination in all_mappings.iter().combinations(k).take(sample_size) {
+        let edge_map = calculate_edge_map(g, h, &combination);
+        objective.evaluate(&edge_map);
+    }
+    let per_combination_secs = start.elapsed().as_secs_f64() / sample_size as f64;
+
+    let projected_secs = per_combination_secs * total_combinations as f64;
+    if projected_secs.is_finite() {
+        Duration::from_secs_f64(projected_secs)
+    } else {
+        Duration::MAX
+    }
+}
+
+/// Canonical form used to break cost ties between two equally-good results:
+/// each candidate's mapping list, sorted. `Mapping` is `Vec<usize>`, which
+/// has a natural lexicographic `Ord`, so sorting the outer list gives a
+/// representation that's independent of the order in which the search
+/// happened to assemble it — the same *set* of mappings always sorts to the
+/// same `Vec<Vec<usize>>`, regardless of which branch found it or in what
+/// order its indices were chosen.
+fn canonical_mappings(mappings: &[Mapping]) -> Vec<Mapping> {
+    let mut sorted = mappings.to_vec();
+    sorted.sort();
+    sorted
+}
+
+/// Orders two (possibly absent) candidate results by cost ascending, then by
+/// their canonical mapping form ascending. Used by `branch_and_bound_search`
+/// to reduce every parallel branch's local best into one deterministic
+/// overall winner, independent of the order branches happen to finish in.
+fn prefer(a: Option<SolutionResult>, b: Option<SolutionResult>) -> Option<SolutionResult> {
+    match (a, b) {
+        (None, other) | (other, None) => other,
+        (Some(a), Some(b)) => {
+            let key = |r: &SolutionResult| (r.0, canonical_mappings(&r.2));
+            if key(&b) < key(&a) {
+                Some(b)
+            } else {
+                Some(a)
+            }
+        }
+    }
+}
+
+/// Write a `--checkpoint` (or final) report of the best solution found so
+/// far: enough to `--resume` from (a grep-able "Total Cost (edges added): N"
+/// line and one "Mapping N: [...]" line per chosen mapping, plus an
+/// "Instance Hash" line and a "Completed Through First Index" watermark, see
+/// `read_resume_result`) and to sanity-check by eye, without the fuller
+/// adjacency-matrix/sharing-stats printing `main` does for the actual final
+/// report.
+fn write_checkpoint_file(
+    path: &PathBuf,
+    k: usize,
+    cost: usize,
+    mappings: &[Mapping],
+    elapsed: Duration,
+    instance_hash: u64,
+    first_watermark: usize,
+) -> io::Result<()> {
+    let mut file = File::create(path)?;
+    writeln!(file, "CHECKPOINT (NOT FINAL)")?;
+    writeln!(file, "k (required mappings): {}", k)?;
+    writeln!(file, "Time: {}ms", elapsed.as_millis())?;
+    writeln!(file, "Instance Hash: {}", instance_hash)?;
+    writeln!(file, "Completed Through First Index: {}", first_watermark)?;
+    writeln!(file, "Total Cost (edges added): {}", cost)?;
+    writeln!(file)?;
+    writeln!(file, "Mappings:")?;
+    for (i, mapping) in mappings.iter().enumerate() {
+        writeln!(file, "  Mapping {}: {:?}", i + 1, mapping)?;
+    }
+    Ok(())
+}
+
+/// A hash of everything `branch_and_bound_search` treats as "the instance"
+/// for `--resume` purposes: `g`, `h`, `k`, and `objective`. Written to every
+/// checkpoint and checked on `--resume` so a checkpoint from a different
+/// instance is rejected outright instead of silently seeding a meaningless
+/// resume (see `read_resume_result`). Doesn't cover flags that reshape the
+/// candidate pool itself (`--undirected`, `--mapping-range`,
+/// `--symmetry-breaking`, `--mod-aut`) -- point `--resume` back at the same
+/// invocation's own checkpoint and those stay fixed along with it.
+fn instance_hash(g: &Graph, h: &Graph, k: usize, objective: &Objective) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    g.adj.hash(&mut hasher);
+    h.adj.hash(&mut hasher);
+    k.hash(&mut hasher);
+    match objective {
+        Objective::TotalEdges => 0u8.hash(&mut hasher),
+        Objective::MaxEdgeMultiplicity => 1u8.hash(&mut hasher),
+        Objective::WeightedTotal(weights) => {
+            2u8.hash(&mut hasher);
+            let mut entries: Vec<(&(usize, usize), &usize)> = weights.iter().collect();
+            entries.sort();
+            entries.hash(&mut hasher);
+        }
+    }
+    hasher.finish()
+}
+
+/// Write G's edges, H's edges, and the extension `edge_map` to `path` as
+/// three TSV sections (see [`write_edge_list`]), each preceded by a `#`
+/// comment header naming it.
+fn write_edge_list_report(
+    path: &PathBuf,
+    g: &Graph,
+    h: &Graph,
+    edge_map: &EdgeMap,
+) -> io::Result<()> {
+    let mut file = File::create(path)?;
+    writeln!(file, "# G edges (u\tv\tweight)")?;
+    write_edge_list(
+        &mut file,
+        &compute_edge_delta(&Graph::new(g.num_vertices()), g),
+    )?;
+    writeln!(file, "# H edges (u\tv\tweight)")?;
+    write_edge_list(
+        &mut file,
+        &compute_edge_delta(&Graph::new(h.num_vertices()), h),
+    )?;
+    writeln!(file, "# Added edges (u\tv\tweight)")?;
+    write_edge_list(&mut file, edge_map)?;
+    Ok(())
+}
+
+/// Parse one `{:?}`-formatted `Vec<usize>` (e.g. `[0, 1, 2]`) as written by
+/// `write_checkpoint_file`'s "Mapping N: ..." lines. Returns `None` if any
+/// element fails to parse.
+fn parse_mapping_line(list: &str) -> Option<Mapping> {
+    list.trim_start_matches('[')
+        .trim_end_matches(']')
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|n| n.parse::<usize>().ok())
+        .collect()
+}
+
+/// Writes `g`/`h`'s minimal-`k`-extension problem as an LP-format ILP model
+/// to `path`, for handing hard instances to an external MIP solver (e.g.
+/// Gurobi) instead of running the branch-and-bound search below.
+///
+/// One binary `pick_m` variable per candidate mapping and one integer
+/// `added_x_y` variable per host edge `(x, y)` that some candidate's image
+/// could ever need, linked by the big-M constraint
+/// `added[x][y] + h[x][y] >= g[u][v] - M*(1 - pick[m])`
+/// for every candidate mapping `m` and every pattern edge `(u, v)` it sends
+/// to `(x, y) = (m[u], m[v])`. `sum(pick) = k` forces exactly `k` mappings
+/// to be chosen; the objective minimizes `sum(added)`.
+///
+/// `M` is the largest edge weight anywhere in `g` — the largest shortfall
+/// any single constraint could ever need waived. Any `M` at least that
+/// large keeps an unpicked mapping's constraints non-binding (their
+/// right-hand side collapses to `<= 0`) regardless of what `h[x][y]`
+/// happens to be, since `g[u][v] - M <= 0` for every pattern edge weight.
+///
+/// If `max_candidates` is `Some`, restricts the candidate pool to the `m`
+/// cheapest-looking mappings first — standalone cost against `h` (see
+/// `calculate_edge_map`), ignoring any sharing with whatever else ends up
+/// picked. This keeps the model a sane size on instances with a huge
+/// mapping pool, but it's a heuristic: a mapping that looks expensive alone
+/// can still belong to the true optimal set if it shares edges cheaply with
+/// others, so the exported model's optimum can be worse than the
+/// unrestricted problem's.
+fn write_ilp_model(
+    path: &PathBuf,
+    all_mappings: &[Mapping],
+    g: &Graph,
+    h: &Graph,
+    k: usize,
+    max_candidates: Option<usize>,
+) -> io::Result<()> {
+    let mut candidate_indices: Vec<usize> = (0..all_mappings.len()).collect();
+    if let Some(m) = max_candidates {
+        candidate_indices.sort_by_key(|&i| {
+            let edge_map = calculate_edge_map(g, h, std::slice::from_ref(&all_mappings[i]));
+            Objective::TotalEdges.evaluate(&edge_map)
+        });
+        candidate_indices.truncate(m);
+    }
+
+    let n_g = g.num_vertices();
+    let pattern_edges: Vec<(usize, usize, usize)> = (0..n_g)
+        .flat_map(|u| (0..n_g).map(move |v| (u, v)))
+        .filter_map(|(u, v)| {
+            let weight = g.get_edge(u, v);
+            (weight > 0).then_some((u, v, weight))
+        })
+        .collect();
+
+    let big_m = pattern_edges.iter().map(|&(_, _, w)| w).max().unwrap_or(0);
+
+    let mut host_edges: std::collections::BTreeSet<(usize, usize)> =
+        std::collections::BTreeSet::new();
+    for &i in &candidate_indices {
+        let mapping = &all_mappings[i];
+        for &(u, v, _) in &pattern_edges {
+            host_edges.insert((mapping[u], mapping[v]));
+        }
+    }
+
+    let mut file = File::create(path)?;
+    writeln!(
+        file,
+        "\\ ILP model for minimal-{}-isomorphic-subgraph-extension",
+        k
+    )?;
+    writeln!(
+        file,
+        "\\ {} candidate mappings, {} host edges, big-M = {}",
+        candidate_indices.len(),
+        host_edges.len(),
+        big_m
+    )?;
+    writeln!(file)?;
+
+    writeln!(file, "Minimize")?;
+    let objective_terms = host_edges
+        .iter()
+        .map(|(x, y)| format!("added_{}_{}", x, y))
+        .collect::<Vec<_>>()
+        .join(" + ");
+    writeln!(file, " obj: {}", objective_terms)?;
+    writeln!(file)?;
+
+    writeln!(file, "Subject To")?;
+    for (m_idx, &i) in candidate_indices.iter().enumerate() {
+        let mapping = &all_mappings[i];
+        for &(u, v, weight) in &pattern_edges {
+            let (x, y) = (mapping[u], mapping[v]);
+            let h_weight = h.get_edge(x, y);
+            let rhs = weight as i64 - big_m as i64 + h_weight as i64;
+            writeln!(
+                file,
+                " edge_m{}_{}_{}: added_{}_{} + {} pick_{} >= {}",
+                m_idx, x, y, x, y, big_m, m_idx, rhs
+            )?;
+        }
+    }
+    let pick_terms = (0..candidate_indices.len())
+        .map(|m_idx| format!("pick_{}", m_idx))
+        .collect::<Vec<_>>()
+        .join(" + ");
+    writeln!(file, " pick_count: {} = {}", pick_terms, k)?;
+    writeln!(file)?;
+
+    writeln!(file, "Binary")?;
+    for m_idx in 0..candidate_indices.len() {
+        writeln!(file, " pick_{}", m_idx)?;
+    }
+    writeln!(file)?;
+
+    writeln!(file, "General")?;
+    for (x, y) in &host_edges {
+        writeln!(file, " added_{}_{}", x, y)?;
+    }
+    writeln!(file)?;
+
+    writeln!(file, "End")?;
+    Ok(())
+}
+
+/// `--export-ilp` entry point: build the candidate mapping pool the same
+/// way the exact search would, then hand it to `write_ilp_model`.
+fn run_export_ilp(
+    g: &Graph,
+    h: &Graph,
+    k: usize,
+    undirected: bool,
+    path: &PathBuf,
+    max_candidates: Option<usize>,
+) {
+    println!("Finding all possible mappings from G to H...");
+    let all_mappings = if undirected {
+        find_all_mappings_undirected(g, h)
+    } else {
+        find_all_mappings(g, h)
+    };
+    println!("Found {} total mappings", all_mappings.len());
+
+    match write_ilp_model(path, &all_mappings, g, h, k, max_candidates) {
+        Ok(()) => println!("Wrote ILP model to {}", path.display()),
+        Err(e) => {
+            eprintln!("Error writing ILP model to {}: {}", path.display(), e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Reconstruct the best solution recorded in a checkpoint or completed
+/// solution file written by `write_checkpoint_file`: its "Total Cost (edges
+/// added): N" line and every "Mapping N: [...]" line, with the edge map
+/// recomputed from the recovered mappings via `calculate_edge_map` (the file
+/// itself doesn't store it). Also verifies the file's "Instance Hash" line
+/// against `instance_hash(g, h, k, objective)` before trusting anything else
+/// in it, and recovers its "Completed Through First Index" watermark.
+///
+/// - `Ok(None)`: the file can't be read, or doesn't contain a cost line and
+///   at least one mapping (a stale, incomplete, or nonexistent file) -- the
+///   caller falls back to a fresh run with a warning.
+/// - `Ok(Some((result, first_watermark)))`: the file matches this instance;
+///   `first_watermark` says how many of `branch_and_bound_search`'s `first`
+///   values (see its doc comment) are already fully accounted for.
+/// - `Err(reason)`: the file names a *different* instance, or predates
+///   instance hashing altogether -- resuming against the wrong instance
+///   would silently seed a meaningless solution, so this is a hard error
+///   instead of a silent fallback.
+fn read_resume_result(
+    path: &PathBuf,
+    g: &Graph,
+    h: &Graph,
+    k: usize,
+    objective: &Objective,
+) -> Result<Option<(SolutionResult, usize)>, String> {
+    let Ok(contents) = std::fs::read_to_string(path) else {
+        return Ok(None);
+    };
+
+    let Some(cost) = contents
+        .lines()
+        .find_map(|line| line.strip_prefix("Total Cost (edges added): "))
+        .and_then(|rest| rest.trim().parse::<usize>().ok())
+    else {
+        return Ok(None);
+    };
+
+    let mappings: Vec<Mapping> = contents
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("Mapping "))
+        .filter_map(|rest| rest.split_once(": "))
+        .filter_map(|(_, list)| parse_mapping_line(list))
+        .collect();
+
+    if mappings.is_empty() {
+        return Ok(None);
+    }
+
+    let recorded_hash: u64 = contents
+        .lines()
+        .find_map(|line| line.strip_prefix("Instance Hash: "))
+        .and_then(|rest| rest.trim().parse().ok())
+        .ok_or_else(|| {
+            format!(
+                "{} has no recorded instance hash (written before instance hashing existed, \
+                 or hand-edited); refusing to resume against it",
+                path.display()
+            )
+        })?;
+    let expected_hash = instance_hash(g, h, k, objective);
+    if recorded_hash != expected_hash {
+        return Err(format!(
+            "{} was checkpointed for a different instance (recorded hash {}, this run's is {}); \
+             refusing to resume against it -- point --resume back at this exact invocation's own \
+             checkpoint file",
+            path.display(),
+            recorded_hash,
+            expected_hash
+        ));
+    }
+
+    let first_watermark: usize = contents
+        .lines()
+        .find_map(|line| line.strip_prefix("Completed Through First Index: "))
+        .and_then(|rest| rest.trim().parse().ok())
+        .unwrap_or(0);
+
+    let chosen: Vec<&Mapping> = mappings.iter().collect();
+    let edge_map = calculate_edge_map(g, h, &chosen);
+    Ok(Some(((cost, edge_map, mappings), first_watermark)))
+}
+
+/// Recursively extend `chosen` (indices into `all_mappings`, strictly
+/// increasing) with mappings starting at `next_idx`, pushing each candidate
+/// into `accumulator` before recursing and popping it back out on return.
+/// Once `chosen` reaches length `k`, records the accumulator's cost as a new
+/// best if it beats `best_cost`.
+///
+/// `all_mappings` and `costs` must be sorted ascending by `costs[i]`, each
+/// mapping's own standalone cost (its cost under `objective` as if it were
+/// the only mapping chosen) — this is what lets the loop below both compute
+/// a branch-and-bound lower bound cheaply and `break` out entirely once that
+/// bound can no longer improve on the incumbent, instead of only skipping
+/// one candidate at a time. `nodes_visited` counts every mapping actually
+/// pushed into `accumulator`, for the branch-and-bound's own test coverage
+/// (see the module's tests).
+///
+/// `best_cost` and `best_result` are owned exclusively by the single branch
+/// of `branch_and_bound_search`'s parallel fan-out that called in here (see
+/// its doc comment): nothing outside that branch's own call tree ever reads
+/// or writes them mid-search, so a plain `&mut` pair — and a strict "only a
+/// strictly lower cost wins" rule — is enough to make each branch's result
+/// a deterministic function of its own, fixed traversal order. Breaking
+/// ties between *different* branches' results is `prefer`'s job, applied
+/// once after every branch has finished.
+///
+/// `best_checkpointed_cost`, unlike `best_cost`, *is* shared across every
+/// branch: checkpoints are a best-effort progress artifact for `--resume`,
+/// not part of the search's own correctness, so it's fine (and useful) for
+/// it to reflect whichever branch has found the best result so far,
+/// ratcheted down via compare-and-swap the same way a shared incumbent
+/// bound would be.
+///
+/// `stop` is set by `branch_and_bound_search`'s `--timeout` watcher thread;
+/// checked once per loop iteration (a single atomic load) and, once set,
+/// unwinds the recursion immediately, leaving `best_result` as whatever this
+/// branch had found so far.
+///
+/// `pruned_by_bound` counts every candidate index this call's loop skips via
+/// its `break` below, i.e. every combination that would have started with
+/// one of those indices and was never visited because the bound already
+/// ruled it out.
+///
+/// `disjoint_bitsets`, if set (see `--disjoint`), holds each candidate
+/// mapping's H-vertex image as a bitset in the same order as `all_mappings`;
+/// an `idx` whose image overlaps any already-`chosen` mapping's is skipped
+/// via `continue` rather than explored, since disjointness isn't correlated
+/// with the ascending cost order the `break` above relies on.
+#[allow(clippy::too_many_arguments)]
+fn search_combinations(
+    all_mappings: &[Mapping],
+    costs: &[usize],
+    g: &Graph,
+    h: &Graph,
+    k: usize,
+    next_idx: usize,
+    objective: &Objective,
+    accumulator: &mut EdgeMapAccumulator,
+    chosen: &mut Vec<usize>,
+    best_cost: &mut usize,
+    best_result: &mut Option<SolutionResult>,
+    nodes_visited: &AtomicUsize,
+    pruned_by_bound: &AtomicUsize,
+    disjoint_bitsets: Option<&[Vec<u64>]>,
+    checkpoint: &Option<(PathBuf, Duration)>,
+    best_checkpointed_cost: &AtomicUsize,
+    last_checkpoint: &Mutex<Instant>,
+    search_start: Instant,
+    stop: &AtomicBool,
+    completed_firsts: &Mutex<BTreeSet<usize>>,
+    instance_hash: u64,
+) {
+    if chosen.len() == k {
+        let total_cost = accumulator.evaluate(objective);
+
+        if total_cost >= *best_cost {
+            return;
+        }
+
+        let mappings: Vec<Mapping> = chosen.iter().map(|&i| all_mappings[i].clone()).collect();
+        *best_cost = total_cost;
+        *best_result = Some((total_cost, accumulator.edge_map(), mappings.clone()));
+
+        let mut observed = best_checkpointed_cost.load(Ordering::Relaxed);
+        let became_global_best = loop {
+            if total_cost >= observed {
+                break false;
+            }
+            match best_checkpointed_cost.compare_exchange_weak(
+                observed,
+                total_cost,
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => break true,
+                Err(latest) => observed = latest,
+            }
+        };
+
+        if became_global_best {
+            if let Some((path, interval)) = checkpoint {
+                let mut last_guard = last_checkpoint.lock().unwrap();
+                if last_guard.elapsed() >= *interval {
+                    *last_guard = Instant::now();
+                    let first_watermark = contiguous_prefix_len(&completed_firsts.lock().unwrap());
+                    let _ = write_checkpoint_file(
+                        path,
+                        k,
+                        total_cost,
+                        &mappings,
+                        search_start.elapsed(),
+                        instance_hash,
+                        first_watermark,
+                    );
+                }
+            }
+        }
+        return;
+    }
+
+    // Leave enough room in `all_mappings` after `idx` for the remaining slots.
+    let remaining = k - chosen.len();
+    let last_idx = all_mappings.len() - remaining;
+
+    for idx in next_idx..=last_idx {
+        if stop.load(Ordering::Relaxed) {
+            return;
+        }
+
+        // Lower bound on any solution completed from here: the accumulated
+        // cost so far never falls as more mappings are merged in, and at
+        // least one more mapping must still be added, whose own standalone
+        // cost is at least `costs[idx]` (the cheapest remaining candidate,
+        // since `all_mappings` is sorted ascending). Mirrors
+        // `cost::lower_bound_partial`, generalized to `objective` instead of
+        // being fixed to `TotalEdges`.
+        let bound = accumulator.evaluate(objective).max(costs[idx]);
+        if bound >= *best_cost {
+            // Every later idx has an equal or higher standalone cost, so the
+            // bound only gets worse from here: nothing later in this loop
+            // can improve on the incumbent either.
+            pruned_by_bound.fetch_add(last_idx - idx + 1, Ordering::Relaxed);
+            break;
+        }
+
+        if let Some(bitsets) = disjoint_bitsets {
+            if chosen
+                .iter()
+                .any(|&c| bitsets_intersect(&bitsets[idx], &bitsets[c]))
+            {
+                continue;
+            }
+        }
+
+        nodes_visited.fetch_add(1, Ordering::Relaxed);
+        accumulator.add_mapping(g, h, &all_mappings[idx]);
+        chosen.push(idx);
+
+        search_combinations(
+            all_mappings,
+            costs,
+            g,
+            h,
+            k,
+            idx + 1,
+            objective,
+            accumulator,
+            chosen,
+            best_cost,
+            best_result,
+            nodes_visited,
+            pruned_by_bound,
+            disjoint_bitsets,
+            checkpoint,
+            best_checkpointed_cost,
+            last_checkpoint,
+            search_start,
+            stop,
+            completed_firsts,
+            instance_hash,
+        );
+
+        chosen.pop();
+        accumulator.remove_mapping(g, h, &all_mappings[idx]);
+    }
+}
+
+/// How many of `0, 1, 2, ...` are present in `set`, i.e. the length of the
+/// longest prefix of naturals fully contained in it. Used to turn
+/// `completed_firsts` (which `first` values `branch_and_bound_search`'s
+/// fan-out has finished, possibly out of order) into a single watermark: the
+/// largest `n` such that every `first < n` is provably done, safe to skip on
+/// `--resume` even though some `first >= n` may also already be finished.
+fn contiguous_prefix_len(set: &BTreeSet<usize>) -> usize {
+    let mut n = 0;
+    while set.contains(&n) {
+        n += 1;
+    }
+    n
+}
+
+/// Main exact algorithm implementation. Returns the best solution found
+/// alongside whether `--timeout` cut the search off before it could prove
+/// optimality.
+#[allow(clippy::too_many_arguments)]
+fn exact_minimal_k_extension(
+    g: &Graph,
+    h: &Graph,
+    k: usize,
+    mapping_range: Option<(u128, u64)>,
+    objective: &Objective,
+    undirected: bool,
+    vertices_were_added: bool,
+    timeout: Option<u64>,
+    resume: Option<(SolutionResult, usize)>,
+    checkpoint: Option<(PathBuf, Duration)>,
+    max_combinations: usize,
+    force: bool,
+    symmetry_breaking: bool,
+    mod_aut: bool,
+    disjoint: bool,
+    merge_semantics: MergeSemantics,
+) -> (Option<SolutionResult>, bool) {
+    // A disjoint mapping set needs k * g.num_vertices() distinct H vertices
+    // with no sharing between mappings, so this many H vertices is a hard
+    // floor regardless of G and H's structure -- report it up front instead
+    // of letting the search run and come back empty.
+    if disjoint && k.saturating_mul(g.num_vertices()) > h.num_vertices() {
+        println!(
+            "Error: --disjoint vertices needs {} * {} = {} distinct H vertices, but H only has {}.",
+            k,
+            g.num_vertices(),
+            k * g.num_vertices(),
+            h.num_vertices()
+        );
+        return (None, false);
+    }
+
+    // `count_satisfying_mappings`'s weighted-degree pruning assumes a
+    // mapping's edges already exist in H (see its doc comment); it's a
+    // sound pre-check for the common case, but synthetic vertices added by
+    // `--allow-vertex-additions` start with no edges of their own by
+    // construction, so the estimate would always come back 0 for them.
+    // Skip it in that case and rely on the full enumeration below instead.
+    if !vertices_were_added {
+        println!("Estimating feasibility via mapping count (inclusion-exclusion)...");
+        let estimated_count = count_satisfying_mappings(g, h);
+        println!("Estimated satisfying mappings: {}", estimated_count);
+        if estimated_count < k {
+            println!(
+                "Error: Not enough satisfying mappings. Need {}, estimated {} (the maximum achievable k here is approximately {})",
+                k, estimated_count, estimated_count
+            );
+            return (None, false);
+        }
+    }
+
+    let mut all_mappings = if let Some((start, count)) = mapping_range {
+        println!(
+            "Restricting candidate pool to mapping range [{}, {})  -- result may be suboptimal \
+             for the full problem; merge with other slices to recover the true optimum.",
+            start,
+            start + count as u128
+        );
+        enumerate_range(g, h, start, count)
+    } else if undirected {
+        println!("Finding all possible mappings from G to H (undirected, symmetry-broken)...");
+        find_all_mappings_undirected(g, h)
+    } else {
+        println!("Finding all possible mappings from G to H...");
+        find_all_mappings(g, h)
+    };
+
+    println!("Found {} total mappings", all_mappings.len());
+
+    let automorphism_estimate = estimate_automorphisms(g);
+    println!(
+        "Estimated automorphisms of G: {} (rough approximation; see utils::estimate_automorphisms)",
+        automorphism_estimate
+    );
+
+    if symmetry_breaking && mapping_range.is_none() {
+        if let Some(canonical_image) = all_mappings.iter().map(|mapping| mapping[0]).min() {
+            let before = all_mappings.len();
+            all_mappings.retain(|mapping| mapping[0] == canonical_image);
+            println!(
+                "Symmetry breaking: keeping only mappings that send G's vertex 0 to H's vertex {} \
+                 ({} of {} mappings kept)",
+                canonical_image,
+                all_mappings.len(),
+                before
+            );
+        }
+    }
+
+    if mod_aut && mapping_range.is_none() {
+        let autos = automorphisms(g);
+        let before = all_mappings.len();
+        all_mappings.retain(|mapping| is_canonical_under_automorphisms(mapping, &autos));
+        println!(
+            "--mod-aut: keeping one mapping per orbit of G's {} automorphisms \
+             ({} of {} mappings kept)",
+            autos.len(),
+            all_mappings.len(),
+            before
+        );
+    }
+
+    if all_mappings.len() < k {
+        println!(
+            "Error: Not enough mappings found. Need {}, but only found {} (the maximum achievable k here is {})",
+            k,
+            all_mappings.len(),
+            all_mappings.len()
+        );
+        return (None, false);
+    }
+
+    // `all_mappings.len() == k` leaves exactly one k-combination: the whole
+    // pool. There's nothing to branch on, so score it directly instead of
+    // spinning up `branch_and_bound_search`'s rayon fan-out and greedy seed
+    // for a search space of size 1.
+    if all_mappings.len() == k {
+        println!("Exactly k mappings available -- using all of them, no search needed");
+        let edge_map = calculate_edge_map_with_semantics(g, h, &all_mappings, merge_semantics);
+        let cost = objective.evaluate(&edge_map);
+        return (Some((cost, edge_map, all_mappings)), false);
+    }
+
+    let binomial_table = BinomialTable::new(all_mappings.len());
+    let total_combinations = binomial_table.get(all_mappings.len(), k);
+    println!(
+        "Effective search space after dividing by the automorphism estimate: ~{} combinations",
+        total_combinations / automorphism_estimate.max(1)
+    );
+
+    if total_combinations > max_combinations && !force {
+        println!(
+            "Error: {} choose {} is {} combinations, which exceeds --max-combinations ({}); refusing to start.",
+            all_mappings.len(),
+            k,
+            total_combinations,
+            max_combinations
+        );
+        let projected =
+            calibrate_projected_runtime(&all_mappings, g, h, k, objective, total_combinations);
+        println!(
+            "Projected runtime at this rate: ~{:.1}s (calibrated from a {}-combination sample)",
+            projected.as_secs_f64(),
+            total_combinations.min(10_000)
+        );
+        println!(
+            "Re-run with --force to proceed anyway, or use the approx_solver binary for a fast \
+             heuristic result, or --mapping-range to split the search across several runs."
+        );
+        return (None, false);
+    }
+
+    println!(
+        "Branch-and-bound search over {}-combinations of mappings ({} total, exhaustive worst case)...",
+        k, total_combinations
+    );
+
+    let (result, nodes_visited, pruned_by_bound, timed_out) = branch_and_bound_search(
+        &all_mappings,
+        g,
+        h,
+        k,
+        objective,
+        timeout,
+        resume,
+        checkpoint,
+        disjoint,
+        merge_semantics,
+    );
+    if timed_out {
+        println!(
+            "TIME LIMIT REACHED -- best found, optimality not proven ({} of {} combinations evaluated, {:.1}%)",
+            nodes_visited,
+            total_combinations,
+            100.0 * nodes_visited as f64 / total_combinations.max(1) as f64
+        );
+    } else {
+        println!(
+            "Finished branch-and-bound search: {} nodes visited (of {} possible combinations)",
+            nodes_visited, total_combinations
+        );
+    }
+    println!(
+        "Pruned by bound: {} combinations skipped without being visited",
+        pruned_by_bound
+    );
+
+    (result, timed_out)
+}
+
+/// Re-enumerates the same candidate pool `exact_minimal_k_extension` just
+/// searched (see `--all-optimal`) and collects every k-combination whose
+/// cost equals `optimal_cost`, instead of the single arbitrary one the
+/// branch-and-bound search happened to land on. Re-deriving the pool here
+/// rather than threading it out of `exact_minimal_k_extension` costs
+/// roughly what the search itself did, but keeps this an optional,
+/// self-contained add-on instead of a second return value every caller
+/// (including every existing test) has to thread through.
+///
+/// Walks the pool in the same ascending-standalone-cost order as
+/// `search_combinations`, with the same lower-bound pruning except the
+/// comparison is `> optimal_cost` rather than `>= best_cost`: a tie with
+/// the optimum is exactly what this pass exists to keep, where the main
+/// search's `>=` exists to discard it. Stops once `cap` combinations have
+/// been collected and reports the cutoff via the returned `bool`.
+#[allow(clippy::too_many_arguments)]
+fn collect_all_optimal(
+    g: &Graph,
+    h: &Graph,
+    k: usize,
+    mapping_range: Option<(u128, u64)>,
+    objective: &Objective,
+    undirected: bool,
+    symmetry_breaking: bool,
+    mod_aut: bool,
+    disjoint: bool,
+    merge_semantics: MergeSemantics,
+    optimal_cost: usize,
+    cap: usize,
+) -> (Vec<SolutionResult>, bool) {
+    let mut all_mappings = if let Some((start, count)) = mapping_range {
+        enumerate_range(g, h, start, count)
+    } else if undirected {
+        find_all_mappings_undirected(g, h)
+    } else {
+        find_all_mappings(g, h)
+    };
+
+    if symmetry_breaking && mapping_range.is_none() {
+        if let Some(canonical_image) = all_mappings.iter().map(|mapping| mapping[0]).min() {
+            all_mappings.retain(|mapping| mapping[0] == canonical_image);
+        }
+    }
+
+    if mod_aut && mapping_range.is_none() {
+        let autos = automorphisms(g);
+        all_mappings.retain(|mapping| is_canonical_under_automorphisms(mapping, &autos));
+    }
+
+    if all_mappings.len() <= k {
+        // No branching to do: the whole pool (if it's exactly k mappings) is
+        // the only combination, and there's nothing to tie against.
+        let chosen: Vec<&Mapping> = all_mappings.iter().collect();
+        let edge_map = calculate_edge_map_with_semantics(g, h, &chosen, merge_semantics);
+        return if objective.evaluate(&edge_map) == optimal_cost {
+            (vec![(optimal_cost, edge_map, all_mappings)], false)
+        } else {
+            (Vec::new(), false)
+        };
+    }
+
+    let standalone_cost: Vec<usize> = all_mappings
+        .iter()
+        .map(|mapping| objective.evaluate(&calculate_edge_map(g, h, std::slice::from_ref(mapping))))
+        .collect();
+    let mut order: Vec<usize> = (0..all_mappings.len()).collect();
+    order.sort_by_key(|&i| standalone_cost[i]);
+    let sorted_mappings: Vec<Mapping> = order.iter().map(|&i| all_mappings[i].clone()).collect();
+    let sorted_costs: Vec<usize> = order.iter().map(|&i| standalone_cost[i]).collect();
+
+    let n_h = h.num_vertices();
+    let disjoint_bitsets: Option<Vec<Vec<u64>>> = disjoint.then(|| {
+        sorted_mappings
+            .iter()
+            .map(|m| mapping_image_bitset(m, n_h))
+            .collect()
+    });
+    let disjoint_bitsets = disjoint_bitsets.as_deref();
+
+    let mut found = Vec::new();
+    let mut truncated = false;
+    let mut accumulator = EdgeMapAccumulator::with_semantics(merge_semantics);
+    let mut chosen = Vec::with_capacity(k);
+    collect_all_optimal_combinations(
+        &sorted_mappings,
+        &sorted_costs,
+        g,
+        h,
+        k,
+        0,
+        objective,
+        &mut accumulator,
+        &mut chosen,
+        optimal_cost,
+        cap,
+        disjoint_bitsets,
+        &mut found,
+        &mut truncated,
+    );
+    (found, truncated)
+}
+
+/// Recursive helper for `collect_all_optimal`; see its doc comment for the
+/// pruning rule. `found` accumulates every completed combination whose cost
+/// equals `optimal_cost`; `truncated` is set once `cap` is reached.
+#[allow(clippy::too_many_arguments)]
+fn collect_all_optimal_combinations(
+    all_mappings: &[Mapping],
+    costs: &[usize],
+    g: &Graph,
+    h: &Graph,
+    k: usize,
+    next_idx: usize,
+    objective: &Objective,
+    accumulator: &mut EdgeMapAccumulator,
+    chosen: &mut Vec<usize>,
+    optimal_cost: usize,
+    cap: usize,
+    disjoint_bitsets: Option<&[Vec<u64>]>,
+    found: &mut Vec<SolutionResult>,
+    truncated: &mut bool,
+) {
+    if found.len() >= cap {
+        *truncated = true;
+        return;
+    }
+
+    if chosen.len() == k {
+        if accumulator.evaluate(objective) == optimal_cost {
+            let mappings: Vec<Mapping> = chosen.iter().map(|&i| all_mappings[i].clone()).collect();
+            found.push((optimal_cost, accumulator.edge_map(), mappings));
+        }
+        return;
+    }
+
+    let remaining = k - chosen.len();
+    let last_idx = all_mappings.len() - remaining;
+
+    for idx in next_idx..=last_idx {
+        if found.len() >= cap {
+            *truncated = true;
+            return;
+        }
+
+        let bound = accumulator.evaluate(objective).max(costs[idx]);
+        if bound > optimal_cost {
+            // Every later idx has an equal or higher standalone cost, so
+            // nothing further in this loop can tie the optimum either.
+            break;
+        }
+
+        if let Some(bitsets) = disjoint_bitsets {
+            if chosen
+                .iter()
+                .any(|&c| bitsets_intersect(&bitsets[idx], &bitsets[c]))
+            {
+                continue;
+            }
+        }
+
+        accumulator.add_mapping(g, h, &all_mappings[idx]);
+        chosen.push(idx);
+
+        collect_all_optimal_combinations(
+            all_mappings,
+            costs,
+            g,
+            h,
+            k,
+            idx + 1,
+            objective,
+            accumulator,
+            chosen,
+            optimal_cost,
+            cap,
+            disjoint_bitsets,
+            found,
+            truncated,
+        );
+
+        chosen.pop();
+        accumulator.remove_mapping(g, h, &all_mappings[idx]);
+    }
+}
+
+/// An entry in `collect_top_n`'s bounded max-heap: a completed combination's
+/// cost, canonicalized mappings (via `canonical_mappings`, for a
+/// deterministic tie order the same way `prefer` uses it), and the edge map
+/// that produced the cost. Ordered so the heap's peek is always the worst
+/// (highest-cost, and among equal costs, lexicographically greatest by
+/// `mappings`) entry currently kept -- the one to evict when a better
+/// combination is found.
+struct TopNEntry {
+    cost: usize,
+    mappings: Vec<Mapping>,
+    edge_map: EdgeMap,
+}
+
+impl PartialEq for TopNEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.cost == other.cost && self.mappings == other.mappings
+    }
+}
+impl Eq for TopNEntry {}
+impl PartialOrd for TopNEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for TopNEntry {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.cost
+            .cmp(&other.cost)
+            .then_with(|| self.mappings.cmp(&other.mappings))
+    }
+}
+
+/// Finds the `n` cheapest k-combinations overall (not just ties at a known
+/// optimum, unlike `collect_all_optimal`), ranked ascending by cost, by
+/// re-enumerating the same candidate pool the search itself used and
+/// keeping a bounded max-heap of the `n` best combinations seen so far --
+/// once the heap is full, any partial combination whose cost bound already
+/// exceeds the heap's worst entry is pruned, the same `break`/skip shape
+/// `collect_all_optimal_combinations` uses against a fixed `optimal_cost`.
+/// Combinations that canonicalize (via `canonical_mappings`) to a mapping
+/// set already kept are skipped, so a host/pattern pair whose automorphisms
+/// let `find_all_mappings` return the same embedding via two different
+/// search paths doesn't count it twice.
+///
+/// Ignored in `vertex-augmentation` mode and by `--budget`; see `--top-n`.
+#[allow(clippy::too_many_arguments)]
+fn collect_top_n(
+    g: &Graph,
+    h: &Graph,
+    k: usize,
+    mapping_range: Option<(u128, u64)>,
+    objective: &Objective,
+    undirected: bool,
+    symmetry_breaking: bool,
+    mod_aut: bool,
+    disjoint: bool,
+    merge_semantics: MergeSemantics,
+    n: usize,
+) -> Vec<Solution> {
+    let mut all_mappings = if let Some((start, count)) = mapping_range {
+        enumerate_range(g, h, start, count)
+    } else if undirected {
+        find_all_mappings_undirected(g, h)
+    } else {
+        find_all_mappings(g, h)
+    };
+
+    if symmetry_breaking && mapping_range.is_none() {
+        if let Some(canonical_image) = all_mappings.iter().map(|mapping| mapping[0]).min() {
+            all_mappings.retain(|mapping| mapping[0] == canonical_image);
+        }
+    }
+
+    if mod_aut && mapping_range.is_none() {
+        let autos = automorphisms(g);
+        all_mappings.retain(|mapping| is_canonical_under_automorphisms(mapping, &autos));
+    }
+
+    if n == 0 || all_mappings.len() < k {
+        return Vec::new();
+    }
+
+    if all_mappings.len() == k {
+        // No branching to do: the whole pool is the only combination.
+        let chosen: Vec<&Mapping> = all_mappings.iter().collect();
+        let edge_map = calculate_edge_map_with_semantics(g, h, &chosen, merge_semantics);
+        let cost = objective.evaluate(&edge_map);
+        return vec![Solution {
+            cost,
+            edge_map,
+            mappings: all_mappings,
+        }];
+    }
+
+    let standalone_cost: Vec<usize> = all_mappings
+        .iter()
+        .map(|mapping| objective.evaluate(&calculate_edge_map(g, h, std::slice::from_ref(mapping))))
+        .collect();
+    let mut order: Vec<usize> = (0..all_mappings.len()).collect();
+    order.sort_by_key(|&i| standalone_cost[i]);
+    let sorted_mappings: Vec<Mapping> = order.iter().map(|&i| all_mappings[i].clone()).collect();
+    let sorted_costs: Vec<usize> = order.iter().map(|&i| standalone_cost[i]).collect();
+
+    let n_h = h.num_vertices();
+    let disjoint_bitsets: Option<Vec<Vec<u64>>> = disjoint.then(|| {
+        sorted_mappings
+            .iter()
+            .map(|m| mapping_image_bitset(m, n_h))
+            .collect()
+    });
+    let disjoint_bitsets = disjoint_bitsets.as_deref();
+
+    let mut heap: BinaryHeap<TopNEntry> = BinaryHeap::new();
+    let mut seen: HashSet<Vec<Mapping>> = HashSet::new();
+    let mut accumulator = EdgeMapAccumulator::with_semantics(merge_semantics);
+    let mut chosen = Vec::with_capacity(k);
+    collect_top_n_combinations(
+        &sorted_mappings,
+        &sorted_costs,
+        g,
+        h,
+        k,
+        0,
+        objective,
+        &mut accumulator,
+        &mut chosen,
+        n,
+        disjoint_bitsets,
+        &mut heap,
+        &mut seen,
+    );
+
+    heap.into_sorted_vec()
+        .into_iter()
+        .map(|entry| Solution {
+            cost: entry.cost,
+            edge_map: entry.edge_map,
+            mappings: entry.mappings,
+        })
+        .collect()
+}
+
+/// Recursive helper for `collect_top_n`; see its doc comment for the pruning
+/// rule and the heap/`seen` bookkeeping.
+#[allow(clippy::too_many_arguments)]
+fn collect_top_n_combinations(
+    all_mappings: &[Mapping],
+    costs: &[usize],
+    g: &Graph,
+    h: &Graph,
+    k: usize,
+    next_idx: usize,
+    objective: &Objective,
+    accumulator: &mut EdgeMapAccumulator,
+    chosen: &mut Vec<usize>,
+    n: usize,
+    disjoint_bitsets: Option<&[Vec<u64>]>,
+    heap: &mut BinaryHeap<TopNEntry>,
+    seen: &mut HashSet<Vec<Mapping>>,
+) {
+    if chosen.len() == k {
+        let cost = accumulator.evaluate(objective);
+        if heap.len() >= n {
+            if let Some(worst) = heap.peek() {
+                if cost > worst.cost {
+                    return;
+                }
+            }
+        }
+
+        let mappings: Vec<Mapping> = chosen.iter().map(|&i| all_mappings[i].clone()).collect();
+        let canon = canonical_mappings(&mappings);
+        if seen.contains(&canon) {
+            return;
+        }
+
+        let entry = TopNEntry {
+            cost,
+            mappings: canon,
+            edge_map: accumulator.edge_map(),
+        };
+        if heap.len() < n {
+            seen.insert(entry.mappings.clone());
+            heap.push(entry);
+        } else if let Some(worst) = heap.peek() {
+            if entry < *worst {
+                if let Some(evicted) = heap.pop() {
+                    seen.remove(&evicted.mappings);
+                }
+                seen.insert(entry.mappings.clone());
+                heap.push(entry);
+            }
+        }
+        return;
+    }
+
+    let remaining = k - chosen.len();
+    let last_idx = all_mappings.len() - remaining;
+
+    for idx in next_idx..=last_idx {
+        let bound = accumulator.evaluate(objective).max(costs[idx]);
+        if heap.len() >= n {
+            if let Some(worst) = heap.peek() {
+                if bound > worst.cost {
+                    // Every later idx has an equal or higher standalone
+                    // cost, so nothing further in this loop can beat the
+                    // heap's current worst entry either.
+                    break;
+                }
+            }
+        }
+
+        if let Some(bitsets) = disjoint_bitsets {
+            if chosen
+                .iter()
+                .any(|&c| bitsets_intersect(&bitsets[idx], &bitsets[c]))
+            {
+                continue;
+            }
+        }
+
+        accumulator.add_mapping(g, h, &all_mappings[idx]);
+        chosen.push(idx);
+
+        collect_top_n_combinations(
+            all_mappings,
+            costs,
+            g,
+            h,
+            k,
+            idx + 1,
+            objective,
+            accumulator,
+            chosen,
+            n,
+            disjoint_bitsets,
+            heap,
+            seen,
+        );
+
+        chosen.pop();
+        accumulator.remove_mapping(g, h, &all_mappings[idx]);
+    }
+}
+
+/// Core branch-and-bound search: given a pool of already-enumerated
+/// candidate mappings (with `all_mappings.len() >= k`), find the
+/// minimum-cost set of `k` of them under `objective`. Returns the result,
+/// how many nodes the search visited (pushed into an `EdgeMapAccumulator`,
+/// which otherwise has no consumer besides this module's own tests asserting
+/// the pruning actually prunes), how many candidates the cost bound pruned
+/// without visiting (see `search_combinations`'s `break` and this function's
+/// own per-branch skip below), and whether `timeout` cut the search off
+/// before every branch finished.
+///
+/// `timeout`, if set, spawns a scoped watcher thread that sleeps for that
+/// many seconds and then sets a shared `stop` flag every branch checks once
+/// per node visited (see `search_combinations`); a branch mid-search simply
+/// stops recursing and returns whatever it had found so far.
+///
+/// `resume`, if set, competes with the greedy seed the same way a parallel
+/// branch's result would (see `prefer`) so a checkpoint taken late in a
+/// search (a tight incumbent) always wins out over a fresh greedy guess, and
+/// a checkpoint taken early never beats a better greedy guess either.
+/// `checkpoint`, if set, flushes the best-so-far solution to a file every
+/// time it improves (see `search_combinations`), at most once per its
+/// configured interval, so an interrupted run leaves something `--resume`
+/// can pick back up from.
+///
+/// `disjoint`, if set, restricts the search to combinations whose mappings'
+/// H-vertex images are pairwise disjoint (see `--disjoint` and
+/// `search_combinations`'s `disjoint_bitsets` parameter).
+///
+/// `merge_semantics` controls how each branch's `EdgeMapAccumulator`
+/// combines multiple mappings' demand on the same edge (see
+/// `MergeSemantics`).
+///
+/// The parallel fan-out below splits the search by the first chosen index
+/// (`0..=n-k`), not by unranking `0..total_combinations` into balanced
+/// per-thread ranges: the bound check on `sorted_costs[first]` already prunes
+/// most of that range before a thread ever starts a branch (see
+/// `pruned_by_bound`), so indexing directly into combination-space would
+/// force every thread to materialize combinations the bound would otherwise
+/// skip for free. First-index fan-out keeps that pruning intact while still
+/// giving every thread a large, independent unit of work.
+///
+/// That same first-index granularity is also what `--resume` skips by: the
+/// `usize` alongside `resume`'s solution is a "completed through first
+/// index" watermark (see `contiguous_prefix_len`), and every `first` below
+/// it is skipped entirely rather than re-explored.
+#[allow(clippy::too_many_arguments)]
+fn branch_and_bound_search(
+    all_mappings: &[Mapping],
+    g: &Graph,
+    h: &Graph,
+    k: usize,
+    objective: &Objective,
+    timeout: Option<u64>,
+    resume: Option<(SolutionResult, usize)>,
+    checkpoint: Option<(PathBuf, Duration)>,
+    disjoint: bool,
+    merge_semantics: MergeSemantics,
+) -> (Option<SolutionResult>, usize, usize, bool) {
+    println!("Computing a quick greedy solution to seed the search...");
+    let greedy_seed = greedy_incumbent(all_mappings, g, h, k, objective, disjoint, merge_semantics);
+    if let Some((seed_cost, _, _)) = &greedy_seed {
+        println!("Greedy seed cost: {}", seed_cost);
+    }
+    let (resume_result, resume_watermark) = match resume {
+        Some((result, watermark)) => (Some(result), watermark),
+        None => (None, 0),
+    };
+    if let Some((resume_cost, _, _)) = &resume_result {
+        println!(
+            "Resuming from checkpoint: incumbent cost {}, skipping first-index branches 0..{}",
+            resume_cost, resume_watermark
+        );
+    }
+    let seed = prefer(greedy_seed, resume_result);
+
+    // Visit cheapest-standalone-cost mappings first: a tight incumbent found
+    // early prunes more of the (still-expensive) remainder, and it's what
+    // lets `search_combinations` treat "bound can't improve on the
+    // incumbent" as "nothing later in this loop can either" (see its doc
+    // comment).
+    let mut order: Vec<usize> = (0..all_mappings.len()).collect();
+    let standalone_cost: Vec<usize> = all_mappings
+        .iter()
+        .map(|mapping| objective.evaluate(&calculate_edge_map(g, h, std::slice::from_ref(mapping))))
+        .collect();
+    order.sort_by_key(|&i| standalone_cost[i]);
+    let sorted_mappings: Vec<Mapping> = order.iter().map(|&i| all_mappings[i].clone()).collect();
+    let sorted_costs: Vec<usize> = order.iter().map(|&i| standalone_cost[i]).collect();
+
+    // See `--disjoint`: precomputed once, in the same order as
+    // `sorted_mappings`, so `search_combinations` can check two candidates'
+    // H-vertex images for overlap with a few word-wise ANDs instead of
+    // rebuilding a set per comparison.
+    let n_h = h.num_vertices();
+    let disjoint_bitsets: Option<Vec<Vec<u64>>> = disjoint.then(|| {
+        sorted_mappings
+            .iter()
+            .map(|m| mapping_image_bitset(m, n_h))
+            .collect()
+    });
+    let disjoint_bitsets = disjoint_bitsets.as_deref();
+
+    let search_start = std::time::Instant::now();
+
+    // Every branch starts from the same fixed incumbent and never observes
+    // another branch's progress, so two runs always fan out into the exact
+    // same per-branch work and arrive at the exact same per-branch result —
+    // there's nothing left for thread scheduling to make nondeterministic.
+    // That does give up the pruning a shared, continuously-improving bound
+    // would otherwise offer sibling branches mid-search; `prefer` below pays
+    // for it by reducing every branch's local best into one overall winner,
+    // with ties between equally-cheap branches broken by canonical mapping
+    // order instead of by whichever branch happened to finish first.
+    let initial_best_cost = seed.as_ref().map_or(usize::MAX, |(c, _, _)| *c);
+    let nodes_visited = AtomicUsize::new(0);
+    let pruned_by_bound = AtomicUsize::new(0);
+    let best_checkpointed_cost = AtomicUsize::new(initial_best_cost);
+    let last_checkpoint: Mutex<Instant> = Mutex::new(search_start);
+    let stop = AtomicBool::new(false);
+    // Pre-seeded with everything `resume_watermark` already vouches for, so
+    // a checkpoint written partway through *this* run reports a watermark
+    // that still covers the earlier run's completed prefix too.
+    let completed_firsts: Mutex<BTreeSet<usize>> = Mutex::new((0..resume_watermark).collect());
+    let instance_hash = instance_hash(g, h, k, objective);
+
+    // Fan out over the first mapping of each combination in parallel; each
+    // branch then walks the remaining choices sequentially, pushing a
+    // mapping into its own `EdgeMapAccumulator` before recursing and popping
+    // it on the way back out. This avoids rebuilding the edge map from
+    // scratch for every combination, since siblings share all but one of
+    // their k mappings with their parent.
+    let n = sorted_mappings.len();
+    let final_result = thread::scope(|scope| {
+        if let Some(timeout_secs) = timeout {
+            let stop = &stop;
+            scope.spawn(move || {
+                thread::sleep(Duration::from_secs(timeout_secs));
+                stop.store(true, Ordering::Relaxed);
+            });
+        }
+
+        (resume_watermark..=n - k)
+            .into_par_iter()
+            .map(|first| {
+                if stop.load(Ordering::Relaxed) {
+                    return None;
+                }
+                if sorted_costs[first] >= initial_best_cost {
+                    // Same reasoning as the inner loop's `break`, just
+                    // without one: a parallel iterator can't short-circuit
+                    // the remaining `first` values, so each checks the
+                    // (sorted-ascending) bound (and the timeout) itself.
+                    pruned_by_bound.fetch_add(n - k - first + 1, Ordering::Relaxed);
+                    completed_firsts.lock().unwrap().insert(first);
+                    return None;
+                }
+
+                let mut accumulator = EdgeMapAccumulator::with_semantics(merge_semantics);
+                let mut chosen = Vec::with_capacity(k);
+                let mut best_cost = initial_best_cost;
+                let mut best_result = None;
+
+                nodes_visited.fetch_add(1, Ordering::Relaxed);
+                accumulator.add_mapping(g, h, &sorted_mappings[first]);
+                chosen.push(first);
+
+                search_combinations(
+                    &sorted_mappings,
+                    &sorted_costs,
+                    g,
+                    h,
+                    k,
+                    first + 1,
+                    objective,
+                    &mut accumulator,
+                    &mut chosen,
+                    &mut best_cost,
+                    &mut best_result,
+                    &nodes_visited,
+                    &pruned_by_bound,
+                    disjoint_bitsets,
+                    &checkpoint,
+                    &best_checkpointed_cost,
+                    &last_checkpoint,
+                    search_start,
+                    &stop,
+                    &completed_firsts,
+                    instance_hash,
+                );
+
+                // Only a branch that ran to completion (not cut short by
+                // `--timeout`) has actually accounted for every combination
+                // starting with `first` -- a stopped branch may have more
+                // left to search, so it must not be marked done.
+                if !stop.load(Ordering::Relaxed) {
+                    completed_firsts.lock().unwrap().insert(first);
+                }
+
+                best_result
+            })
+            .reduce(|| None, prefer)
+    });
+
+    let timed_out = stop.load(Ordering::Relaxed);
+    let final_result = prefer(seed, final_result);
+
+    let search_elapsed = search_start.elapsed();
+    println!("Search time: {:.3}s", search_elapsed.as_secs_f64());
+
+    let nodes_visited = nodes_visited.into_inner();
+    let pruned_by_bound = pruned_by_bound.into_inner();
+
+    (final_result, nodes_visited, pruned_by_bound, timed_out)
+}
+
+/// Run the vertex-augmentation variant and print its result.
+fn run_vertex_augmentation(g: &Graph, h: &Graph, k: usize) {
+    println!("Running vertex-augmentation search...");
+    let start_time = std::time::Instant::now();
+
+    match min_vertex_augmentation(g, h, k) {
+        Some((added, augmented_h)) => {
+            let elapsed = start_time.elapsed();
+
+            println!();
+            println!("==========================================================");
+            println!("OPTIMAL SOLUTION FOUND");
+            println!("==========================================================");
+            println!("Minimum vertices added: {}", added);
+            println!("Computation time: {:.3} ms", elapsed.as_millis());
+            println!();
+
+            println!("Augmented H adjacency matrix:");
+            for row in &augmented_h.adj {
+                println!("  {:?}", row);
+            }
+        }
+        None => {
+            println!();
+            println!("No solution found.");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Run the budgeted feasibility check and print FEASIBLE/INFEASIBLE.
+fn run_budgeted_feasibility(g: &Graph, h: &Graph, k: usize, budget: usize) {
+    println!(
+        "Checking feasibility within a budget of {} added edges...",
+        budget
+    );
+    let start_time = std::time::Instant::now();
+
+    match feasible_under_budget(g, h, k, budget) {
+        Some(solution) => {
+            let elapsed = start_time.elapsed();
+            println!();
+            println!(
+                "FEASIBLE: found a solution of cost {} (budget {})",
+                solution.cost, budget
+            );
+            println!("Computation time: {:.3} ms", elapsed.as_millis());
+            println!();
+
+            println!("Mappings:");
+            for (i, mapping) in solution.mappings.iter().enumerate() {
+                println!("  Mapping {}: {:?}", i + 1, mapping);
+            }
+        }
+        None => {
+            println!();
+            println!(
+                "INFEASIBLE: no set of {} mappings fits within a budget of {} added edges.",
+                k, budget
+            );
+            std::process::exit(1);
+        }
+    }
+}
+
+fn main() {
+    let args = Args::parse();
+
+    println!("Exact Solver for Minimal k-Isomorphic Subgraph Extension");
+    println!("==========================================================");
+    println!();
+
+    // Parse input graphs using nom-based parser, either from `--input` or
+    // (if that's omitted, or `--stdin` forces it) from stdin directly.
+    let (g, h) = match (&args.input, args.stdin) {
+        (Some(path), false) => match parse_input_file(path) {
+            Ok(graphs) => graphs,
+            Err(e) => {
+                eprintln!("Error parsing input file: {}", e);
+                std::process::exit(1);
+            }
+        },
+        _ => match parse_stdin() {
+            Ok(graphs) => graphs,
+            Err(e) => {
+                eprintln!("Error parsing stdin input: {}", e);
+                std::process::exit(1);
+            }
+        },
+    };
+    let (g, h) = if args.undirected {
+        (g.as_undirected(), h.as_undirected())
+    } else {
+        (g, h)
+    };
+
+    let (h, vertices_added) =
+        if args.allow_vertex_additions && args.mode != ModeArg::VertexAugmentation {
+            pad_host_to_pattern_size(&g, &h)
+        } else {
+            (h, 0)
+        };
+
+    println!("Graph G (pattern): {} vertices", g.num_vertices());
+    println!("Graph H (host): {} vertices", h.num_vertices());
+    if vertices_added > 0 {
+        println!(
+            "Synthetic vertices added to H (indices {}..{}): {} (cost {} each)",
+            h.num_vertices() - vertices_added,
+            h.num_vertices(),
+            vertices_added,
+            args.vertex_cost
+        );
+    }
+    println!("Required distinct mappings (k): {}", args.k);
+    println!("Objective: {:?}", args.objective);
+    if args.disjoint.is_some() {
+        println!("Disjointness: chosen mappings must have pairwise disjoint H vertex sets");
+    }
+    println!("Edge sharing: {:?}", args.edge_sharing);
+    println!(
+        "Threads: {}",
+        if args.threads == 0 {
+            "default (global rayon pool)".to_string()
+        } else {
+            format!("{} (scoped pool)", args.threads)
+        }
+    );
+    println!();
+
+    // Display adjacency matrices
+    println!("Graph G adjacency matrix:");
+    for row in &g.adj {
+        println!("  {:?}", row);
+    }
+    println!();
+
+    println!("Graph H adjacency matrix:");
+    for row in &h.adj {
+        println!("  {:?}", row);
+    }
+    println!();
+
+    if args.mode == ModeArg::VertexAugmentation {
+        run_vertex_augmentation(&g, &h, args.k);
+        return;
+    }
+
+    if let Some(budget) = args.budget {
+        run_budgeted_feasibility(&g, &h, args.k, budget);
+        return;
+    }
+
+    if let Some(path) = &args.export_ilp {
+        run_export_ilp(
+            &g,
+            &h,
+            args.k,
+            args.undirected,
+            path,
+            args.export_ilp_max_candidates,
+        );
+        return;
+    }
+
+    let mapping_range = match args.mapping_range.as_deref().map(parse_mapping_range) {
+        Some(Ok(range)) => Some(range),
+        Some(Err(e)) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        None => None,
+    };
+
+    let objective: Objective = args.objective.into();
+
+    let resume = args.resume.as_ref().and_then(|path| {
+        match read_resume_result(path, &g, &h, args.k, &objective) {
+            Ok(Some(resumable)) => Some(resumable),
+            Ok(None) => {
+                eprintln!(
+                    "Warning: could not read a resumable solution from {}; starting fresh",
+                    path.display()
+                );
+                None
+            }
+            Err(reason) => {
+                eprintln!("{}", reason);
+                std::process::exit(1);
+            }
+        }
+    });
+    let checkpoint = args
+        .checkpoint
+        .clone()
+        .zip(args.checkpoint_interval)
+        .map(|(path, secs)| (path, Duration::from_secs(secs)));
+
+    // Run exact algorithm
+    println!("Running exact algorithm...");
+    let start_time = std::time::Instant::now();
+
+    let merge_semantics: MergeSemantics = args.edge_sharing.into();
+
+    let run_search = || {
+        exact_minimal_k_extension(
+            &g,
+            &h,
+            args.k,
+            mapping_range,
+            &objective,
+            args.undirected,
+            vertices_added > 0,
+            args.timeout,
+            resume,
+            checkpoint,
+            args.max_combinations,
+            args.force,
+            args.symmetry_breaking,
+            args.mod_aut,
+            args.disjoint.is_some(),
+            merge_semantics,
+        )
+    };
+    let (result, timed_out) = if args.threads > 0 {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(args.threads)
+            .build()
+            .expect("requested thread count builds a valid pool")
+            .install(run_search)
+    } else {
+        run_search()
+    };
+
+    match result {
+        Some((cost, edge_set, mappings)) => {
+            let elapsed = start_time.elapsed();
+            let vertex_surcharge = vertices_added * args.vertex_cost;
+
+            println!();
+            println!("==========================================================");
+            if timed_out {
+                println!("TIME LIMIT REACHED -- best found, optimality not proven");
+            } else {
+                println!("OPTIMAL SOLUTION FOUND");
+            }
+            println!("==========================================================");
+            if vertex_surcharge > 0 {
+                println!(
+                    "Minimal total cost: {} ({} edges + {} for {} added vertices)",
+                    cost + vertex_surcharge,
+                    cost,
+                    vertex_surcharge,
+                    vertices_added
+                );
+            } else {
+                println!("Minimal total cost: {}", cost);
+            }
+            println!("Computation time: {:.3} ms", elapsed.as_millis());
+            println!("Computation time: {:.3} ns", elapsed.as_nanos());
+            println!();
+
+            println!("Adjacency matrix of edges to add to H:");
+            let n = h.num_vertices();
+            let mut add_matrix = vec![vec![0usize; n]; n];
+            for ((u, v), weight) in &edge_set {
+                add_matrix[*u][*v] = *weight;
+            }
+            for row in add_matrix {
+                println!("  {:?}", row);
+            }
+            println!();
+
+            if args.allow_deletions {
+                let edit = calculate_edit_map(&g, &h, &mappings, args.keep_threshold);
+                println!(
+                    "Adjacency matrix of H edges safe to prune (excess over keep-threshold {}):",
+                    args.keep_threshold
+                );
+                let mut delete_matrix = vec![vec![0usize; n]; n];
+                for ((u, v), weight) in &edit.deletions {
+                    delete_matrix[*u][*v] = *weight;
+                }
+                for row in delete_matrix {
+                    println!("  {:?}", row);
+                }
+                println!();
+            }
+
+            if mappings.len() > 1 {
+                let stats = sharing_stats(&g, &h, &mappings);
+                println!(
+                    "Edge sharing: {} edges shared across mappings ({} summed individually vs {} merged, {:.2}x savings)",
+                    stats.shared_edge_count,
+                    stats.sum_of_individual_costs,
+                    stats.merged_total_cost,
+                    stats.savings_ratio()
+                );
+                println!();
+            }
+
+            println!("Optimal set of {} mappings:", args.k);
+            for (i, mapping) in mappings.iter().enumerate() {
+                println!("  Mapping {}: {:?}", i + 1, mapping);
+            }
+
+            if let Some(path) = &args.output_edge_list {
+                if let Err(e) = write_edge_list_report(path, &g, &h, &edge_set) {
+                    eprintln!(
+                        "Warning: failed to write --output-edge-list to {}: {}",
+                        path.display(),
+                        e
+                    );
+                }
+            }
+
+            if args.all_optimal {
+                if timed_out {
+                    println!();
+                    println!(
+                        "--all-optimal skipped: --timeout cut the search off before cost {} was \
+                         proven optimal, so ties against it wouldn't be meaningful.",
+                        cost
+                    );
+                } else {
+                    let (ties, truncated) = collect_all_optimal(
+                        &g,
+                        &h,
+                        args.k,
+                        mapping_range,
+                        &objective,
+                        args.undirected,
+                        args.symmetry_breaking,
+                        args.mod_aut,
+                        args.disjoint.is_some(),
+                        merge_semantics,
+                        cost,
+                        args.all_optimal_cap,
+                    );
+                    println!();
+                    println!(
+                        "All optimal combinations (cost {}): {} found{}",
+                        cost,
+                        ties.len(),
+                        if truncated {
+                            format!(
+                                " (truncated at --all-optimal-cap {}, more may exist)",
+                                args.all_optimal_cap
+                            )
+                        } else {
+                            String::new()
+                        }
+                    );
+                    for (i, (_, _, tied_mappings)) in ties.iter().enumerate() {
+                        print!("  Combination {}: {:?}", i + 1, tied_mappings);
+                        if tied_mappings.len() > 1 {
+                            let stats = sharing_stats(&g, &h, tied_mappings);
+                            print!(
+                                " ({} edges shared, {:.2}x savings)",
+                                stats.shared_edge_count,
+                                stats.savings_ratio()
+                            );
+                        }
+                        println!();
+                    }
+                }
+            }
+
+            if let Some(n) = args.top_n {
+                if timed_out {
+                    println!();
+                    println!(
+                        "--top-n skipped: --timeout cut the search off before cost {} was \
+                         proven optimal, so a ranked list of best solutions wouldn't be \
+                         meaningful.",
+                        cost
+                    );
+                } else {
+                    let top = collect_top_n(
+                        &g,
+                        &h,
+                        args.k,
+                        mapping_range,
+                        &objective,
+                        args.undirected,
+                        args.symmetry_breaking,
+                        args.mod_aut,
+                        args.disjoint.is_some(),
+                        merge_semantics,
+                        n,
+                    );
+                    println!();
+                    println!("Top {} solutions by cost ({} found):", n, top.len());
+                    for (i, solution) in top.iter().enumerate() {
+                        print!(
+                            "  #{} (cost {}): {:?}",
+                            i + 1,
+                            solution.cost,
+                            solution.mappings
+                        );
+                        if solution.mappings.len() > 1 {
+                            let stats = solution.sharing_stats(&g, &h);
+                            print!(
+                                " ({} edges shared, {:.2}x savings)",
+                                stats.shared_edge_count,
+                                stats.savings_ratio()
+                            );
+                        }
+                        println!();
+                    }
+                }
+            }
+        }
+        None => {
+            println!();
+            println!("No solution found. The host graph H is too small to contain {} distinct embeddings of G.", args.k);
+            std::process::exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use minimal_k_iso_lib::utils::num_combinations;
+
+    /// Independent ground truth for `branch_and_bound_search`: scores every
+    /// k-combination of `all_mappings` by brute force and returns the
+    /// minimum. Only usable on instances small enough that `C(n, k)` is
+    /// cheap, which is the point — it's the thing the branch-and-bound
+    /// search exists to avoid doing in general.
+    fn brute_force_min_cost(
+        all_mappings: &[Mapping],
+        g: &Graph,
+        h: &Graph,
+        k: usize,
+        objective: &Objective,
+    ) -> usize {
+        (0..all_mappings.len())
+            .combinations(k)
+            .map(|indices| {
+                let chosen: Vec<&Mapping> = indices.iter().map(|&i| &all_mappings[i]).collect();
+                objective.evaluate(&calculate_edge_map(g, h, &chosen))
+            })
+            .min()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_branch_and_bound_matches_brute_force_on_small_instances() {
+        let instances = [
+            (
+                Graph::from_adjacency_matrix(vec![vec![0, 1], vec![0, 0]]),
+                Graph::from_adjacency_matrix(vec![vec![0; 4]; 4]),
+                2,
+            ),
+            (
+                Graph::from_adjacency_matrix(vec![vec![0, 1, 0], vec![0, 0, 1], vec![1, 0, 0]]),
+                Graph::from_adjacency_matrix(vec![vec![0; 5]; 5]),
+                3,
+            ),
+            (
+                Graph::from_adjacency_matrix(vec![vec![0, 2], vec![0, 0]]),
+                Graph::from_adjacency_matrix(vec![vec![0, 1], vec![0, 0]]),
+                2,
+            ),
+        ];
+
+        for (g, h, k) in instances {
+            let all_mappings = find_all_mappings(&g, &h);
+            assert!(all_mappings.len() >= k);
+
+            for objective in [Objective::TotalEdges, Objective::MaxEdgeMultiplicity] {
+                let expected = brute_force_min_cost(&all_mappings, &g, &h, k, &objective);
+                let (result, _, _, _) = branch_and_bound_search(
+                    &all_mappings,
+                    &g,
+                    &h,
+                    k,
+                    &objective,
+                    None,
+                    None,
+                    None,
+                    false,
+                    MergeSemantics::Shared,
+                );
+                let (actual, _, _) = result.expect("a k-combination always exists here");
+                assert_eq!(actual, expected);
+            }
+        }
+    }
+
+    /// `--threads 1` builds a single-threaded scoped pool; the search is
+    /// still deterministic (see `branch_and_bound_search`'s doc comment on
+    /// why fan-out order doesn't affect the result), so it must land on the
+    /// same cost as running under the default (potentially multi-threaded)
+    /// global pool.
+    #[test]
+    fn test_single_threaded_pool_matches_default_pool_cost() {
+        let g = Graph::from_adjacency_matrix(vec![vec![0, 1, 0], vec![0, 0, 1], vec![1, 0, 0]]);
+        let h = Graph::from_adjacency_matrix(vec![vec![0; 5]; 5]);
+        let all_mappings = find_all_mappings(&g, &h);
+        let k = 3;
+
+        let (default_result, _, _, _) = branch_and_bound_search(
+            &all_mappings,
+            &g,
+            &h,
+            k,
+            &Objective::TotalEdges,
+            None,
+            None,
+            None,
+            false,
+            MergeSemantics::Shared,
+        );
+
+        let single_threaded_pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(1)
+            .build()
+            .expect("a single-threaded pool always builds");
+        let (single_threaded_result, _, _, _) = single_threaded_pool.install(|| {
+            branch_and_bound_search(
+                &all_mappings,
+                &g,
+                &h,
+                k,
+                &Objective::TotalEdges,
+                None,
+                None,
+                None,
+                false,
+                MergeSemantics::Shared,
+            )
+        });
+
+        assert_eq!(default_result.unwrap().0, single_threaded_result.unwrap().0);
+    }
+
+    /// G = a directed triangle, H = a directed 5-cycle: every vertex in both
+    /// has out-degree 1, so `count_satisfying_mappings`'s weighted-degree
+    /// pre-check never under-rejects a feasible k, and `find_all_mappings`'s
+    /// count (`P(5, 3) = 60`) has a convenient number of divisors to pick
+    /// `k == N`, `k == N - 1`, and `k == N + 1` from.
+    fn triangle_into_five_cycle() -> (Graph, Graph) {
+        let g = Graph::from_adjacency_matrix(vec![vec![0, 1, 0], vec![0, 0, 1], vec![1, 0, 0]]);
+        let h = Graph::from_adjacency_matrix(vec![
+            vec![0, 1, 0, 0, 0],
+            vec![0, 0, 1, 0, 0],
+            vec![0, 0, 0, 1, 0],
+            vec![0, 0, 0, 0, 1],
+            vec![1, 0, 0, 0, 0],
+        ]);
+        (g, h)
+    }
+
+    #[test]
+    fn test_exact_minimal_k_extension_k_equals_available_mappings_takes_the_shortcut() {
+        let (g, h) = triangle_into_five_cycle();
+        let n = find_all_mappings(&g, &h).len();
+        let objective = Objective::TotalEdges;
+
+        let (result, timed_out) = exact_minimal_k_extension(
+            &g,
+            &h,
+            n,
+            None,
+            &objective,
+            false,
+            false,
+            None,
+            None,
+            None,
+            usize::MAX,
+            false,
+            false,
+            false,
+            false,
+            MergeSemantics::Shared,
+        );
+        let (cost, edge_map, mappings) = result.expect("k == available mappings is feasible");
+
+        assert!(!timed_out);
+        assert_eq!(mappings.len(), n);
+        assert_eq!(cost, objective.evaluate(&edge_map));
+    }
+
+    #[test]
+    fn test_exact_minimal_k_extension_k_one_less_than_available_matches_brute_force() {
+        let (g, h) = triangle_into_five_cycle();
+        let all_mappings = find_all_mappings(&g, &h);
+        let k = all_mappings.len() - 1;
+        let objective = Objective::TotalEdges;
+
+        let expected = brute_force_min_cost(&all_mappings, &g, &h, k, &objective);
+        let (result, _) = exact_minimal_k_extension(
+            &g,
+            &h,
+            k,
+            None,
+            &objective,
+            false,
+            false,
+            None,
+            None,
+            None,
+            usize::MAX,
+            false,
+            false,
+            false,
+            false,
+            MergeSemantics::Shared,
+        );
+        let (actual, _, _) = result.expect("k one less than available mappings is feasible");
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_exact_minimal_k_extension_k_greater_than_available_mappings_is_infeasible() {
+        let (g, h) = triangle_into_five_cycle();
+        let k = find_all_mappings(&g, &h).len() + 1;
+
+        let (result, timed_out) = exact_minimal_k_extension(
+            &g,
+            &h,
+            k,
+            None,
+            &Objective::TotalEdges,
+            false,
+            false,
+            None,
+            None,
+            None,
+            usize::MAX,
+            false,
+            false,
+            false,
+            false,
+            MergeSemantics::Shared,
+        );
+
+        assert!(result.is_none());
+        assert!(!timed_out);
+    }
+
+    #[test]
+    fn test_exact_minimal_k_extension_refuses_a_search_over_max_combinations_unless_forced() {
+        let (g, h) = triangle_into_five_cycle();
+        let all_mappings = find_all_mappings(&g, &h);
+        let k = all_mappings.len() - 1;
+        let objective = Objective::TotalEdges;
+        let total_combinations = num_combinations(all_mappings.len(), k);
+
+        let (result, timed_out) = exact_minimal_k_extension(
+            &g,
+            &h,
+            k,
+            None,
+            &objective,
+            false,
+            false,
+            None,
+            None,
+            None,
+            total_combinations - 1,
+            false,
+            false,
+            false,
+            false,
+            MergeSemantics::Shared,
+        );
+        assert!(
+            result.is_none(),
+            "guard should refuse an over-threshold search"
+        );
+        assert!(!timed_out);
+
+        let (result, _) = exact_minimal_k_extension(
+            &g,
+            &h,
+            k,
+            None,
+            &objective,
+            false,
+            false,
+            None,
+            None,
+            None,
+            total_combinations - 1,
+            true,
+            false,
+            false,
+            false,
+            MergeSemantics::Shared,
+        );
+        assert!(result.is_some(), "--force should bypass the guard");
+    }
+
+    #[test]
+    fn test_symmetry_breaking_shrinks_the_pool_and_still_finds_a_valid_solution() {
+        // Fixing G vertex 0's image cuts the candidate pool by a factor of
+        // (H vertex count), since that's how many choices for its image
+        // `find_all_mappings` otherwise enumerates independently of the rest.
+        let (g, h) = triangle_into_five_cycle();
+        let all_mappings = find_all_mappings(&g, &h);
+        let k = 2;
+        let objective = Objective::TotalEdges;
+
+        let (unrestricted_cost, _, _) = exact_minimal_k_extension(
+            &g,
+            &h,
+            k,
+            None,
+            &objective,
+            false,
+            false,
+            None,
+            None,
+            None,
+            usize::MAX,
+            false,
+            false,
+            false,
+            false,
+            MergeSemantics::Shared,
+        )
+        .0
+        .expect("feasible without symmetry breaking");
+
+        let (restricted_cost, restricted_edge_map, restricted_mappings) =
+            exact_minimal_k_extension(
+                &g,
+                &h,
+                k,
+                None,
+                &objective,
+                false,
+                false,
+                None,
+                None,
+                None,
+                usize::MAX,
+                false,
+                false,
+                true,
+                false,
+                MergeSemantics::Shared,
+            )
+            .0
+            .expect("feasible with symmetry breaking");
+
+        assert_eq!(restricted_mappings.len(), k);
+        assert_eq!(restricted_cost, objective.evaluate(&restricted_edge_map));
+        // Symmetry breaking only narrows the search, so it can never beat
+        // the unrestricted optimum -- just possibly miss it.
+        assert!(restricted_cost >= unrestricted_cost);
+        assert!(restricted_mappings.iter().all(|m| all_mappings.contains(m)));
+    }
+
+    #[test]
+    fn test_mod_aut_drops_the_pool_by_the_automorphism_count_and_preserves_the_optimum() {
+        // A directed 4-cycle's only automorphisms are its 4 rotations (any
+        // reflection reverses edge direction), and that rotation group acts
+        // freely on mappings into a directed 8-cycle host -- no mapping is
+        // fixed by a nontrivial rotation, since that would require two
+        // distinct G vertices to share an H image -- so every orbit has
+        // exactly 4 elements and `--mod-aut` should shrink the pool by
+        // exactly that factor.
+        //
+        // `MergeSemantics::Dedicated` here, not `Shared`: under `Shared`,
+        // picking several mappings from the *same* orbit is sometimes
+        // strictly cheapest (they demand the identical edge set, so they
+        // overlap completely), and `--mod-aut` keeps only one
+        // representative per orbit -- it can't reproduce that particular
+        // optimum. `Dedicated` has no cross-mapping sharing for an orbit to
+        // exploit, so the optimum is just the k cheapest standalone
+        // mappings, and `--mod-aut` preserves that as long as the
+        // minimum-cost tier spans at least k distinct orbits, which holds
+        // here.
+        let g = Graph::from_adjacency_matrix(vec![
+            vec![0, 1, 0, 0],
+            vec![0, 0, 1, 0],
+            vec![0, 0, 0, 1],
+            vec![1, 0, 0, 0],
+        ]);
+        let mut h_matrix = vec![vec![0; 8]; 8];
+        for i in 0..8 {
+            h_matrix[i][(i + 1) % 8] = 1;
+        }
+        let h = Graph::from_adjacency_matrix(h_matrix);
+        let k = 2;
+        let objective = Objective::TotalEdges;
+
+        let autos = automorphisms(&g);
+        assert_eq!(autos.len(), 4);
+
+        let all_mappings = find_all_mappings(&g, &h);
+        let canonical: Vec<&Mapping> = all_mappings
+            .iter()
+            .filter(|mapping| is_canonical_under_automorphisms(mapping, &autos))
+            .collect();
+        assert_eq!(canonical.len(), all_mappings.len() / 4);
+
+        let (unrestricted_cost, _, _) = exact_minimal_k_extension(
+            &g,
+            &h,
+            k,
+            None,
+            &objective,
+            false,
+            false,
+            None,
+            None,
+            None,
+            usize::MAX,
+            false,
+            false,
+            false,
+            false,
+            MergeSemantics::Dedicated,
+        )
+        .0
+        .expect("feasible without --mod-aut");
+
+        let (restricted_cost, _, restricted_mappings) = exact_minimal_k_extension(
+            &g,
+            &h,
+            k,
+            None,
+            &objective,
+            false,
+            false,
+            None,
+            None,
+            None,
+            usize::MAX,
+            false,
+            false,
+            true,
+            false,
+            MergeSemantics::Dedicated,
+        )
+        .0
+        .expect("feasible with --mod-aut");
+
+        assert_eq!(restricted_cost, unrestricted_cost);
+        assert!(restricted_mappings.iter().all(|m| all_mappings.contains(m)));
+    }
+
+    #[test]
+    fn test_disjoint_mappings_succeed_when_the_host_has_exactly_enough_vertices() {
+        // k * g.num_vertices() == h.num_vertices() exactly: just barely
+        // enough room for k pairwise vertex-disjoint copies of G. The host
+        // is two disjoint triangles rather than an edgeless graph so that
+        // `count_satisfying_mappings`'s upfront feasibility estimate (see
+        // `exact_minimal_k_extension`) finds some zero-cost mappings to
+        // count instead of reporting the instance infeasible before
+        // `--disjoint` even comes into play.
+        let g = Graph::from_adjacency_matrix(vec![vec![0, 1, 0], vec![0, 0, 1], vec![1, 0, 0]]);
+        let mut h_matrix = vec![vec![0; 6]; 6];
+        for &(u, v) in &[(0, 1), (1, 2), (2, 0), (3, 4), (4, 5), (5, 3)] {
+            h_matrix[u][v] = 1;
+        }
+        let h = Graph::from_adjacency_matrix(h_matrix);
+        let k = 2;
+
+        let (result, timed_out) = exact_minimal_k_extension(
+            &g,
+            &h,
+            k,
+            None,
+            &Objective::TotalEdges,
+            false,
+            false,
+            None,
+            None,
+            None,
+            usize::MAX,
+            false,
+            false,
+            false,
+            true,
+            MergeSemantics::Shared,
+        );
+        assert!(!timed_out);
+        let (_, _, mappings) = result.expect("two disjoint copies fit in exactly 6 host vertices");
+        assert_eq!(mappings.len(), k);
+
+        let mut seen_h_vertices = HashSet::new();
+        for mapping in &mappings {
+            for &v in mapping {
+                assert!(
+                    seen_h_vertices.insert(v),
+                    "mapping {:?} reuses an H vertex already claimed by another chosen mapping",
+                    mapping
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_disjoint_mappings_are_detected_as_infeasible_up_front_when_the_host_is_too_small() {
+        // One vertex short of the 3 * 3 = 9 needed for k = 3 disjoint
+        // triangles: the upfront `k * g.num_vertices() > h.num_vertices()`
+        // check should reject this before the search ever runs, rather than
+        // exhaustively discovering no valid combination exists.
+        let g = Graph::from_adjacency_matrix(vec![vec![0, 1, 0], vec![0, 0, 1], vec![1, 0, 0]]);
+        let h = Graph::from_adjacency_matrix(vec![vec![0; 8]; 8]);
+        let k = 3;
+
+        let start = std::time::Instant::now();
+        let (result, timed_out) = exact_minimal_k_extension(
+            &g,
+            &h,
+            k,
+            None,
+            &Objective::TotalEdges,
+            false,
+            false,
+            None,
+            None,
+            None,
+            usize::MAX,
+            false,
+            false,
+            false,
+            true,
+            MergeSemantics::Shared,
+        );
+        let elapsed = start.elapsed();
+
+        assert!(result.is_none());
+        assert!(!timed_out);
+        assert!(
+            elapsed < Duration::from_millis(500),
+            "expected the upfront infeasibility check to reject this instantly, took {:?}",
+            elapsed
+        );
+    }
+
+    #[test]
+    fn test_branch_and_bound_visits_far_fewer_nodes_than_brute_force() {
+        // `find_all_mappings` is edge-blind (see its doc comment): it
+        // enumerates every injective vertex assignment, so a literal
+        // 12-pattern/20-host instance would already be P(20, 12) ≈ 6*10^16
+        // candidate mappings before the search even starts. This uses a
+        // smaller pattern/host pair in the same spirit — host large and
+        // edgeless relative to the pattern, so most of the C(n, k)
+        // combination space is live — while staying cheap enough to build
+        // the candidate pool and actually run the test.
+        let pattern_edges: Vec<(usize, usize)> = (0..2).map(|i| (i, i + 1)).collect();
+        let mut g_matrix = vec![vec![0; 3]; 3];
+        for (u, v) in pattern_edges {
+            g_matrix[u][v] = 1;
+        }
+        let g = Graph::from_adjacency_matrix(g_matrix);
+        let h = Graph::from_adjacency_matrix(vec![vec![0; 7]; 7]);
+        let k = 3;
+
+        let all_mappings = find_all_mappings(&g, &h);
+        assert!(all_mappings.len() >= k);
+
+        let total_combinations = num_combinations(all_mappings.len(), k);
+        let (result, nodes_visited, _, _) = branch_and_bound_search(
+            &all_mappings,
+            &g,
+            &h,
+            k,
+            &Objective::TotalEdges,
+            None,
+            None,
+            None,
+            false,
+            MergeSemantics::Shared,
+        );
+        assert!(result.is_some());
+
+        // Every mapping here costs the same (the host is edgeless, so any
+        // k of them tie), which is the branch-and-bound's worst case for
+        // pruning: each parallel branch only prunes against its own local
+        // incumbent now (see `branch_and_bound_search`'s doc comment on why
+        // that's no longer shared live across branches), so a fully-tied
+        // instance like this one can't cut nearly as much as when a single
+        // shared bound ruled out every branch but the one that found the
+        // answer first. It should still visit well under the full
+        // combination count.
+        assert!(
+            nodes_visited * 5 < total_combinations,
+            "expected nodes_visited ({}) to be under 20% of total_combinations ({})",
+            nodes_visited,
+            total_combinations
+        );
+    }
+
+    #[test]
+    fn test_pruned_by_bound_is_nonzero_and_leaves_the_optimal_cost_unchanged() {
+        // Unlike the edgeless-host instances above, this host has a gradient
+        // of edge counts across its vertices, so standalone mapping costs
+        // actually differ and a tight incumbent found early can rule out a
+        // real tail of the sorted order via the bound -- not just via
+        // `nodes_visited` staying low, but via `pruned_by_bound` itself
+        // being nonzero.
+        let pattern_edges: Vec<(usize, usize)> = (0..2).map(|i| (i, i + 1)).collect();
+        let mut g_matrix = vec![vec![0; 3]; 3];
+        for (u, v) in pattern_edges {
+            g_matrix[u][v] = 1;
+        }
+        let g = Graph::from_adjacency_matrix(g_matrix);
+        let mut h_matrix = vec![vec![0; 6]; 6];
+        for (i, row) in h_matrix.iter_mut().enumerate() {
+            for (j, cell) in row.iter_mut().enumerate() {
+                if i != j && (i + j) % 3 == 0 {
+                    *cell = 1;
+                }
+            }
+        }
+        let h = Graph::from_adjacency_matrix(h_matrix);
+        let k = 3;
+
+        let all_mappings = find_all_mappings(&g, &h);
+        assert!(all_mappings.len() >= k);
+        let expected = brute_force_min_cost(&all_mappings, &g, &h, k, &Objective::TotalEdges);
+
+        let (result, _, pruned_by_bound, timed_out) = branch_and_bound_search(
+            &all_mappings,
+            &g,
+            &h,
+            k,
+            &Objective::TotalEdges,
+            None,
+            None,
+            None,
+            false,
+            MergeSemantics::Shared,
+        );
+        assert!(!timed_out);
+        let (actual, _, _) = result.expect("a k-combination always exists here");
+        assert_eq!(actual, expected);
+        assert!(
+            pruned_by_bound > 0,
+            "expected the cost bound to prune at least one candidate"
+        );
+    }
+
+    #[test]
+    fn test_repeated_search_reports_byte_identical_results() {
+        // Regression test for the move from a shared `AtomicUsize`/`Mutex`
+        // incumbent (racy: whichever thread's leaf ran first for a given
+        // optimal cost nondeterministically became the reported witness) to
+        // branch-local search plus a final `prefer` reduction. The host here
+        // is edgeless, so every mapping costs the same and the search is
+        // thick with cost ties — exactly the scenario that used to make
+        // which mapping set got reported depend on thread scheduling.
+        let g = Graph::from_adjacency_matrix(vec![vec![0, 1, 0], vec![0, 0, 1], vec![1, 0, 0]]);
+        let h = Graph::from_adjacency_matrix(vec![vec![0; 6]; 6]);
+        let k = 3;
+
+        let all_mappings = find_all_mappings(&g, &h);
+
+        let (first_result, _, _, _) = branch_and_bound_search(
+            &all_mappings,
+            &g,
+            &h,
+            k,
+            &Objective::TotalEdges,
+            None,
+            None,
+            None,
+            false,
+            MergeSemantics::Shared,
+        );
+        let first = first_result.expect("a k-combination always exists here");
+
+        for _ in 0..19 {
+            let (result, _, _, _) = branch_and_bound_search(
+                &all_mappings,
+                &g,
+                &h,
+                k,
+                &Objective::TotalEdges,
+                None,
+                None,
+                None,
+                false,
+                MergeSemantics::Shared,
+            );
+            assert_eq!(result, Some(first.clone()));
+        }
+    }
+
+    #[test]
+    fn test_timeout_returns_promptly_with_a_verifiable_suboptimal_solution() {
+        // Same shape as `test_branch_and_bound_visits_far_fewer_nodes_than_brute_force`:
+        // an edgeless host large relative to the pattern, so the combination
+        // space is wide and every mapping ties on cost, giving the search
+        // plenty to chew on instead of finishing before the timeout fires.
+        let pattern_edges: Vec<(usize, usize)> = (0..2).map(|i| (i, i + 1)).collect();
+        let mut g_matrix = vec![vec![0; 3]; 3];
+        for (u, v) in pattern_edges {
+            g_matrix[u][v] = 1;
+        }
+        let g = Graph::from_adjacency_matrix(g_matrix);
+        let h = Graph::from_adjacency_matrix(vec![vec![0; 9]; 9]);
+        let k = 3;
+
+        let all_mappings = find_all_mappings(&g, &h);
+        assert!(all_mappings.len() >= k);
+
+        let start = std::time::Instant::now();
+        let (result, _, _, timed_out) = branch_and_bound_search(
+            &all_mappings,
+            &g,
+            &h,
+            k,
+            &Objective::TotalEdges,
+            Some(1),
+            None,
+            None,
+            false,
+            MergeSemantics::Shared,
+        );
+        let elapsed = start.elapsed();
+
+        assert!(timed_out);
+        assert!(
+            elapsed < Duration::from_secs(10),
+            "expected the search to stop close to the 1s timeout, took {:?}",
+            elapsed
+        );
+
+        let (cost, _, chosen) = result.expect("the greedy seed already guarantees a result");
+        assert_eq!(chosen.len(), k);
+        assert_eq!(
+            cost,
+            Objective::TotalEdges.evaluate(&calculate_edge_map(
+                &g,
+                &h,
+                &chosen.iter().collect::<Vec<_>>()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_resuming_a_checkpointed_search_matches_an_uninterrupted_run() {
+        // Same wide, fully-tied combination space as the timeout tests above,
+        // so a 1s `--timeout` interrupts the search mid-way (rather than it
+        // finishing on its own) and leaves an improving incumbent for the
+        // checkpoint writer to pick up.
+        let pattern_edges: Vec<(usize, usize)> = (0..2).map(|i| (i, i + 1)).collect();
+        let mut g_matrix = vec![vec![0; 3]; 3];
+        for (u, v) in pattern_edges {
+            g_matrix[u][v] = 1;
+        }
+        let g = Graph::from_adjacency_matrix(g_matrix);
+        let h = Graph::from_adjacency_matrix(vec![vec![0; 9]; 9]);
+        let k = 3;
+
+        let all_mappings = find_all_mappings(&g, &h);
+        assert!(all_mappings.len() >= k);
+
+        let checkpoint_path = std::env::temp_dir().join("exact_solver_test_resume_checkpoint.txt");
+
+        // Kill the search mid-way by limiting how long it's allowed to run.
+        let (_, _, _, timed_out) = branch_and_bound_search(
+            &all_mappings,
+            &g,
+            &h,
+            k,
+            &Objective::TotalEdges,
+            Some(1),
+            None,
+            Some((checkpoint_path.clone(), Duration::ZERO)),
+            false,
+            MergeSemantics::Shared,
+        );
+        assert!(timed_out);
+
+        let resumed_checkpoint =
+            read_resume_result(&checkpoint_path, &g, &h, k, &Objective::TotalEdges)
+                .expect("a checkpoint for this exact instance should be accepted")
+                .expect("the interrupted search should have left a checkpoint behind");
+
+        let (resumed_result, _, _, resumed_timed_out) = branch_and_bound_search(
+            &all_mappings,
+            &g,
+            &h,
+            k,
+            &Objective::TotalEdges,
+            None,
+            Some(resumed_checkpoint),
+            None,
+            false,
+            MergeSemantics::Shared,
+        );
+        assert!(!resumed_timed_out);
+
+        let (uninterrupted_result, _, _, uninterrupted_timed_out) = branch_and_bound_search(
+            &all_mappings,
+            &g,
+            &h,
+            k,
+            &Objective::TotalEdges,
+            None,
+            None,
+            None,
+            false,
+            MergeSemantics::Shared,
+        );
+        assert!(!uninterrupted_timed_out);
+
+        assert_eq!(resumed_result, uninterrupted_result);
+    }
+
+    #[test]
+    fn test_resuming_from_a_watermark_visits_fewer_first_index_branches() {
+        // Same wide, fully-tied combination space as the timeout tests above,
+        // so there's a wide `first` range (`0..=n-k`) to skip a chunk of via
+        // an explicit watermark, rather than relying on how far a real
+        // checkpointed run happened to get before being interrupted.
+        let pattern_edges: Vec<(usize, usize)> = (0..2).map(|i| (i, i + 1)).collect();
+        let mut g_matrix = vec![vec![0; 3]; 3];
+        for (u, v) in pattern_edges {
+            g_matrix[u][v] = 1;
+        }
+        let g = Graph::from_adjacency_matrix(g_matrix);
+        let h = Graph::from_adjacency_matrix(vec![vec![0; 9]; 9]);
+        let k = 3;
+
+        let all_mappings = find_all_mappings(&g, &h);
+        assert!(all_mappings.len() > k + 10);
+
+        let (fresh_result, fresh_nodes_visited, fresh_pruned, fresh_timed_out) =
+            branch_and_bound_search(
+                &all_mappings,
+                &g,
+                &h,
+                k,
+                &Objective::TotalEdges,
+                None,
+                None,
+                None,
+                false,
+                MergeSemantics::Shared,
+            );
+        assert!(!fresh_timed_out);
+        let (fresh_cost, _, fresh_mappings) =
+            fresh_result.expect("this instance always has a feasible solution");
+
+        // Resume from a watermark that skips roughly half of the first-index
+        // range, seeded with the fresh run's own answer (any solution that
+        // isn't worse than the true optimum works, since it's only there to
+        // seed the bound).
+        let watermark = (all_mappings.len() - k) / 2;
+        let seeded_edge_map =
+            calculate_edge_map(&g, &h, &fresh_mappings.iter().collect::<Vec<_>>());
+        let resume_result = (fresh_cost, seeded_edge_map, fresh_mappings);
+
+        let (resumed_result, resumed_nodes_visited, resumed_pruned, resumed_timed_out) =
+            branch_and_bound_search(
+                &all_mappings,
+                &g,
+                &h,
+                k,
+                &Objective::TotalEdges,
+                None,
+                Some((resume_result, watermark)),
+                None,
+                false,
+                MergeSemantics::Shared,
+            );
+        assert!(!resumed_timed_out);
+
+        assert_eq!(resumed_result.map(|(cost, _, _)| cost), Some(fresh_cost));
+        assert!(
+            resumed_nodes_visited + resumed_pruned < fresh_nodes_visited + fresh_pruned,
+            "resuming from watermark {} should visit or prune fewer first-index branches \
+             than a fresh run: resumed {} + {} vs fresh {} + {}",
+            watermark,
+            resumed_nodes_visited,
+            resumed_pruned,
+            fresh_nodes_visited,
+            fresh_pruned
+        );
+    }
+
+    #[test]
+    fn test_resume_is_rejected_against_a_different_instance() {
+        let (g, h) = triangle_into_five_cycle();
+        let k = 3;
+        let checkpoint_path =
+            std::env::temp_dir().join("exact_solver_test_resume_hash_mismatch_checkpoint.txt");
+        write_checkpoint_file(
+            &checkpoint_path,
+            k,
+            0,
+            &[vec![0, 1, 2]],
+            Duration::ZERO,
+            instance_hash(&g, &h, k, &Objective::TotalEdges),
+            0,
+        )
+        .unwrap();
+
+        let other_h = Graph::from_adjacency_matrix(vec![vec![0; 4]; 4]);
+        let result = read_resume_result(&checkpoint_path, &g, &other_h, k, &Objective::TotalEdges);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_write_ilp_model_has_the_expected_variable_and_constraint_counts() {
+        let (g, h) = triangle_into_five_cycle();
+        let k = 3;
+        let max_candidates = 5;
+
+        let all_mappings = find_all_mappings(&g, &h);
+        assert!(all_mappings.len() >= max_candidates);
+
+        let path = std::env::temp_dir().join("exact_solver_test_ilp_export.lp");
+        write_ilp_model(&path, &all_mappings, &g, &h, k, Some(max_candidates)).unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+
+        let pattern_edge_count = 3; // the triangle has 3 edges
+        let expected_constraint_count = max_candidates * pattern_edge_count;
+
+        let constraint_lines = contents
+            .lines()
+            .filter(|line| line.trim_start().starts_with("edge_m"))
+            .count();
+        assert_eq!(constraint_lines, expected_constraint_count);
+
+        let pick_lines = contents
+            .lines()
+            .skip_while(|line| *line != "Binary")
+            .skip(1)
+            .take_while(|line| !line.is_empty())
+            .count();
+        assert_eq!(pick_lines, max_candidates);
+
+        let added_lines = contents
+            .lines()
+            .skip_while(|line| *line != "General")
+            .skip(1)
+            .take_while(|line| !line.is_empty())
+            .count();
+        assert!(added_lines >= 1 && added_lines <= max_candidates * pattern_edge_count);
+
+        assert!(contents.contains("Minimize"));
+        assert!(contents.contains("Subject To"));
+        assert!(contents.contains(&format!("= {}", k)));
+        assert!(contents.contains("End"));
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    /// A single directed edge pattern against an empty triangle host: every
+    /// mapping needs exactly one edge added, and since each mapping picks a
+    /// distinct (source, target) pair, no two mappings ever need the same
+    /// edge, so every 2-combination costs exactly 2. With `C(6, 2) = 15`
+    /// (6 ordered pairs over 3 host vertices), this instance is symmetric
+    /// enough that *every* combination ties the optimum -- the case
+    /// `--all-optimal` exists for.
+    fn single_edge_into_empty_triangle() -> (Graph, Graph) {
+        let g = Graph::from_adjacency_matrix(vec![vec![0, 1], vec![0, 0]]);
+        let h = Graph::from_adjacency_matrix(vec![vec![0, 0, 0]; 3]);
+        (g, h)
+    }
+
+    #[test]
+    fn test_collect_all_optimal_reports_every_tied_combination() {
+        let (g, h) = single_edge_into_empty_triangle();
+        let k = 2;
+        let objective = Objective::TotalEdges;
+
+        // `exact_minimal_k_extension`'s inclusion-exclusion feasibility
+        // pre-check assumes the pattern's edges already exist somewhere in
+        // the host (see `count_satisfying_mappings`), which an edgeless
+        // host fails by construction even though every mapping is still a
+        // perfectly valid (costly) extension candidate. Get the optimum
+        // straight from `brute_force_min_cost` instead of going through
+        // that gate.
+        let all_mappings = find_all_mappings(&g, &h);
+        let optimal_cost = brute_force_min_cost(&all_mappings, &g, &h, k, &objective);
+        assert_eq!(optimal_cost, 2);
+
+        let (ties, truncated) = collect_all_optimal(
+            &g,
+            &h,
+            k,
+            None,
+            &objective,
+            false,
+            false,
+            false,
+            false,
+            MergeSemantics::Shared,
+            optimal_cost,
+            usize::MAX,
+        );
+
+        assert!(!truncated);
+        assert_eq!(ties.len(), num_combinations(6, 2));
+        assert!(ties.iter().all(|(cost, _, _)| *cost == optimal_cost));
+
+        let distinct: HashSet<Vec<Mapping>> = ties
+            .iter()
+            .map(|(_, _, mappings)| canonical_mappings(mappings))
+            .collect();
+        assert_eq!(distinct.len(), ties.len());
+    }
+
+    #[test]
+    fn test_collect_all_optimal_cap_truncates_and_reports_it() {
+        let (g, h) = single_edge_into_empty_triangle();
+        let k = 2;
+        let objective = Objective::TotalEdges;
+        let cap = 5;
+
+        let (ties, truncated) = collect_all_optimal(
+            &g,
+            &h,
+            k,
+            None,
+            &objective,
+            false,
+            false,
+            false,
+            false,
+            MergeSemantics::Shared,
+            2,
+            cap,
+        );
+
+        assert!(truncated);
+        assert_eq!(ties.len(), cap);
+    }
+
+    #[test]
+    fn test_collect_top_n_is_sorted_deduplicated_and_matches_the_optimum() {
+        let (g, h) = triangle_into_five_cycle();
+        let k = 2;
+        let objective = Objective::TotalEdges;
+
+        let (result, timed_out) = exact_minimal_k_extension(
+            &g,
+            &h,
+            k,
+            None,
+            &objective,
+            false,
+            false,
+            None,
+            None,
+            None,
+            usize::MAX,
+            false,
+            false,
+            false,
+            false,
+            MergeSemantics::Shared,
+        );
+        let (optimal_cost, _, _) = result.expect("k=2 is feasible on a 3-into-5-vertex instance");
+        assert!(!timed_out);
+
+        let top = collect_top_n(
+            &g,
+            &h,
+            k,
+            None,
+            &objective,
+            false,
+            false,
+            false,
+            false,
+            MergeSemantics::Shared,
+            5,
+        );
+
+        assert!(!top.is_empty());
+        assert_eq!(top[0].cost, optimal_cost);
+        for pair in top.windows(2) {
+            assert!(pair[0].cost <= pair[1].cost);
+        }
+
+        let distinct: HashSet<Vec<Mapping>> = top
+            .iter()
+            .map(|solution| canonical_mappings(&solution.mappings))
+            .collect();
+        assert_eq!(distinct.len(), top.len());
+    }
+
+    #[test]
+    fn test_collect_top_n_caps_the_result_at_n() {
+        let (g, h) = single_edge_into_empty_triangle();
+        let k = 2;
+        let objective = Objective::TotalEdges;
+        let n = 3;
+
+        let top = collect_top_n(
+            &g,
+            &h,
+            k,
+            None,
+            &objective,
+            false,
+            false,
+            false,
+            false,
+            MergeSemantics::Shared,
+            n,
+        );
+
+        // Every 2-combination on this instance ties at cost 2 (see
+        // `single_edge_into_empty_triangle`), so the heap fills to exactly
+        // `n` rather than running out of distinct combinations first.
+        assert_eq!(top.len(), n);
+        assert!(top.iter().all(|solution| solution.cost == 2));
+    }
+}
@@ -0,0 +1,5969 @@
+use clap::{Parser, ValueEnum};
+use crossterm::{
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind,
+        KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+    },
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use itertools::Itertools;
+use minimal_k_iso_lib::{
+    approx::{approximate_best_mapping, refine_mapping_sa, EarlyStop, SaSchedule, SeedStrategy},
+    augmentation::pad_host_to_pattern_size,
+    cost::{
+        apply_edge_map, approximation_lower_bound, breakdown, calculate_cost_matrix,
+        calculate_edge_map, compute_edge_delta, cost_lower_bound, coverage_analysis,
+        format_approximation_gap, marginal_cost, sharing_stats, EdgeMapAccumulator,
+        MergeSemantics, Objective,
+    },
+    formats::to_networkx_solution_string,
+    mapping::{
+        count_satisfying_mappings, find_all_mappings, find_all_mappings_undirected,
+        find_all_mappings_with_progress, local_search_2opt, MappingSet,
+    },
+    output::write_edge_list,
+    parser::{
+        parse_all_graph_pairs_file, parse_input_file, parse_input_file_matrix_market, parse_stdin,
+        parse_stdin_matrix_market,
+    },
+    progress::{Algorithm, CompareResult, ProgressMessage},
+    stats::{GraphStats, MAX_VERTICES_FOR_DIAMETER},
+    utils::{estimate_memory_bytes, num_combinations, permutation_count, BinomialTable},
+    Graph, Mapping,
+};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use ratatui::{
+    backend::CrosstermBackend,
+    layout::{Alignment, Constraint, Direction, Layout, Margin, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, Paragraph, Row, Table, Wrap},
+    Frame, Terminal,
+};
+use rayon::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Type alias for edge map: (source, target) -> edge count
+type EdgeMap = HashMap<(usize, usize), usize>;
+/// Type alias for a completed search result: total cost, its edge map, and
+/// the mappings that produced it.
+type SolutionResult = (usize, EdgeMap, Vec<Mapping>);
+
+/// One incumbent improvement, sent from `search_combinations` across a
+/// channel to `run_incumbent_writer` so the rayon search workers that find it
+/// never block on the file IO needed to record it (see
+/// `run_incumbent_writer`'s doc comment).
+struct IncumbentUpdate {
+    cost: usize,
+    edge_map: EdgeMap,
+    mappings: Vec<Mapping>,
+    elapsed: Duration,
+    nodes_visited: usize,
+}
+
+/// Selects an `Objective` from the CLI. `WeightedTotal` needs a weight map
+/// that has no natural CLI representation, so it's only reachable by
+/// constructing `Objective` directly from library code.
+#[derive(ValueEnum, Clone, Copy, Debug, Default)]
+enum ObjectiveArg {
+    #[default]
+    TotalEdges,
+    MaxEdgeMultiplicity,
+}
+
+impl From<ObjectiveArg> for Objective {
+    fn from(arg: ObjectiveArg) -> Self {
+        match arg {
+            ObjectiveArg::TotalEdges => Objective::TotalEdges,
+            ObjectiveArg::MaxEdgeMultiplicity => Objective::MaxEdgeMultiplicity,
+        }
+    }
+}
+
+/// Which post-construction refinement, if any, to run on each approx
+/// mapping before it's committed (see `approx_solver`'s flag of the same
+/// name).
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum RefineArg {
+    /// Keep the greedy construction's mapping as-is. The default.
+    #[default]
+    None,
+    /// Refine it by simulated annealing (see `approx::refine_mapping_sa`).
+    Sa,
+    /// Refine it by deterministic 2-opt hill-climbing (see
+    /// `mapping::local_search_2opt`). Cheaper than `sa` and never accepts a
+    /// worsening move, so it can settle for a worse local optimum in
+    /// exchange for being deterministic and needing no `Rng`.
+    #[value(name = "2opt")]
+    TwoOpt,
+}
+
+/// The resolved refinement `--refine`/`--refine-iterations`/
+/// `--refine-temperature` imply, carrying whatever data the chosen kind
+/// needs (see `RefineArg`, and `approx_solver`'s type of the same name).
+#[derive(Clone, Copy, Debug)]
+enum RefineMode {
+    Sa(SaSchedule),
+    TwoOpt,
+}
+
+/// Builds the `RefineMode` `--refine` implies from `args`, or `None` when
+/// `--refine` wasn't given. Shared by the batch and TUI dispatch paths so
+/// they can't drift on how `--refine-iterations`/`--refine-temperature` are
+/// interpreted.
+fn refine_schedule_from_args(args: &Args) -> Option<RefineMode> {
+    match args.refine {
+        RefineArg::None => None,
+        RefineArg::Sa => Some(RefineMode::Sa(SaSchedule {
+            iterations: args.refine_iterations,
+            initial_temperature: args.refine_temperature,
+        })),
+        RefineArg::TwoOpt => Some(RefineMode::TwoOpt),
+    }
+}
+
+/// Which per-trial construction strategy builds a candidate approx mapping
+/// before any `--refine` pass runs on top of it (see `approx_solver`'s flag
+/// of the same name).
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum ConstructionArg {
+    /// Extend the partial mapping one G vertex at a time, keeping only the
+    /// single best candidate at each step. The default.
+    #[default]
+    Greedy,
+    /// Keep the `--beam-width` best partial mappings at each step instead of
+    /// just one (see `approx::beam_search_construct`). `--beam-width 1`
+    /// reproduces `greedy` exactly.
+    Beam,
+}
+
+/// Selects a `SeedStrategy` from the CLI (see `approx_solver`'s flag of the
+/// same name).
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum SeedStrategyArg {
+    /// Seed each trial from G's highest-degree vertices and H's
+    /// best-covering vertices (see `approx::SeedStrategy::HighestDegree`).
+    /// The default.
+    #[default]
+    HighestDegree,
+    /// Seed each trial uniformly at random, the original behavior, kept for
+    /// comparison.
+    Random,
+}
+
+impl From<SeedStrategyArg> for SeedStrategy {
+    fn from(arg: SeedStrategyArg) -> Self {
+        match arg {
+            SeedStrategyArg::HighestDegree => SeedStrategy::HighestDegree,
+            SeedStrategyArg::Random => SeedStrategy::Random,
+        }
+    }
+}
+
+/// Format of `--input` (and every file `--batch` matches): either this
+/// crate's native `n` + adjacency-matrix text format, or two back-to-back
+/// Matrix Market coordinate blocks (see
+/// `parser::parse_two_graphs_matrix_market`).
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum InputFormat {
+    #[default]
+    Native,
+    MatrixMarket,
+}
+
+impl InputFormat {
+    /// Parse `path` as a `(G, H)` pair according to this format.
+    fn parse(self, path: &PathBuf) -> Result<(Graph, Graph), String> {
+        match self {
+            InputFormat::Native => parse_input_file(path).map_err(|e| e.to_string()),
+            InputFormat::MatrixMarket => {
+                parse_input_file_matrix_market(path).map_err(|e| e.to_string())
+            }
+        }
+    }
+
+    /// Parse a `(G, H)` pair read from stdin according to this format (see
+    /// `parser::parse_stdin`/`parser::parse_stdin_matrix_market`), for
+    /// `--stdin`.
+    fn parse_stdin(self) -> Result<(Graph, Graph), String> {
+        match self {
+            InputFormat::Native => parse_stdin().map_err(|e| e.to_string()),
+            InputFormat::MatrixMarket => {
+                parse_stdin_matrix_market().map_err(|e| e.to_string())
+            }
+        }
+    }
+}
+
+/// Command-line arguments
+#[derive(Parser, Debug)]
+#[command(author, version, about = "Unified Solver for Minimal k-Isomorphic Subgraph Extension", long_about = None)]
+struct Args {
+    /// Algorithm to use: 'exact', 'approx', or 'compare' (runs both and
+    /// reports the approximation ratio between them, see
+    /// `run_compare_algorithm`)
+    #[arg(short, long)]
+    algorithm: Algorithm,
+
+    /// Path to the input file containing graph descriptions. Required
+    /// unless `--batch` is given, which solves a whole glob of files
+    /// instead of this single one, or `--stdin` is given (or this is
+    /// omitted entirely), which reads the pair from stdin instead.
+    #[arg(short, long)]
+    input: Option<PathBuf>,
+
+    /// Read the graph descriptions from stdin instead of `--input`, for
+    /// piping input directly from an earlier command. Read to completion
+    /// (with a 10-second timeout, see `parser::parse_stdin`) before the TUI
+    /// starts, since the TUI also reads keystrokes from stdin. Implied by
+    /// omitting `--input` when `--batch` isn't given either.
+    #[arg(long)]
+    stdin: bool,
+
+    /// Number of distinct isomorphic mappings required (k)
+    #[arg(short, long)]
+    k: usize,
+
+    /// Format `--input` (and every `--batch` match) is written in: this
+    /// crate's native text format, or two back-to-back Matrix Market
+    /// coordinate blocks (see `parser::from_matrix_market`), one for G and
+    /// one for H.
+    #[arg(long, value_enum, default_value_t = InputFormat::Native)]
+    format: InputFormat,
+
+    /// Output file path for results. Default: solution_{algorithm}.txt
+    /// If not specified and graph has >15 vertices, output goes to file automatically.
+    #[arg(short, long)]
+    output_file: Option<PathBuf>,
+
+    /// Also write G's edges, H's edges, and the computed extension as a
+    /// sparse `u\tv\tweight` TSV to this path (see
+    /// `output::write_edge_list`), instead of only the dense adjacency
+    /// matrices `--output-file` reports. Under `--batch`, only presence is
+    /// used -- each instance gets its own `edges_{name}.tsv` in
+    /// `--batch-output-dir` instead of all instances colliding on this path.
+    #[arg(long)]
+    output_edge_list: Option<PathBuf>,
+
+    /// Also write a NetworkX Python script string to this path, rebuilding
+    /// G, H, and H_extended as `nx.MultiDiGraph`s with the added edges called
+    /// out (see `formats::to_networkx_solution_string`) -- paste straight
+    /// into a Jupyter notebook cell to visualize the solution. Under
+    /// `--batch`, only presence is used, same as `--output-edge-list`.
+    #[arg(long)]
+    output_networkx: Option<PathBuf>,
+
+    /// Number of independent randomized restarts for the approx algorithm;
+    /// the lowest-cost run is kept. Ignored by the exact algorithm.
+    #[arg(long, default_value_t = 1)]
+    restarts: usize,
+
+    /// Wall-clock budget in seconds for the approx algorithm, instead of a
+    /// fixed trial count. The remaining budget is divided evenly across the
+    /// mappings not yet found (recomputed after each one commits) and every
+    /// restart under `--restarts` shares the same overall deadline. Always
+    /// finishes with a complete, verifiable set of `k` mappings -- even a
+    /// mapping started after the deadline runs at least one trial. Ignored
+    /// by the exact algorithm, which has `--checkpoint-interval` instead.
+    #[arg(long)]
+    time_limit: Option<f64>,
+
+    /// Which scalar objective to minimize.
+    #[arg(long, value_enum, default_value_t = ObjectiveArg::TotalEdges)]
+    objective: ObjectiveArg,
+
+    /// Treat G and H as undirected (see `Graph::as_undirected`) before
+    /// searching. The exact algorithm also skips mappings that are just a
+    /// relabeling of one already tried (see
+    /// `mapping::find_all_mappings_undirected`).
+    #[arg(long)]
+    undirected: bool,
+
+    /// Minimum number of seconds between checkpoint writes of the
+    /// best-so-far solution to `output_file` while the exact algorithm is
+    /// still running. Ignored unless `output_file` is set. Ignored by the
+    /// approx algorithm, which is already fast enough that a final write
+    /// suffices.
+    #[arg(long)]
+    checkpoint_interval: Option<u64>,
+
+    /// Resume an interrupted exact run: read the "Total Cost (edges added)"
+    /// line out of a previous checkpoint (or completed solution) file and
+    /// use it to seed the search's best-known cost, so combinations no
+    /// better than it are skipped without being re-evaluated.
+    #[arg(long)]
+    resume_from: Option<PathBuf>,
+
+    /// Stream the exact algorithm's incumbent to this path every time it
+    /// improves (atomic rename, so a reader polling the file never observes
+    /// a half-written one), instead of only at `--checkpoint-interval`. The
+    /// TUI's calculating view also shows a short history of recent
+    /// improvements. Ignored by the approx algorithm, which has no notion of
+    /// an improving incumbent, and by `--batch`, which writes one solution
+    /// file per instance already.
+    #[arg(long)]
+    incumbent_file: Option<PathBuf>,
+
+    /// When H has fewer vertices than G, pad it with isolated vertices up to
+    /// G's size instead of reporting no solution (see
+    /// `augmentation::pad_host_to_pattern_size`). Every padded vertex is
+    /// always used by the resulting mappings and contributes `--vertex-cost`
+    /// to the reported total; the Extension view labels the synthetic
+    /// vertices. A no-op when H already has at least as many vertices as G.
+    #[arg(long)]
+    allow_vertex_additions: bool,
+
+    /// Cost charged per vertex added to H by `--allow-vertex-additions`.
+    #[arg(long, default_value_t = 1)]
+    vertex_cost: usize,
+
+    /// Cap, in megabytes, on the estimated memory `find_all_mappings` would
+    /// use to materialize the full candidate pool (see
+    /// `utils::estimate_memory_bytes`). Ignored by the approx algorithm,
+    /// which never materializes the full pool. If the exact algorithm's
+    /// estimate exceeds this, it prints a warning and falls back to running
+    /// the approx algorithm instead, using `--restarts`.
+    #[arg(long)]
+    memory_limit: Option<usize>,
+
+    /// Report problem-size statistics (satisfying mapping count, candidate
+    /// combinations, estimated exact-solver memory, and a one-trial approx
+    /// cost upper bound) and wait for confirmation before running the actual
+    /// computation. Lets you judge exact vs. approx without committing to a
+    /// run first.
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Print a structural statistics report for G and H -- vertex/edge
+    /// counts, density, weighted-degree histogram, strongly connected
+    /// component sizes, diameter (when affordable), an estimate of how many
+    /// injective mappings exist, and the candidate combination count for
+    /// `--k` -- and exit without running either solver algorithm. Unlike
+    /// `--dry-run`, this never prompts and never touches the actual search,
+    /// so it completes in roughly O(n^2) time and works even for a `--k`
+    /// too large to be satisfiable or an instance this crate can't embed at
+    /// all.
+    #[arg(long)]
+    stats_only: bool,
+
+    /// Stop the exact algorithm's branch-and-bound search after this many
+    /// seconds and report the best solution found so far instead of running
+    /// to completion (labeled as unproven-optimal everywhere it's reported).
+    /// If no solution exists yet when time runs out, falls back to the
+    /// approx algorithm, same as `--memory-limit`. Ignored by the approx
+    /// algorithm itself, which is expected to already finish quickly.
+    #[arg(long)]
+    timeout: Option<u64>,
+
+    /// Refuse to start the exact algorithm's branch-and-bound search when
+    /// the number of k-combinations to consider exceeds this many; the TUI
+    /// reports the estimate and a calibrated runtime projection instead and
+    /// offers to switch to the approx algorithm with a keypress.
+    /// `num_combinations` can reach the 10^18 range on a large instance,
+    /// which is antisocial to launch unannounced on a shared machine.
+    /// Bypass with `--force`. Ignored by the approx algorithm and by
+    /// `--batch`, which runs unattended and always proceeds as if `--force`
+    /// were given.
+    #[arg(long, default_value_t = 1_000_000_000)]
+    max_combinations: usize,
+
+    /// Bypass the `--max-combinations` guard and start the search regardless
+    /// of its estimated size.
+    #[arg(long)]
+    force: bool,
+
+    /// Solve every file matching this glob (e.g. `instances/*.txt`) in one
+    /// invocation instead of launching the interactive TUI on `--input`.
+    /// Each match is solved independently in parallel (see
+    /// `rayon::par_iter`) with the algorithm, k, and every other flag above
+    /// applied the same way to all of them; a failure on one instance is
+    /// logged and skipped rather than aborting the rest. `--input` is
+    /// ignored when this is set. Mutually exclusive with `--batch-file`;
+    /// `--batch-file` wins if both are given.
+    #[arg(long)]
+    batch: Option<String>,
+
+    /// Solve every graph pair in this single `---`-delimited combined
+    /// instances file (see `parser::parse_all_graph_pairs_file`) instead of
+    /// a glob of separate files. Otherwise behaves exactly like `--batch`:
+    /// same parallel fan-out, same flags applied to every pair, same
+    /// per-instance error handling. `--input` and `--format` are ignored
+    /// when this is set (every block is always the native text format).
+    #[arg(long)]
+    batch_file: Option<PathBuf>,
+
+    /// Directory to write `--batch`/`--batch-file` solution files into.
+    /// Default: the current directory.
+    #[arg(long, default_value = ".")]
+    batch_output_dir: PathBuf,
+
+    /// Number of threads the exact algorithm's branch-and-bound fan-out (and
+    /// the approx algorithm's `--restarts` fan-out, and `--batch`'s
+    /// per-instance fan-out) uses. Builds a scoped `rayon::ThreadPoolBuilder`
+    /// pool instead of relying on the global pool, so this can be set
+    /// per-run without the `RAYON_NUM_THREADS` env var (which a job wrapper
+    /// may not be able to set) and without affecting any other rayon-using
+    /// process sharing the machine. `0` (the default) keeps the existing
+    /// behavior of using the global pool's thread count. Echoed in the
+    /// solution report written to `--output-file`.
+    #[arg(long, default_value_t = 0)]
+    threads: usize,
+
+    /// Trials multiplier for the approx algorithm's greedy construction
+    /// (see `approx_solver`'s flag of the same name): number of trials per
+    /// mapping = n₁ × n₂ × this. Ignored by the exact algorithm.
+    #[arg(short = 't', long, default_value_t = 1)]
+    trials_multiplier: usize,
+
+    /// Seed for the approx algorithm's RNG, for reproducible runs (see
+    /// `approx_solver`'s flag of the same name). When omitted, a seed is
+    /// drawn from entropy and printed to stdout, so a run that produces an
+    /// interesting (or buggy) result can be reproduced exactly by passing
+    /// that same seed back in. Ignored by the exact algorithm, except that
+    /// it's still forwarded to whatever approx fallback `--memory-limit` or
+    /// `--timeout` might trigger.
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// Post-construction refinement to run on each approx mapping before
+    /// it's committed (see `approx_solver`'s flag of the same name).
+    /// Ignored by the exact algorithm.
+    #[arg(long, value_enum, default_value_t = RefineArg::None)]
+    refine: RefineArg,
+
+    /// Iteration budget for `--refine sa`'s simulated annealing search.
+    /// Ignored unless `--refine sa` is set.
+    #[arg(long, default_value_t = SaSchedule::default().iterations)]
+    refine_iterations: usize,
+
+    /// Starting Metropolis temperature for `--refine sa`, cooling linearly
+    /// to 0 over `--refine-iterations` (see `SaSchedule`). Ignored unless
+    /// `--refine sa` is set.
+    #[arg(long, default_value_t = SaSchedule::default().initial_temperature)]
+    refine_temperature: f64,
+
+    /// Per-trial construction strategy for the approx algorithm (see
+    /// `ConstructionArg`). Ignored by the exact algorithm.
+    #[arg(long, value_enum, default_value_t = ConstructionArg::Greedy)]
+    construction: ConstructionArg,
+
+    /// Number of partial mappings kept alive at each step of
+    /// `--construction beam` (see `approx::beam_search_construct`). Ignored
+    /// unless `--construction beam` is set.
+    #[arg(long, default_value_t = 16)]
+    beam_width: usize,
+
+    /// How each approx trial picks its starting `(u_start, v_start)` pair
+    /// (see `approx_solver`'s flag of the same name). Ignored by the exact
+    /// algorithm.
+    #[arg(long, value_enum, default_value_t = SeedStrategyArg::HighestDegree)]
+    seed_strategy: SeedStrategyArg,
+    /// Break a cost tie during construction in favor of the lowest-index H
+    /// vertex instead of choosing uniformly at random among the tied
+    /// candidates (see `approx::beam_search_construct`, and `approx_solver`'s
+    /// flag of the same name). The randomized default gives repeated trials
+    /// from the same seed pair a chance to diverge; this restores the old
+    /// always-lowest-index behavior for comparison or reproducibility.
+    #[arg(long)]
+    deterministic_ties: bool,
+}
+
+/// Current view in the TUI
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum View {
+    Calculating,
+    Menu,
+    Graphs,
+    Extension,
+    Mappings,
+    Coverage,
+    /// The keybinding help screen, opened by pressing `?` from any other
+    /// view (see `AppState::handle_key`). Carries the view it was opened
+    /// from so both the keybinding table and the return-on-dismiss
+    /// navigation know where to go back to.
+    Help(Box<View>),
+}
+
+/// Viewport for scrolling large matrices
+#[derive(Debug, Clone, Default)]
+struct Viewport {
+    row_offset: usize,
+    col_offset: usize,
+}
+
+/// Application state
+struct AppState {
+    // Input data
+    algorithm: Algorithm,
+    g: Graph,
+    h: Graph,
+    k: usize,
+    vertices_added: usize,
+    vertex_cost: usize,
+
+    // Calculation state
+    calculating: bool,
+    start_time: Instant,
+    status_message: String,
+    current_mapping: usize,
+    total_mappings: usize,
+    spinner_frame: usize,
+    /// The most recent `INCUMBENT_HISTORY_LEN` improvements reported by
+    /// `run_incumbent_writer`, oldest first. Only ever populated when
+    /// `--incumbent-file` is set.
+    incumbent_history: Vec<(usize, Duration, usize)>,
+
+    // Results
+    cost: Option<usize>,
+    edge_map: Option<EdgeMap>,
+    mappings: Option<Vec<Mapping>>,
+    elapsed: Option<Duration>,
+    search_stats: Option<(usize, usize)>,
+    timed_out: bool,
+    /// Set once `--algorithm compare` finishes both sub-algorithms; `cost`,
+    /// `edge_map`, and `mappings` above are populated from its exact half so
+    /// the Graphs/Mappings views work unchanged, while `render_extension` and
+    /// `render_menu` reach into this directly for the side-by-side comparison.
+    compare_result: Option<CompareResult>,
+    /// A lower bound on the true optimal cost, computed once when an
+    /// `Algorithm::Approx` run completes (see `cost::approximation_lower_bound`).
+    /// `None` for `Exact`/`Compare` runs, where the true optimum is already known.
+    approximation_lower_bound: Option<usize>,
+
+    // UI state
+    current_view: View,
+    selected_mapping: usize,
+    viewport_g: Viewport,
+    viewport_h: Viewport,
+    viewport_ext: Viewport,
+    viewport_mappings: Viewport,
+    viewport_coverage: Viewport,
+
+    // File output
+    output_file: Option<PathBuf>,
+    output_edge_list: Option<PathBuf>,
+    output_networkx: Option<PathBuf>,
+
+    // Progress channel
+    progress_rx: Receiver<ProgressMessage>,
+
+    /// Kept only so the Calculating view's "switch to approx" keypress (see
+    /// `combination_guard` below) can spawn a fresh `run_approx_algorithm`
+    /// thread on its own, without the rest of `AppState` needing to know
+    /// about the approx algorithm's parameters at all otherwise.
+    objective: Objective,
+    refine: Option<RefineMode>,
+    beam_width: usize,
+    seed_strategy: SeedStrategy,
+    deterministic_ties: bool,
+    restarts: usize,
+    time_limit: Option<f64>,
+    trials_multiplier: usize,
+    seed: u64,
+    progress_tx: Sender<ProgressMessage>,
+    /// `--threads`, kept around only to echo into `write_results_to_file`'s
+    /// report; the pool it configures is built and installed once in `main`,
+    /// around the whole algorithm thread.
+    threads: usize,
+    /// Set when `run_exact_algorithm` refuses to start because
+    /// `num_combinations(all_mappings.len(), k)` exceeds `--max-combinations`
+    /// (see `ProgressMessage::CombinationGuardTriggered`). `render_calculating`
+    /// shows this instead of the usual spinner, and `handle_key` reacts to
+    /// 'a' on it by launching the approx algorithm in its place.
+    combination_guard: Option<CombinationGuardInfo>,
+}
+
+/// The `ProgressMessage::CombinationGuardTriggered` payload, kept around in
+/// `AppState` so `render_calculating` can keep displaying it across ticks.
+#[derive(Debug, Clone, Copy)]
+struct CombinationGuardInfo {
+    total_combinations: usize,
+    max_combinations: usize,
+    projected_runtime: Duration,
+}
+
+const SPINNER_FRAMES: &[&str] = &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+/// How many recent incumbent improvements `render_calculating` shows at once.
+const INCUMBENT_HISTORY_LEN: usize = 5;
+
+impl AppState {
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        algorithm: Algorithm,
+        g: Graph,
+        h: Graph,
+        k: usize,
+        vertices_added: usize,
+        vertex_cost: usize,
+        output_file: Option<PathBuf>,
+        output_edge_list: Option<PathBuf>,
+        output_networkx: Option<PathBuf>,
+        progress_rx: Receiver<ProgressMessage>,
+        objective: Objective,
+        refine: Option<RefineMode>,
+        beam_width: usize,
+        seed_strategy: SeedStrategy,
+        deterministic_ties: bool,
+        restarts: usize,
+        time_limit: Option<f64>,
+        trials_multiplier: usize,
+        seed: u64,
+        progress_tx: Sender<ProgressMessage>,
+        threads: usize,
+    ) -> Self {
+        Self {
+            algorithm,
+            g,
+            h,
+            k,
+            vertices_added,
+            vertex_cost,
+            calculating: true,
+            start_time: Instant::now(),
+            status_message: "Initializing...".to_string(),
+            current_mapping: 0,
+            total_mappings: k,
+            spinner_frame: 0,
+            incumbent_history: Vec::new(),
+            cost: None,
+            edge_map: None,
+            mappings: None,
+            elapsed: None,
+            search_stats: None,
+            timed_out: false,
+            compare_result: None,
+            approximation_lower_bound: None,
+            current_view: View::Calculating,
+            selected_mapping: 0,
+            viewport_g: Viewport::default(),
+            viewport_h: Viewport::default(),
+            viewport_ext: Viewport::default(),
+            viewport_mappings: Viewport::default(),
+            viewport_coverage: Viewport::default(),
+            output_file,
+            output_edge_list,
+            output_networkx,
+            progress_rx,
+            objective,
+            refine,
+            beam_width,
+            seed_strategy,
+            deterministic_ties,
+            restarts,
+            time_limit,
+            trials_multiplier,
+            seed,
+            progress_tx,
+            threads,
+            combination_guard: None,
+        }
+    }
+
+    fn update(&mut self) -> io::Result<()> {
+        // Advance spinner animation
+        if self.calculating {
+            self.spinner_frame = (self.spinner_frame + 1) % SPINNER_FRAMES.len();
+        }
+
+        // Check for progress messages (non-blocking)
+        while let Ok(msg) = self.progress_rx.try_recv() {
+            match msg {
+                ProgressMessage::Status(status) => {
+                    self.status_message = status;
+                }
+                ProgressMessage::MappingProgress { current, total } => {
+                    self.current_mapping = current;
+                    self.total_mappings = total;
+                }
+                ProgressMessage::RestartProgress {
+                    restart,
+                    total_restarts,
+                    best_cost_so_far,
+                } => {
+                    self.status_message = match best_cost_so_far {
+                        Some(cost) => format!(
+                            "Restart {}/{} (best so far: {})",
+                            restart, total_restarts, cost
+                        ),
+                        None => format!("Restart {}/{} (no result yet)", restart, total_restarts),
+                    };
+                }
+                ProgressMessage::Complete {
+                    algorithm,
+                    cost,
+                    edge_map,
+                    mappings,
+                    elapsed,
+                    search_stats,
+                    estimated_memory_bytes,
+                    timed_out,
+                } => {
+                    self.calculating = false;
+                    self.algorithm = algorithm;
+                    self.cost = Some(cost);
+                    self.edge_map = Some(edge_map.clone());
+                    self.mappings = Some(mappings.clone());
+                    self.elapsed = Some(elapsed);
+                    self.search_stats = search_stats;
+                    self.timed_out = timed_out;
+                    self.approximation_lower_bound = if algorithm == Algorithm::Approx {
+                        Some(approximation_lower_bound(&self.g, &self.h, self.k, MergeSemantics::Shared))
+                    } else {
+                        None
+                    };
+
+                    // Save to file if output_file is set
+                    if let Some(ref path) = self.output_file {
+                        let _ = write_results_to_file(
+                            path,
+                            &self.g,
+                            &self.h,
+                            self.k,
+                            self.algorithm,
+                            cost,
+                            self.vertices_added,
+                            self.vertex_cost,
+                            &edge_map,
+                            &mappings,
+                            elapsed,
+                            false,
+                            estimated_memory_bytes,
+                            timed_out,
+                            Some(self.threads),
+                            self.approximation_lower_bound,
+                        );
+                    }
+                    if let Some(ref path) = self.output_edge_list {
+                        let _ = write_edge_list_report(path, &self.g, &self.h, &edge_map);
+                    }
+                    if let Some(ref path) = self.output_networkx {
+                        let _ = write_networkx_report(path, &self.g, &self.h, &mappings, &edge_map);
+                    }
+
+                    self.current_view = View::Menu;
+                }
+                ProgressMessage::CompareComplete(compare) => {
+                    self.calculating = false;
+                    self.algorithm = Algorithm::Compare;
+                    self.cost = Some(compare.exact_cost);
+                    self.edge_map = Some(compare.exact_edge_map.clone());
+                    self.mappings = Some(compare.exact_mappings.clone());
+                    self.elapsed = Some(compare.exact_elapsed);
+                    self.timed_out = compare.exact_timed_out;
+
+                    if let Some(ref path) = self.output_file {
+                        let _ = write_comparison_file(path, &self.g, &self.h, self.k, &compare);
+                    }
+                    if let Some(ref path) = self.output_edge_list {
+                        let _ =
+                            write_edge_list_report(path, &self.g, &self.h, &compare.exact_edge_map);
+                    }
+                    if let Some(ref path) = self.output_networkx {
+                        let _ = write_networkx_report(
+                            path,
+                            &self.g,
+                            &self.h,
+                            &compare.exact_mappings,
+                            &compare.exact_edge_map,
+                        );
+                    }
+
+                    self.compare_result = Some(*compare);
+                    self.current_view = View::Menu;
+                }
+                ProgressMessage::Incumbent {
+                    cost,
+                    elapsed,
+                    nodes_visited,
+                } => {
+                    self.incumbent_history.push((cost, elapsed, nodes_visited));
+                    if self.incumbent_history.len() > INCUMBENT_HISTORY_LEN {
+                        self.incumbent_history.remove(0);
+                    }
+                }
+                ProgressMessage::Error(err) => {
+                    self.status_message = format!("Error: {}", err);
+                    self.calculating = false;
+                }
+                ProgressMessage::CombinationGuardTriggered {
+                    total_combinations,
+                    max_combinations,
+                    projected_runtime,
+                } => {
+                    self.calculating = false;
+                    self.combination_guard = Some(CombinationGuardInfo {
+                        total_combinations,
+                        max_combinations,
+                        projected_runtime,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Abandons the exact algorithm's branch-and-bound search after a
+    /// `CombinationGuardTriggered` and starts the approx algorithm fresh on
+    /// its own thread, reusing `progress_tx` so `update` keeps receiving its
+    /// progress on the same channel as before.
+    fn switch_to_approx_after_guard(&mut self) {
+        self.combination_guard = None;
+        self.calculating = true;
+        self.status_message = "Switching to the approximation algorithm...".to_string();
+
+        let g = self.g.clone();
+        let h = self.h.clone();
+        let k = self.k;
+        let restarts = self.restarts;
+        let time_limit = self.time_limit;
+        let trials_multiplier = self.trials_multiplier;
+        let seed = self.seed;
+        let objective = self.objective.clone();
+        let refine = self.refine;
+        let beam_width = self.beam_width;
+        let seed_strategy = self.seed_strategy;
+        let deterministic_ties = self.deterministic_ties;
+        let vertices_added = self.vertices_added;
+        let vertex_cost = self.vertex_cost;
+        let tx = self.progress_tx.clone();
+        let threads = self.threads;
+        thread::spawn(move || {
+            let run = move || {
+                run_approx_algorithm(
+                    g,
+                    h,
+                    k,
+                    restarts,
+                    time_limit,
+                    trials_multiplier,
+                    seed,
+                    objective,
+                    refine,
+                    beam_width,
+                    seed_strategy,
+                    deterministic_ties,
+                    vertices_added,
+                    vertex_cost,
+                    None,
+                    tx,
+                );
+            };
+            if threads > 0 {
+                rayon::ThreadPoolBuilder::new()
+                    .num_threads(threads)
+                    .build()
+                    .expect("requested thread count builds a valid pool")
+                    .install(run);
+            } else {
+                run();
+            }
+        });
+    }
+
+    fn handle_key(&mut self, key: KeyCode) {
+        // The help screen takes over every key while it's open (see its
+        // variant doc), including `?` itself, and dismisses on any of them.
+        if let View::Help(previous) = &self.current_view {
+            self.current_view = (**previous).clone();
+            return;
+        }
+        if key == KeyCode::Char('?') {
+            self.current_view = View::Help(Box::new(self.current_view.clone()));
+            return;
+        }
+
+        match self.current_view {
+            View::Calculating => {
+                if self.combination_guard.is_some() {
+                    if let KeyCode::Char('a') | KeyCode::Char('A') = key {
+                        self.switch_to_approx_after_guard();
+                    }
+                }
+            }
+            View::Menu => match key {
+                KeyCode::Char('g') | KeyCode::Char('G') => self.current_view = View::Graphs,
+                KeyCode::Char('e') | KeyCode::Char('E') => self.current_view = View::Extension,
+                KeyCode::Char('v') | KeyCode::Char('V') => self.current_view = View::Mappings,
+                KeyCode::Char('c') | KeyCode::Char('C') => self.current_view = View::Coverage,
+                _ => {}
+            },
+            View::Graphs => match key {
+                KeyCode::Esc => self.current_view = View::Menu,
+                KeyCode::Tab => {
+                    // Tab switches between scrolling G and H (toggle focus)
+                    // We use a simple swap of offsets to indicate focus change
+                    std::mem::swap(&mut self.viewport_g, &mut self.viewport_h);
+                    std::mem::swap(&mut self.viewport_g, &mut self.viewport_h);
+                }
+                KeyCode::Up => {
+                    self.viewport_g.row_offset = self.viewport_g.row_offset.saturating_sub(1);
+                    self.viewport_h.row_offset = self.viewport_h.row_offset.saturating_sub(1);
+                }
+                KeyCode::Down => {
+                    if self.viewport_g.row_offset < self.g.num_vertices().saturating_sub(1) {
+                        self.viewport_g.row_offset += 1;
+                    }
+                    if self.viewport_h.row_offset < self.h.num_vertices().saturating_sub(1) {
+                        self.viewport_h.row_offset += 1;
+                    }
+                }
+                KeyCode::Left => {
+                    self.viewport_g.col_offset = self.viewport_g.col_offset.saturating_sub(1);
+                    self.viewport_h.col_offset = self.viewport_h.col_offset.saturating_sub(1);
+                }
+                KeyCode::Right => {
+                    if self.viewport_g.col_offset < self.g.num_vertices().saturating_sub(1) {
+                        self.viewport_g.col_offset += 1;
+                    }
+                    if self.viewport_h.col_offset < self.h.num_vertices().saturating_sub(1) {
+                        self.viewport_h.col_offset += 1;
+                    }
+                }
+                KeyCode::Char('[') => {
+                    self.viewport_g.col_offset = self.viewport_g.col_offset.saturating_sub(5);
+                    self.viewport_h.col_offset = self.viewport_h.col_offset.saturating_sub(5);
+                }
+                KeyCode::Char(']') => {
+                    self.viewport_g.col_offset = (self.viewport_g.col_offset + 5)
+                        .min(self.g.num_vertices().saturating_sub(1));
+                    self.viewport_h.col_offset = (self.viewport_h.col_offset + 5)
+                        .min(self.h.num_vertices().saturating_sub(1));
+                }
+                KeyCode::PageUp => {
+                    self.viewport_g.row_offset = self.viewport_g.row_offset.saturating_sub(10);
+                    self.viewport_h.row_offset = self.viewport_h.row_offset.saturating_sub(10);
+                }
+                KeyCode::PageDown => {
+                    self.viewport_g.row_offset = (self.viewport_g.row_offset + 10)
+                        .min(self.g.num_vertices().saturating_sub(1));
+                    self.viewport_h.row_offset = (self.viewport_h.row_offset + 10)
+                        .min(self.h.num_vertices().saturating_sub(1));
+                }
+                KeyCode::Home => {
+                    self.viewport_g.row_offset = 0;
+                    self.viewport_g.col_offset = 0;
+                    self.viewport_h.row_offset = 0;
+                    self.viewport_h.col_offset = 0;
+                }
+                KeyCode::End => {
+                    self.viewport_g.row_offset = self.g.num_vertices().saturating_sub(1);
+                    self.viewport_g.col_offset = self.g.num_vertices().saturating_sub(1);
+                    self.viewport_h.row_offset = self.h.num_vertices().saturating_sub(1);
+                    self.viewport_h.col_offset = self.h.num_vertices().saturating_sub(1);
+                }
+                _ => {}
+            },
+            View::Extension => match key {
+                KeyCode::Esc => self.current_view = View::Menu,
+                KeyCode::Up => {
+                    self.viewport_ext.row_offset = self.viewport_ext.row_offset.saturating_sub(1)
+                }
+                KeyCode::Down => {
+                    if self.viewport_ext.row_offset < self.h.num_vertices().saturating_sub(1) {
+                        self.viewport_ext.row_offset += 1;
+                    }
+                }
+                KeyCode::Left => {
+                    self.viewport_ext.col_offset = self.viewport_ext.col_offset.saturating_sub(1)
+                }
+                KeyCode::Right => {
+                    if self.viewport_ext.col_offset < self.h.num_vertices().saturating_sub(1) {
+                        self.viewport_ext.col_offset += 1;
+                    }
+                }
+                KeyCode::Char('[') => {
+                    self.viewport_ext.col_offset = self.viewport_ext.col_offset.saturating_sub(5)
+                }
+                KeyCode::Char(']') => {
+                    self.viewport_ext.col_offset = (self.viewport_ext.col_offset + 5)
+                        .min(self.h.num_vertices().saturating_sub(1));
+                }
+                KeyCode::PageUp => {
+                    self.viewport_ext.row_offset = self.viewport_ext.row_offset.saturating_sub(10)
+                }
+                KeyCode::PageDown => {
+                    self.viewport_ext.row_offset = (self.viewport_ext.row_offset + 10)
+                        .min(self.h.num_vertices().saturating_sub(1));
+                }
+                KeyCode::Home => {
+                    self.viewport_ext.row_offset = 0;
+                    self.viewport_ext.col_offset = 0;
+                }
+                KeyCode::End => {
+                    self.viewport_ext.row_offset = self.h.num_vertices().saturating_sub(1);
+                    self.viewport_ext.col_offset = self.h.num_vertices().saturating_sub(1);
+                }
+                _ => {}
+            },
+            View::Mappings => match key {
+                KeyCode::Esc => self.current_view = View::Menu,
+                KeyCode::Left => {
+                    self.viewport_mappings.col_offset =
+                        self.viewport_mappings.col_offset.saturating_sub(1);
+                }
+                KeyCode::Right => {
+                    if self.viewport_mappings.col_offset < self.h.num_vertices().saturating_sub(1) {
+                        self.viewport_mappings.col_offset += 1;
+                    }
+                }
+                KeyCode::Up => {
+                    self.viewport_mappings.row_offset =
+                        self.viewport_mappings.row_offset.saturating_sub(1);
+                }
+                KeyCode::Down => {
+                    if self.viewport_mappings.row_offset < self.g.num_vertices().saturating_sub(1) {
+                        self.viewport_mappings.row_offset += 1;
+                    }
+                }
+                KeyCode::Char('[') => {
+                    self.viewport_mappings.col_offset =
+                        self.viewport_mappings.col_offset.saturating_sub(5);
+                }
+                KeyCode::Char(']') => {
+                    self.viewport_mappings.col_offset = (self.viewport_mappings.col_offset + 5)
+                        .min(self.h.num_vertices().saturating_sub(1));
+                }
+                KeyCode::PageUp => {
+                    self.viewport_mappings.row_offset =
+                        self.viewport_mappings.row_offset.saturating_sub(10);
+                }
+                KeyCode::PageDown => {
+                    self.viewport_mappings.row_offset = (self.viewport_mappings.row_offset + 10)
+                        .min(self.g.num_vertices().saturating_sub(1));
+                }
+                KeyCode::Home => {
+                    self.viewport_mappings.row_offset = 0;
+                    self.viewport_mappings.col_offset = 0;
+                }
+                KeyCode::End => {
+                    self.viewport_mappings.row_offset = self.g.num_vertices().saturating_sub(1);
+                    self.viewport_mappings.col_offset = self.h.num_vertices().saturating_sub(1);
+                }
+                KeyCode::Char(',') | KeyCode::Char('<') => {
+                    // Previous mapping
+                    if self.selected_mapping > 0 {
+                        self.selected_mapping -= 1;
+                        self.viewport_mappings.row_offset = 0;
+                        self.viewport_mappings.col_offset = 0;
+                    }
+                }
+                KeyCode::Char('.') | KeyCode::Char('>') => {
+                    // Next mapping
+                    if let Some(ref mappings) = self.mappings {
+                        if self.selected_mapping < mappings.len() - 1 {
+                            self.selected_mapping += 1;
+                            self.viewport_mappings.row_offset = 0;
+                            self.viewport_mappings.col_offset = 0;
+                        }
+                    }
+                }
+                _ => {}
+            },
+            View::Coverage => match key {
+                KeyCode::Esc => self.current_view = View::Menu,
+                KeyCode::Up => {
+                    self.viewport_coverage.row_offset =
+                        self.viewport_coverage.row_offset.saturating_sub(1);
+                }
+                KeyCode::Down => {
+                    let max_offset = self.num_g_edges().saturating_sub(1);
+                    if self.viewport_coverage.row_offset < max_offset {
+                        self.viewport_coverage.row_offset += 1;
+                    }
+                }
+                KeyCode::PageUp => {
+                    self.viewport_coverage.row_offset =
+                        self.viewport_coverage.row_offset.saturating_sub(10);
+                }
+                KeyCode::PageDown => {
+                    self.viewport_coverage.row_offset = (self.viewport_coverage.row_offset + 10)
+                        .min(self.num_g_edges().saturating_sub(1));
+                }
+                KeyCode::Home => {
+                    self.viewport_coverage.row_offset = 0;
+                }
+                KeyCode::End => {
+                    self.viewport_coverage.row_offset = self.num_g_edges().saturating_sub(1);
+                }
+                _ => {}
+            },
+            View::Help(_) => unreachable!("handled by the early return above"),
+        }
+    }
+
+    /// Mouse wheel scrolling for the active viewport, plus click-to-select on
+    /// the menu. `area` is the full terminal area from the most recent
+    /// `terminal.draw`, needed to hit-test menu clicks against the same
+    /// layout `render_menu` drew into.
+    fn handle_mouse(&mut self, mouse: MouseEvent, area: Rect) {
+        if matches!(self.current_view, View::Help(_)) {
+            return;
+        }
+
+        const SCROLL_STEP: usize = 3;
+
+        match mouse.kind {
+            MouseEventKind::ScrollUp => match self.current_view {
+                View::Graphs => {
+                    self.viewport_g.row_offset = self.viewport_g.row_offset.saturating_sub(SCROLL_STEP);
+                    self.viewport_h.row_offset = self.viewport_h.row_offset.saturating_sub(SCROLL_STEP);
+                }
+                View::Extension => {
+                    self.viewport_ext.row_offset =
+                        self.viewport_ext.row_offset.saturating_sub(SCROLL_STEP);
+                }
+                View::Mappings => {
+                    self.viewport_mappings.row_offset =
+                        self.viewport_mappings.row_offset.saturating_sub(SCROLL_STEP);
+                }
+                View::Coverage => {
+                    self.viewport_coverage.row_offset =
+                        self.viewport_coverage.row_offset.saturating_sub(SCROLL_STEP);
+                }
+                _ => {}
+            },
+            MouseEventKind::ScrollDown => match self.current_view {
+                View::Graphs => {
+                    self.viewport_g.row_offset = (self.viewport_g.row_offset + SCROLL_STEP)
+                        .min(self.g.num_vertices().saturating_sub(1));
+                    self.viewport_h.row_offset = (self.viewport_h.row_offset + SCROLL_STEP)
+                        .min(self.h.num_vertices().saturating_sub(1));
+                }
+                View::Extension => {
+                    self.viewport_ext.row_offset = (self.viewport_ext.row_offset + SCROLL_STEP)
+                        .min(self.h.num_vertices().saturating_sub(1));
+                }
+                View::Mappings => {
+                    self.viewport_mappings.row_offset = (self.viewport_mappings.row_offset
+                        + SCROLL_STEP)
+                        .min(self.g.num_vertices().saturating_sub(1));
+                }
+                View::Coverage => {
+                    let max_offset = self.num_g_edges().saturating_sub(1);
+                    self.viewport_coverage.row_offset =
+                        (self.viewport_coverage.row_offset + SCROLL_STEP).min(max_offset);
+                }
+                _ => {}
+            },
+            MouseEventKind::ScrollLeft => match self.current_view {
+                View::Graphs => {
+                    self.viewport_g.col_offset = self.viewport_g.col_offset.saturating_sub(SCROLL_STEP);
+                    self.viewport_h.col_offset = self.viewport_h.col_offset.saturating_sub(SCROLL_STEP);
+                }
+                View::Extension => {
+                    self.viewport_ext.col_offset =
+                        self.viewport_ext.col_offset.saturating_sub(SCROLL_STEP);
+                }
+                View::Mappings => {
+                    self.viewport_mappings.col_offset =
+                        self.viewport_mappings.col_offset.saturating_sub(SCROLL_STEP);
+                }
+                _ => {}
+            },
+            MouseEventKind::ScrollRight => match self.current_view {
+                View::Graphs => {
+                    self.viewport_g.col_offset = (self.viewport_g.col_offset + SCROLL_STEP)
+                        .min(self.g.num_vertices().saturating_sub(1));
+                    self.viewport_h.col_offset = (self.viewport_h.col_offset + SCROLL_STEP)
+                        .min(self.h.num_vertices().saturating_sub(1));
+                }
+                View::Extension => {
+                    self.viewport_ext.col_offset = (self.viewport_ext.col_offset + SCROLL_STEP)
+                        .min(self.h.num_vertices().saturating_sub(1));
+                }
+                View::Mappings => {
+                    self.viewport_mappings.col_offset = (self.viewport_mappings.col_offset
+                        + SCROLL_STEP)
+                        .min(self.h.num_vertices().saturating_sub(1));
+                }
+                _ => {}
+            },
+            MouseEventKind::Down(MouseButton::Left) if self.current_view == View::Menu => {
+                match menu_item_at(self, area, mouse.column, mouse.row) {
+                    Some(0) => self.current_view = View::Graphs,
+                    Some(1) => self.current_view = View::Extension,
+                    Some(2) => self.current_view = View::Mappings,
+                    Some(3) => self.current_view = View::Coverage,
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Number of nonzero edges in `g`, i.e. how many rows `render_coverage`
+    /// has to scroll through.
+    fn num_g_edges(&self) -> usize {
+        (0..self.g.num_vertices())
+            .flat_map(|u| (0..self.g.num_vertices()).map(move |v| (u, v)))
+            .filter(|&(u, v)| self.g.get_edge(u, v) > 0)
+            .count()
+    }
+}
+
+/// Render the calculating view
+fn render_calculating(f: &mut Frame, app: &AppState, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Length(10),
+            Constraint::Min(0),
+        ])
+        .split(area);
+
+    // Header
+    let title = match app.algorithm {
+        Algorithm::Exact => "Exact Solver for k-Isomorphic Subgraph Extension",
+        Algorithm::Approx => "Approximation Solver for k-Isomorphic Subgraph Extension",
+        Algorithm::Compare => "Comparison Solver for k-Isomorphic Subgraph Extension",
+    };
+    let header = Paragraph::new(title)
+        .style(
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL));
+    f.render_widget(header, chunks[0]);
+
+    // Info section
+    let elapsed = app.start_time.elapsed();
+    let trials_info = if app.algorithm == Algorithm::Approx {
+        format!(
+            "\nTrials per mapping: {} (n₁ × n₂ × {})",
+            app.g.num_vertices() * app.h.num_vertices() * app.trials_multiplier,
+            app.trials_multiplier
+        )
+    } else {
+        String::new()
+    };
+
+    let progress_line = if app.algorithm == Algorithm::Exact {
+        let current = app.current_mapping.min(app.total_mappings);
+        let total = app.total_mappings;
+        let percent = if total > 0 {
+            100.0 * current as f64 / total as f64
+        } else {
+            0.0
+        };
+        let rate = if elapsed.as_secs_f64() > 0.0 {
+            current as f64 / elapsed.as_secs_f64()
+        } else {
+            0.0
+        };
+        format!(
+            "Combinations evaluated: {}/{} ({:.1}%, {:.0} combos/sec)",
+            current, total, percent, rate
+        )
+    } else {
+        format!(
+            "Finding mapping {}/{}...",
+            app.current_mapping.min(app.total_mappings),
+            app.total_mappings
+        )
+    };
+
+    let spinner = SPINNER_FRAMES[app.spinner_frame];
+    let info_text = format!(
+        "Graph G (pattern): {} vertices\n\
+        Graph H (host): {} vertices\n\
+        Required distinct mappings (k): {}\n\
+        Algorithm: {}{}\n\n\
+        Status: {} {}\n\n\
+        {}\n\n\
+        Elapsed time: {:.3}s",
+        app.g.num_vertices(),
+        app.h.num_vertices(),
+        app.k,
+        match app.algorithm {
+            Algorithm::Exact => "Exact",
+            Algorithm::Approx => "Approximation",
+            Algorithm::Compare => "Comparison (exact then approx)",
+        },
+        trials_info,
+        app.status_message,
+        spinner,
+        progress_line,
+        elapsed.as_secs_f64()
+    );
+
+    let info = Paragraph::new(info_text)
+        .style(Style::default().fg(Color::White))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Gray)),
+        );
+    f.render_widget(info, chunks[1]);
+
+    if !app.incumbent_history.is_empty() {
+        let history_items: Vec<ListItem> = app
+            .incumbent_history
+            .iter()
+            .rev()
+            .map(|&(cost, elapsed, nodes_visited)| {
+                ListItem::new(format!(
+                    "new best: cost={} after {:.1}s, {} combos",
+                    cost,
+                    elapsed.as_secs_f64(),
+                    nodes_visited
+                ))
+            })
+            .collect();
+
+        let history = List::new(history_items)
+            .style(Style::default().fg(Color::White))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(Color::Gray))
+                    .title(" Best so far "),
+            );
+        f.render_widget(history, chunks[2]);
+    }
+
+    if let Some(guard) = &app.combination_guard {
+        let text = format!(
+            "{} candidate {}-combinations exceeds --max-combinations ({}).\n\
+            Projected runtime at this rate: ~{:.1}s (calibrated from a small sample).\n\n\
+            Press 'a' to switch to the approximation algorithm instead, \
+            or re-run with --force to proceed anyway.",
+            guard.total_combinations,
+            app.k,
+            guard.max_combinations,
+            guard.projected_runtime.as_secs_f64(),
+        );
+        let warning = Paragraph::new(text)
+            .style(Style::default().fg(Color::Yellow))
+            .wrap(Wrap { trim: true })
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(Color::Yellow))
+                    .title(" Search size guard triggered "),
+            );
+        f.render_widget(warning, chunks[2]);
+    }
+}
+
+/// The `Rect` the menu's `List` widget is rendered into, i.e. `chunks[2]` of
+/// `render_menu`'s layout. Shared with `menu_item_at` so a mouse click is
+/// hit-tested against exactly the area the items were drawn in.
+fn menu_list_area(app: &AppState, area: Rect) -> Rect {
+    let results_height = if app.compare_result.is_some() { 8 } else { 6 };
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Length(results_height),
+            Constraint::Min(8),
+        ])
+        .split(area);
+    chunks[2]
+}
+
+/// Which menu item (0-indexed, top to bottom) a mouse click at `(col, row)`
+/// landed on, or `None` if it missed the list (e.g. the border, or the blank
+/// separator line above Help/Quit).
+fn menu_item_at(app: &AppState, area: Rect, col: u16, row: u16) -> Option<usize> {
+    let list_area = menu_list_area(app, area);
+    // Account for the list block's own border before indexing into its items.
+    let inner = list_area.inner(Margin {
+        horizontal: 1,
+        vertical: 1,
+    });
+    if col < inner.x || col >= inner.x + inner.width || row < inner.y {
+        return None;
+    }
+    let index = (row - inner.y) as usize;
+    if index < inner.height as usize {
+        Some(index)
+    } else {
+        None
+    }
+}
+
+/// Render the main menu (post-completion results and view shortcuts)
+fn render_menu(f: &mut Frame, app: &AppState, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Length(if app.compare_result.is_some() { 8 } else { 6 }),
+            Constraint::Min(8),
+        ])
+        .split(area);
+
+    // Header with solution type
+    let title = if app.timed_out {
+        "TIME LIMIT REACHED — best found, optimality not proven"
+    } else {
+        match app.algorithm {
+            Algorithm::Exact => "EXACT SOLUTION FOUND",
+            Algorithm::Approx => "APPROXIMATE SOLUTION FOUND",
+            Algorithm::Compare => "COMPARISON COMPLETE",
+        }
+    };
+    let header_color = if app.timed_out {
+        Color::Yellow
+    } else {
+        Color::Green
+    };
+    let header = Paragraph::new(title)
+        .style(
+            Style::default()
+                .fg(header_color)
+                .add_modifier(Modifier::BOLD),
+        )
+        .alignment(Alignment::Center)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(header_color)),
+        );
+    f.render_widget(header, chunks[0]);
+
+    // Results info
+    let cost = app.cost.unwrap_or(0);
+    let elapsed = app.elapsed.unwrap_or(Duration::from_secs(0));
+    let mappings_count = app.mappings.as_ref().map(|m| m.len()).unwrap_or(0);
+
+    let mut results_lines = vec![Line::from(Span::styled(
+        format!(
+            "Cost: {} edges  │  Time: {}ms  │  Mappings: {}",
+            cost,
+            elapsed.as_millis(),
+            mappings_count
+        ),
+        Style::default().fg(Color::Yellow),
+    ))];
+
+    if let Some(compare) = &app.compare_result {
+        results_lines.push(Line::from(Span::styled(
+            format!(
+                "Optimal (exact): {} edges, {}ms  │  Approx: {} edges ({} mappings), {}ms",
+                compare.exact_cost,
+                compare.exact_elapsed.as_millis(),
+                compare.approx_cost,
+                compare.approx_mappings.len(),
+                compare.approx_elapsed.as_millis()
+            ),
+            Style::default().fg(Color::Cyan),
+        )));
+        results_lines.push(Line::from(Span::styled(
+            if compare.ratio >= usize::MAX as f64 {
+                "Approximation ratio (approx/optimal): infinite".to_string()
+            } else {
+                format!(
+                    "Approximation ratio (approx/optimal): {:.3}x",
+                    compare.ratio
+                )
+            },
+            Style::default()
+                .fg(Color::Magenta)
+                .add_modifier(Modifier::BOLD),
+        )));
+    }
+
+    if let Some(lower_bound) = app.approximation_lower_bound {
+        results_lines.push(Line::from(Span::styled(
+            format!(
+                "{} (lower bound, not the true optimum)",
+                format_approximation_gap(cost, lower_bound)
+            ),
+            Style::default().fg(Color::Magenta),
+        )));
+    }
+
+    if let Some(ref path) = app.output_file {
+        results_lines.push(Line::from(""));
+        results_lines.push(Line::from(Span::styled(
+            format!("✓ Results saved to: {}", path.display()),
+            Style::default().fg(Color::Green),
+        )));
+    }
+
+    let results = Paragraph::new(results_lines)
+        .alignment(Alignment::Center)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Gray)),
+        );
+    f.render_widget(results, chunks[1]);
+
+    // Menu options
+    let menu_items = vec![
+        ListItem::new("  [G] View Graphs G and H adjacency matrices"),
+        ListItem::new("  [E] View Extension (edges to add to H)"),
+        ListItem::new(format!("  [V] View Mappings ({} found)", mappings_count)),
+        ListItem::new("  [C] View Coverage (which G edges are satisfied)"),
+        ListItem::new(""),
+        ListItem::new("  [?] Help"),
+        ListItem::new("  [Q] Quit"),
+    ];
+
+    let menu = List::new(menu_items)
+        .style(Style::default().fg(Color::White))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Cyan))
+                .title(" Menu "),
+        );
+    f.render_widget(menu, chunks[2]);
+}
+
+/// Render the extension view (original+added format)
+fn render_extension(f: &mut Frame, app: &AppState, area: Rect) {
+    let n = app.h.num_vertices();
+    let viewport = &app.viewport_ext;
+
+    // `--algorithm compare` shows the exact solution's added edges next to
+    // the approx solution's extra ones (the approx edges beyond what exact
+    // already adds at that cell) side-by-side in the same matrix, rather than
+    // drawing two separate matrices -- see `CompareResult`.
+    let approx_extended = app
+        .compare_result
+        .as_ref()
+        .map(|compare| apply_edge_map(&app.h, &compare.approx_edge_map));
+
+    // Calculate visible rows/cols based on terminal size. Comparison cells
+    // can carry two "+N" suffixes instead of one, so they need more room.
+    let chars_per_col: usize = if approx_extended.is_some() { 10 } else { 7 };
+    let rows_visible = (area.height.saturating_sub(10) as usize).max(5);
+    let cols_visible = ((area.width.saturating_sub(8) as usize) / chars_per_col).max(5);
+
+    let max_row = viewport.row_offset + rows_visible.min(n - viewport.row_offset);
+    let max_col = viewport.col_offset + cols_visible.min(n - viewport.col_offset);
+
+    let edge_map = app.edge_map.as_ref().unwrap();
+    let extended = apply_edge_map(&app.h, edge_map);
+    let exact_delta = compute_edge_delta(&app.h, &extended);
+    let approx_delta = approx_extended
+        .as_ref()
+        .map(|approx_extended| compute_edge_delta(&app.h, approx_extended));
+
+    // Build matrix text with "original+added" format
+    let mut lines = vec![];
+    lines.push(Line::from(Span::styled(
+        if approx_extended.is_some() {
+            "Format: original+exact+approx (exact added in green, extra approx-only edges in orange)"
+        } else {
+            "Format: original+added"
+        },
+        Style::default().fg(Color::Yellow),
+    )));
+    if app.vertices_added > 0 {
+        lines.push(Line::from(Span::styled(
+            format!(
+                "Synthetic vertices (added by --allow-vertex-additions): {}..{}",
+                n - app.vertices_added,
+                n
+            ),
+            Style::default().fg(Color::Magenta),
+        )));
+    }
+    lines.push(Line::from(""));
+
+    // Header line with column numbers
+    let mut header = String::from("     ");
+    for col in viewport.col_offset..max_col {
+        header.push_str(&format!("{:width$}", col, width = chars_per_col - 1));
+    }
+    if max_col < n {
+        header.push_str("   ...");
+    }
+    lines.push(Line::from(Span::styled(
+        header,
+        Style::default().fg(Color::Cyan),
+    )));
+
+    // Matrix rows
+    for row in viewport.row_offset..max_row {
+        let mut line_spans = vec![Span::styled(
+            format!("{:3}│", row),
+            Style::default().fg(Color::Cyan),
+        )];
+
+        for col in viewport.col_offset..max_col {
+            let original = app.h.get_edge(row, col);
+            let exact_added = exact_delta.get(&(row, col)).copied().unwrap_or(0);
+            let approx_extra = approx_delta.as_ref().map(|approx_delta| {
+                let approx_added = approx_delta.get(&(row, col)).copied().unwrap_or(0);
+                approx_added.saturating_sub(exact_added)
+            });
+
+            let exact_text = if exact_added > 0 {
+                format!("+{}", exact_added)
+            } else {
+                String::new()
+            };
+            let approx_text = match approx_extra {
+                Some(extra) if extra > 0 => format!("+{}", extra),
+                _ => String::new(),
+            };
+            let base_text = original.to_string();
+
+            let pad_width = (chars_per_col - 1)
+                .saturating_sub(base_text.len() + exact_text.len() + approx_text.len());
+            if pad_width > 0 {
+                line_spans.push(Span::raw(" ".repeat(pad_width)));
+            }
+
+            let base_style = if original > 0 {
+                Style::default().fg(Color::Yellow)
+            } else {
+                Style::default().fg(Color::DarkGray)
+            };
+            line_spans.push(Span::styled(base_text, base_style));
+            if !exact_text.is_empty() {
+                line_spans.push(Span::styled(
+                    exact_text,
+                    Style::default()
+                        .fg(Color::Green)
+                        .add_modifier(Modifier::BOLD),
+                ));
+            }
+            if !approx_text.is_empty() {
+                line_spans.push(Span::styled(
+                    approx_text,
+                    Style::default()
+                        .fg(Color::Rgb(255, 140, 0))
+                        .add_modifier(Modifier::BOLD),
+                ));
+            }
+            line_spans.push(Span::raw(" "));
+        }
+
+        if max_col < n {
+            line_spans.push(Span::styled("   ...", Style::default().fg(Color::DarkGray)));
+        }
+
+        lines.push(Line::from(line_spans));
+    }
+
+    if max_row < n {
+        lines.push(Line::from(Span::styled(
+            "  ...",
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+
+    // Stats
+    let total_added: usize = edge_map.values().sum();
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        format!("Total edges added: {}", total_added),
+        Style::default().fg(Color::Gray),
+    )));
+
+    // Navigation info under the matrix
+    if n > rows_visible || n > cols_visible {
+        lines.push(Line::from(Span::styled(
+            format!(
+                "Viewing rows {}-{}, cols {}-{} of {}x{}",
+                viewport.row_offset,
+                max_row.saturating_sub(1),
+                viewport.col_offset,
+                max_col.saturating_sub(1),
+                n,
+                n
+            ),
+            Style::default().fg(Color::DarkGray),
+        )));
+        lines.push(Line::from(Span::styled(
+            "[↑↓←→] Scroll  [PgUp/Dn] Jump rows  [[/]] Jump cols  [Home/End] First/Last",
+            Style::default().fg(Color::Magenta),
+        )));
+    }
+
+    // Add hint to the content
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "[Esc] Menu  [Q] Quit",
+        Style::default().fg(Color::Magenta),
+    )));
+
+    let paragraph = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Green))
+                .title(format!(" Extension to Graph H ({} vertices) ", n)),
+        )
+        .wrap(Wrap { trim: false });
+
+    f.render_widget(paragraph, area);
+}
+
+/// Render the coverage view: for each G edge, how many of the found
+/// mappings already satisfy it in H without needing an addition.
+fn render_coverage(f: &mut Frame, app: &AppState, area: Rect) {
+    let mappings = app.mappings.as_ref().unwrap();
+    let viewport = &app.viewport_coverage;
+    let k = mappings.len();
+
+    let coverage = coverage_analysis(&app.g, &app.h, mappings);
+    let mut edges: Vec<_> = coverage.keys().copied().collect();
+    edges.sort();
+
+    let rows_visible = (area.height.saturating_sub(8) as usize).max(5);
+    let max_row =
+        viewport.row_offset + rows_visible.min(edges.len().saturating_sub(viewport.row_offset));
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            "Coverage: how many mappings already satisfy each G edge in H",
+            Style::default().fg(Color::Yellow),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            format!("{:<14} {:<10} {}", "G edge", "weight", "satisfied by"),
+            Style::default().fg(Color::Cyan),
+        )),
+    ];
+
+    for &(u, v) in &edges[viewport.row_offset..max_row] {
+        let count = coverage[&(u, v)];
+        let style = if count == 0 {
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+        } else if count >= k {
+            Style::default()
+                .fg(Color::Green)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::Yellow)
+        };
+        lines.push(Line::from(Span::styled(
+            format!(
+                "({:>2},{:>2})      {:<10} {}/{} mappings",
+                u,
+                v,
+                app.g.get_edge(u, v),
+                count,
+                k
+            ),
+            style,
+        )));
+    }
+
+    if max_row < edges.len() {
+        lines.push(Line::from(Span::styled(
+            "  ...",
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+
+    lines.push(Line::from(""));
+    if edges.len() > rows_visible {
+        lines.push(Line::from(Span::styled(
+            format!(
+                "Viewing edges {}-{} of {}",
+                viewport.row_offset,
+                max_row.saturating_sub(1),
+                edges.len()
+            ),
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+    lines.push(Line::from(Span::styled(
+        "Red = never satisfied (coverage 0)   Green = always satisfied (coverage >= k)",
+        Style::default().fg(Color::Magenta),
+    )));
+    lines.push(Line::from(Span::styled(
+        "[↑↓] Scroll  [PgUp/Dn] Jump  [Home/End] First/Last  [Esc] Menu  [Q] Quit",
+        Style::default().fg(Color::Magenta),
+    )));
+
+    let paragraph = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Green))
+                .title(format!(" Coverage ({} edges, k={}) ", edges.len(), k)),
+        )
+        .wrap(Wrap { trim: false });
+
+    f.render_widget(paragraph, area);
+}
+
+/// Render the mappings view
+fn render_mappings(f: &mut Frame, app: &AppState, area: Rect) {
+    let mappings = app.mappings.as_ref().unwrap();
+    let current_idx = app.selected_mapping;
+    let mapping = &mappings[current_idx];
+    let n_g = mapping.len();
+    let n_h = app.h.num_vertices();
+    let viewport = &app.viewport_mappings;
+
+    let mut lines = vec![];
+
+    let cost_matrix = calculate_cost_matrix(&app.g, &app.h, mappings);
+
+    // Title with navigation
+    lines.push(Line::from(vec![
+        Span::styled("          ◄  ", Style::default().fg(Color::Cyan)),
+        Span::styled(
+            format!("Mapping {} of {}  ", current_idx + 1, mappings.len()),
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::styled("►", Style::default().fg(Color::Cyan)),
+        Span::styled(
+            format!("  (cost: {})", cost_matrix.per_mapping[current_idx]),
+            Style::default().fg(Color::Magenta),
+        ),
+    ]));
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "Permutation Matrix - Each row shows where G vertex maps to in H",
+        Style::default().fg(Color::Gray),
+    )));
+    lines.push(Line::from(""));
+
+    // Calculate visible rows/cols based on terminal size
+    let rows_visible = (area.height.saturating_sub(16) as usize).max(3);
+    let cols_visible = ((area.width.saturating_sub(16)) / 4).max(5) as usize;
+
+    let row_offset = viewport.row_offset.min(n_g.saturating_sub(1));
+    let col_offset = viewport.col_offset.min(n_h.saturating_sub(1));
+
+    let max_row = row_offset + rows_visible.min(n_g.saturating_sub(row_offset));
+    let max_col = col_offset + cols_visible.min(n_h.saturating_sub(col_offset));
+
+    // Header line with H vertex numbers
+    let mut header = String::from("   H vertices: ");
+    for col in col_offset..max_col {
+        header.push_str(&format!("{:3} ", col));
+    }
+    if max_col < n_h {
+        header.push_str(" ...");
+    }
+    lines.push(Line::from(Span::styled(
+        header,
+        Style::default()
+            .fg(Color::Cyan)
+            .add_modifier(Modifier::BOLD),
+    )));
+
+    // Separator line
+    let mut separator = String::from("               ┌");
+    for _ in col_offset..max_col {
+        separator.push_str("────");
+    }
+    lines.push(Line::from(Span::styled(
+        separator,
+        Style::default().fg(Color::DarkGray),
+    )));
+
+    // Matrix rows
+    for (idx, &h_vertex) in mapping
+        .iter()
+        .enumerate()
+        .skip(row_offset)
+        .take(max_row - row_offset)
+    {
+        let g_vertex = row_offset + idx;
+        let mut line_spans = vec![
+            Span::styled(
+                format!("   G[{:2}] → {:2}  ", g_vertex, h_vertex),
+                Style::default().fg(Color::Green),
+            ),
+            Span::styled("│", Style::default().fg(Color::DarkGray)),
+        ];
+
+        for col in col_offset..max_col {
+            let symbol = if col == h_vertex {
+                "◉" // Filled circle for the mapping
+            } else {
+                "·" // Middle dot for empty cells
+            };
+
+            let style = if col == h_vertex {
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::DarkGray)
+            };
+
+            line_spans.push(Span::styled(format!(" {}  ", symbol), style));
+        }
+
+        if max_col < n_h {
+            line_spans.push(Span::styled("  ·", Style::default().fg(Color::DarkGray)));
+        }
+
+        lines.push(Line::from(line_spans));
+    }
+
+    // Show ellipsis if there are more rows
+    if max_row < n_g {
+        lines.push(Line::from(Span::styled(
+            "               │  ...",
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+
+    // Footer separator
+    let mut footer_sep = String::from("               └");
+    for _ in col_offset..max_col {
+        footer_sep.push_str("────");
+    }
+    lines.push(Line::from(Span::styled(
+        footer_sep,
+        Style::default().fg(Color::DarkGray),
+    )));
+
+    lines.push(Line::from(""));
+
+    // Legend
+    lines.push(Line::from(vec![
+        Span::styled("   ◉ = mapped    ", Style::default().fg(Color::Yellow)),
+        Span::styled("· = not mapped", Style::default().fg(Color::DarkGray)),
+    ]));
+
+    // Navigation info
+    lines.push(Line::from(""));
+    if n_g > rows_visible || n_h > cols_visible {
+        lines.push(Line::from(Span::styled(
+            format!(
+                "   Viewing rows {}-{}, cols {}-{} of {}x{}",
+                row_offset,
+                max_row.saturating_sub(1),
+                col_offset,
+                max_col.saturating_sub(1),
+                n_g,
+                n_h
+            ),
+            Style::default().fg(Color::DarkGray),
+        )));
+        lines.push(Line::from(Span::styled(
+            "   [↑↓←→] Scroll  [PgUp/Dn] Jump rows  [[/]] Jump cols  [Home/End] First/Last",
+            Style::default().fg(Color::Magenta),
+        )));
+    }
+
+    lines.push(Line::from(Span::styled(
+        "   [</,] Previous  [>/.]  Next mapping",
+        Style::default().fg(Color::Magenta),
+    )));
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "   [Esc] Menu  [Q] Quit",
+        Style::default().fg(Color::Magenta),
+    )));
+
+    let paragraph = Paragraph::new(lines).alignment(Alignment::Left).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan))
+            .title(format!(" Permutation Matrix (k={}) ", app.k)),
+    );
+
+    f.render_widget(paragraph, area);
+}
+
+/// Render combined graphs view (G and H side by side)
+fn render_graphs_combined(f: &mut Frame, app: &AppState, area: Rect) {
+    // Split vertically: main content and hint bar at bottom
+    let main_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(5), Constraint::Length(1)])
+        .split(area);
+
+    // Split horizontally for G and H
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(main_chunks[0]);
+
+    render_graph_matrix_panel(
+        f,
+        &app.g,
+        &app.viewport_g,
+        "Graph G Adjacency Matrix",
+        chunks[0],
+    );
+    render_graph_matrix_panel(
+        f,
+        &app.h,
+        &app.viewport_h,
+        "Graph H Adjacency Matrix",
+        chunks[1],
+    );
+
+    // Navigation hint at bottom
+    let hint = Paragraph::new("[Esc] Menu  [Q] Quit")
+        .style(Style::default().fg(Color::Magenta))
+        .alignment(Alignment::Center);
+    f.render_widget(hint, main_chunks[1]);
+}
+
+/// Render a graph adjacency matrix panel (for combined view)
+fn render_graph_matrix_panel(
+    f: &mut Frame,
+    graph: &Graph,
+    viewport: &Viewport,
+    title: &str,
+    area: Rect,
+) {
+    let n = graph.num_vertices();
+
+    // Calculate visible rows/cols based on panel size
+    let rows_visible = (area.height.saturating_sub(6) as usize).max(3);
+    let cols_visible = ((area.width.saturating_sub(6)) / 5).max(3) as usize;
+
+    let row_offset = viewport.row_offset.min(n.saturating_sub(1));
+    let col_offset = viewport.col_offset.min(n.saturating_sub(1));
+
+    let max_row = row_offset + rows_visible.min(n.saturating_sub(row_offset));
+    let max_col = col_offset + cols_visible.min(n.saturating_sub(col_offset));
+
+    let mut lines = vec![];
+
+    // Header line with column numbers
+    let mut header = String::from("    ");
+    for col in col_offset..max_col {
+        header.push_str(&format!("{:4}", col));
+    }
+    if max_col < n {
+        header.push_str(" ...");
+    }
+    lines.push(Line::from(Span::styled(
+        header,
+        Style::default().fg(Color::Cyan),
+    )));
+
+    // Matrix rows
+    for row in row_offset..max_row {
+        let mut line_spans = vec![Span::styled(
+            format!("{:3}│", row),
+            Style::default().fg(Color::Cyan),
+        )];
+
+        for col in col_offset..max_col {
+            let value = graph.get_edge(row, col);
+            let style = if value > 0 {
+                Style::default().fg(Color::Yellow)
+            } else {
+                Style::default().fg(Color::DarkGray)
+            };
+            line_spans.push(Span::styled(format!("{:4}", value), style));
+        }
+
+        if max_col < n {
+            line_spans.push(Span::styled(" ...", Style::default().fg(Color::DarkGray)));
+        }
+
+        lines.push(Line::from(line_spans));
+    }
+
+    if max_row < n {
+        lines.push(Line::from(Span::styled(
+            "  ...",
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+
+    // Navigation info
+    if n > rows_visible || n > cols_visible {
+        lines.push(Line::from(Span::styled(
+            format!(
+                "[{}-{}, {}-{}] of {}x{}",
+                row_offset,
+                max_row.saturating_sub(1),
+                col_offset,
+                max_col.saturating_sub(1),
+                n,
+                n
+            ),
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+
+    let paragraph = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Cyan))
+                .title(format!(" {} ", title)),
+        )
+        .wrap(Wrap { trim: false });
+
+    f.render_widget(paragraph, area);
+}
+
+/// Main UI rendering
+fn ui(f: &mut Frame, app: &AppState) {
+    let size = f.area();
+
+    match &app.current_view {
+        View::Calculating => render_calculating(f, app, size),
+        View::Menu => render_menu(f, app, size),
+        View::Graphs => render_graphs_combined(f, app, size),
+        View::Extension => render_extension(f, app, size),
+        View::Mappings => render_mappings(f, app, size),
+        View::Coverage => render_coverage(f, app, size),
+        View::Help(previous) => render_help(f, previous, size),
+    }
+}
+
+/// Keybindings for `view`, in display order, as `(key, description)` pairs.
+/// Shared by `render_help`'s per-view section and nothing else, so it can
+/// freely describe keys in prose rather than mirror `handle_key`'s `KeyCode`
+/// matching exactly.
+fn view_keybindings(view: &View) -> Vec<(&'static str, &'static str)> {
+    match view {
+        View::Calculating => vec![(
+            "a",
+            "Switch to the approximation algorithm (only while the exact \
+             search is waiting on confirmation to run an expensive search)",
+        )],
+        View::Menu => vec![
+            ("g", "View Graphs G and H adjacency matrices"),
+            ("e", "View Extension (edges to add to H)"),
+            ("v", "View Mappings"),
+            ("c", "View Coverage (which G edges are satisfied)"),
+            ("q", "Quit"),
+        ],
+        View::Graphs => vec![
+            ("Esc", "Back to menu"),
+            ("Tab", "Swap scroll focus between G and H"),
+            ("Arrow keys", "Scroll the matrices"),
+            ("[ / ]", "Scroll columns by 5"),
+            ("PageUp / PageDown", "Scroll rows by 10"),
+            ("Home / End", "Jump to the first/last row and column"),
+        ],
+        View::Extension => vec![
+            ("Esc", "Back to menu"),
+            ("Arrow keys", "Scroll the matrix"),
+            ("[ / ]", "Scroll columns by 5"),
+            ("PageUp / PageDown", "Scroll rows by 10"),
+            ("Home / End", "Jump to the first/last row and column"),
+        ],
+        View::Mappings => vec![
+            ("Esc", "Back to menu"),
+            ("Arrow keys", "Scroll the current mapping's matrix"),
+            (", / .", "Previous/next mapping"),
+            ("[ / ]", "Scroll columns by 5"),
+            ("PageUp / PageDown", "Scroll rows by 10"),
+            ("Home / End", "Jump to the first/last row and column"),
+        ],
+        View::Coverage => vec![
+            ("Esc", "Back to menu"),
+            ("Arrow keys", "Scroll the list of G edges"),
+            ("PageUp / PageDown", "Scroll by 10"),
+            ("Home / End", "Jump to the first/last edge"),
+        ],
+        View::Help(_) => vec![],
+    }
+}
+
+/// Render the help screen opened from `previous` (see `View::Help`): that
+/// view's own keybindings, plus a fixed block of problem/algorithm
+/// background that's the same regardless of which view `?` was pressed
+/// from. Dismissed by any keypress (`AppState::handle_key`), so there's no
+/// keybinding of its own to list.
+fn render_help(f: &mut Frame, previous: &View, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([Constraint::Length(3), Constraint::Min(10)])
+        .split(area);
+
+    let header = Paragraph::new("Help  —  press any key to return")
+        .style(
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )
+        .alignment(Alignment::Center)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Cyan)),
+        );
+    f.render_widget(header, chunks[0]);
+
+    let background: [(&str, &str); 5] = [
+        (
+            "Problem",
+            "Find k distinct injective mappings of pattern graph G into host \
+             graph H, adding as few edges to H as needed so every mapping's \
+             image satisfies every edge G requires.",
+        ),
+        (
+            "Cost",
+            "The objective being minimized: either the total number of edges \
+             added to H, or the highest multiplicity any single edge needs \
+             (see --objective).",
+        ),
+        (
+            "Exact algorithm",
+            "Branch-and-bound over combinations of candidate mappings; \
+             guaranteed optimal, but can be slow once the candidate pool is \
+             large (see exact_solver --help).",
+        ),
+        (
+            "Approx algorithm",
+            "Randomized and greedy heuristics that trade optimality for \
+             speed on instances too large to search exactly (see \
+             approx_solver --help for the full list of strategies).",
+        ),
+        (
+            "Docs",
+            "See this repository's README.md, and --help on exact_solver, \
+             approx_solver, and this binary for the full flag reference.",
+        ),
+    ];
+
+    let rows = background
+        .into_iter()
+        .chain(std::iter::once(("", "")))
+        .chain(view_keybindings(previous))
+        .map(|(key, description)| Row::new(vec![key, description]));
+
+    let table = Table::new(rows, [Constraint::Length(18), Constraint::Min(20)])
+        .header(
+            Row::new(vec!["Key", "Description"]).style(Style::default().add_modifier(Modifier::BOLD)),
+        )
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!(" Keybindings ({:?} view) ", previous)),
+        );
+    f.render_widget(table, chunks[1]);
+}
+
+/// A fast, deterministic greedy construction used only to seed the
+/// branch-and-bound search below with a reasonably tight starting incumbent:
+/// repeatedly commit whichever unused mapping adds the least to the cost
+/// under `objective` given what's already committed (see
+/// `cost::marginal_cost`). Not guaranteed optimal on its own — the exact
+/// search below still explores every combination the bound can't rule out.
+fn greedy_incumbent(
+    all_mappings: &[Mapping],
+    g: &Graph,
+    h: &Graph,
+    k: usize,
+    objective: &Objective,
+) -> Option<SolutionResult> {
+    let mut committed = EdgeMap::new();
+    let mut used = vec![false; all_mappings.len()];
+    let mut chosen = Vec::with_capacity(k);
+
+    for _ in 0..k {
+        let (_, idx, increments) = (0..all_mappings.len())
+            .filter(|&i| !used[i])
+            .map(|i| {
+                let (_, increments) =
+                    marginal_cost(g, h, &committed, &all_mappings[i], MergeSemantics::Shared);
+                (objective.evaluate(&increments), i, increments)
+            })
+            .min_by_key(|(cost, _, _)| *cost)?;
+
+        for (edge, weight) in increments {
+            *committed.entry(edge).or_insert(0) += weight;
+        }
+        used[idx] = true;
+        chosen.push(all_mappings[idx].clone());
+    }
+
+    Some((objective.evaluate(&committed), committed, chosen))
+}
+
+/// Times how long evaluating a small sample of k-combinations actually
+/// takes and extrapolates a full-search runtime from it, for the
+/// `--max-combinations` guard's error message. The branch-and-bound search
+/// itself prunes far more aggressively than this naive per-combination
+/// evaluation, so the real run is almost always faster than this projects --
+/// it's meant to convey scale, not to be a tight estimate.
+fn calibrate_projected_runtime(
+    all_mappings: &[Mapping],
+    g: &Graph,
+    h: &Graph,
+    k: usize,
+    objective: &Objective,
+    total_combinations: usize,
+) -> Duration {
+    const SAMPLE_SIZE: usize = 10_000;
+    let sample_size = SAMPLE_SIZE.min(total_combinations).max(1);
+
+    let start = Instant::now();
+    for combination in all_mappings.iter().combinations(k).take(sample_size) {
+        let edge_map = calculate_edge_map(g, h, &combination);
+        objective.evaluate(&edge_map);
+    }
+    let per_combination_secs = start.elapsed().as_secs_f64() / sample_size as f64;
+
+    let projected_secs = per_combination_secs * total_combinations as f64;
+    if projected_secs.is_finite() {
+        Duration::from_secs_f64(projected_secs)
+    } else {
+        Duration::MAX
+    }
+}
+
+/// Canonical form used to break cost ties between two equally-good results:
+/// each candidate's mapping list, sorted. `Mapping` is `Vec<usize>`, which
+/// has a natural lexicographic `Ord`, so sorting the outer list gives a
+/// representation that's independent of the order in which the search
+/// happened to assemble it — the same *set* of mappings always sorts to the
+/// same `Vec<Vec<usize>>`, regardless of which branch found it or in what
+/// order its indices were chosen.
+fn canonical_mappings(mappings: &[Mapping]) -> Vec<Mapping> {
+    let mut sorted = mappings.to_vec();
+    sorted.sort();
+    sorted
+}
+
+/// Orders two (possibly absent) candidate results by cost ascending, then by
+/// their canonical mapping form ascending. Used by `run_exact_algorithm` to
+/// reduce every parallel branch's local best into one deterministic overall
+/// winner, independent of the order branches happen to finish in.
+fn prefer(a: Option<SolutionResult>, b: Option<SolutionResult>) -> Option<SolutionResult> {
+    match (a, b) {
+        (None, other) | (other, None) => other,
+        (Some(a), Some(b)) => {
+            let key = |r: &SolutionResult| (r.0, canonical_mappings(&r.2));
+            if key(&b) < key(&a) {
+                Some(b)
+            } else {
+                Some(a)
+            }
+        }
+    }
+}
+
+/// How many times worse `approx_cost` is than `exact_cost` (the proven, or
+/// best-effort, optimum). `usize::MAX`, cast to `f64`, stands in for
+/// infinity: `exact_cost` is 0 (no edges needed at all) but `approx_cost`
+/// isn't, so no finite multiple describes the gap. Equal zero costs are a
+/// perfect match (ratio 1.0), not a division by zero.
+fn approximation_ratio(exact_cost: usize, approx_cost: usize) -> f64 {
+    if exact_cost == 0 {
+        if approx_cost == 0 {
+            1.0
+        } else {
+            usize::MAX as f64
+        }
+    } else {
+        approx_cost as f64 / exact_cost as f64
+    }
+}
+
+/// Recursively extend `chosen` (indices into `all_mappings`, strictly
+/// increasing) with mappings starting at `next_idx`, pushing each candidate
+/// into `accumulator` before recursing and popping it back out on return.
+/// Once `chosen` reaches length `k`, records the accumulator's cost as a new
+/// best if it beats `best_cost`, flushing a checkpoint (see
+/// `run_exact_algorithm`'s doc comment) when one is configured.
+///
+/// `all_mappings` and `costs` must be sorted ascending by `costs[i]`, each
+/// mapping's own standalone cost (its cost under `objective` as if it were
+/// the only mapping chosen) — this is what lets the loop below both compute
+/// a branch-and-bound lower bound cheaply and `break` out entirely once that
+/// bound can no longer improve on the incumbent, instead of only skipping
+/// one candidate at a time.
+///
+/// `best_cost` and `best_result` are owned exclusively by the single branch
+/// of `run_exact_algorithm`'s parallel fan-out that called in here (see its
+/// doc comment): nothing outside that branch's own call tree ever reads or
+/// writes them mid-search, so a plain `&mut` pair — and a strict "only a
+/// strictly lower cost wins" rule — is enough to make each branch's result a
+/// deterministic function of its own, fixed traversal order. Breaking ties
+/// between *different* branches' results is `prefer`'s job, applied once
+/// after every branch has finished.
+///
+/// `best_checkpointed_cost`, unlike `best_cost`, *is* shared across every
+/// branch: checkpoints are a best-effort progress artifact for the UI and
+/// for `--resume-from`, not part of the search's own correctness, so it's
+/// fine (and useful) for them to reflect whichever branch has found the
+/// best result so far, ratcheted down via compare-and-swap the same way the
+/// old shared `best_cost` hint was. The same ratchet also gates a
+/// `ProgressMessage::Status` announcing the new incumbent, independent of
+/// whether a checkpoint path is configured at all.
+///
+/// `nodes_visited` also drives `ProgressMessage::MappingProgress` reports:
+/// every time it crosses a multiple of 10,000, the current count and
+/// `total_combinations` are sent over `tx` so `render_calculating` can show a
+/// percentage and a combinations/sec rate instead of sitting on a single
+/// status line for the whole search.
+///
+/// `stop` is set by `run_exact_algorithm`'s `--timeout` watcher thread; it's
+/// checked once per loop iteration (cheap: a single atomic load) and, once
+/// set, unwinds the recursion immediately, leaving `best_result` as whatever
+/// this branch had found so far rather than its eventual (possibly unreached)
+/// optimum.
+#[allow(clippy::too_many_arguments)]
+fn search_combinations(
+    all_mappings: &[Mapping],
+    costs: &[usize],
+    g: &Graph,
+    h: &Graph,
+    k: usize,
+    next_idx: usize,
+    objective: &Objective,
+    accumulator: &mut EdgeMapAccumulator,
+    chosen: &mut Vec<usize>,
+    best_cost: &mut usize,
+    best_result: &mut Option<SolutionResult>,
+    nodes_visited: &AtomicUsize,
+    total_combinations: usize,
+    vertex_surcharge: usize,
+    vertices_added: usize,
+    vertex_cost: usize,
+    checkpoint: &Option<(PathBuf, Duration)>,
+    best_checkpointed_cost: &AtomicUsize,
+    last_checkpoint: &Mutex<Instant>,
+    incumbent: &Option<Sender<IncumbentUpdate>>,
+    start_time: Instant,
+    estimated_memory_bytes: usize,
+    tx: &Sender<ProgressMessage>,
+    stop: &AtomicBool,
+) {
+    if chosen.len() == k {
+        let total_cost = accumulator.evaluate(objective);
+
+        if total_cost >= *best_cost {
+            return;
+        }
+
+        let edge_map = accumulator.edge_map();
+        let mappings: Vec<Mapping> = chosen.iter().map(|&i| all_mappings[i].clone()).collect();
+        *best_cost = total_cost;
+        *best_result = Some((total_cost, edge_map.clone(), mappings.clone()));
+
+        let mut observed = best_checkpointed_cost.load(Ordering::Relaxed);
+        let became_global_best = loop {
+            if total_cost >= observed {
+                break false;
+            }
+            match best_checkpointed_cost.compare_exchange_weak(
+                observed,
+                total_cost,
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => break true,
+                Err(latest) => observed = latest,
+            }
+        };
+
+        if became_global_best {
+            tx.send(ProgressMessage::Status(format!(
+                "New best cost found: {}",
+                total_cost + vertex_surcharge
+            )))
+            .ok();
+
+            if let Some((path, interval)) = checkpoint {
+                let mut last_guard = last_checkpoint.lock().unwrap();
+                if last_guard.elapsed() >= *interval {
+                    *last_guard = Instant::now();
+                    let _ = write_results_to_file(
+                        path,
+                        g,
+                        h,
+                        k,
+                        Algorithm::Exact,
+                        total_cost + vertex_surcharge,
+                        vertices_added,
+                        vertex_cost,
+                        &edge_map,
+                        &mappings,
+                        start_time.elapsed(),
+                        true,
+                        Some(estimated_memory_bytes),
+                        false,
+                        None,
+                        None,
+                    );
+                }
+            }
+
+            // Unlike `checkpoint`, which is interval-throttled, every
+            // improvement is sent here unconditionally: the whole point of
+            // `--incumbent-file` is an anytime stream, not a periodic
+            // snapshot (see `run_incumbent_writer`'s doc comment).
+            if let Some(incumbent_tx) = incumbent {
+                incumbent_tx
+                    .send(IncumbentUpdate {
+                        cost: total_cost + vertex_surcharge,
+                        edge_map,
+                        mappings,
+                        elapsed: start_time.elapsed(),
+                        nodes_visited: nodes_visited.load(Ordering::Relaxed),
+                    })
+                    .ok();
+            }
+        }
+        return;
+    }
+
+    // Leave enough room in `all_mappings` after `idx` for the remaining slots.
+    let remaining = k - chosen.len();
+    let last_idx = all_mappings.len() - remaining;
+
+    for idx in next_idx..=last_idx {
+        if stop.load(Ordering::Relaxed) {
+            return;
+        }
+
+        // Lower bound on any solution completed from here: the accumulated
+        // cost so far never falls as more mappings are merged in, and at
+        // least one more mapping must still be added, whose own standalone
+        // cost is at least `costs[idx]` (the cheapest remaining candidate,
+        // since `all_mappings` is sorted ascending).
+        let bound = accumulator.evaluate(objective).max(costs[idx]);
+        if bound >= *best_cost {
+            // Every later idx has an equal or higher standalone cost, so the
+            // bound only gets worse from here: nothing later in this loop
+            // can improve on the incumbent either.
+            break;
+        }
+
+        let visited = nodes_visited.fetch_add(1, Ordering::Relaxed) + 1;
+        if visited.is_multiple_of(10_000) {
+            tx.send(ProgressMessage::MappingProgress {
+                current: visited,
+                total: total_combinations,
+            })
+            .ok();
+        }
+        accumulator.add_mapping(g, h, &all_mappings[idx]);
+        chosen.push(idx);
+
+        search_combinations(
+            all_mappings,
+            costs,
+            g,
+            h,
+            k,
+            idx + 1,
+            objective,
+            accumulator,
+            chosen,
+            best_cost,
+            best_result,
+            nodes_visited,
+            total_combinations,
+            vertex_surcharge,
+            vertices_added,
+            vertex_cost,
+            checkpoint,
+            best_checkpointed_cost,
+            last_checkpoint,
+            incumbent,
+            start_time,
+            estimated_memory_bytes,
+            tx,
+            stop,
+        );
+
+        chosen.pop();
+        accumulator.remove_mapping(g, h, &all_mappings[idx]);
+    }
+}
+
+/// Run the exact algorithm in a background thread.
+///
+/// Performs a depth-first branch-and-bound over mappings sorted by their own
+/// standalone cost (see `search_combinations`), seeded by a quick greedy
+/// construction (`greedy_incumbent`) instead of exhaustively evaluating every
+/// k-combination.
+///
+/// `checkpoint` is `(output path, minimum interval between writes)`: whenever
+/// a new best is found, the best-so-far solution is flushed to that path
+/// (marked `CHECKPOINT (NOT FINAL)`, see `write_results_to_file`) provided at
+/// least that much time has passed since the last checkpoint write.
+/// `resume_best_cost`, if set, seeds the search's best-known cost (alongside
+/// the greedy seed, whichever is lower) so combinations no better than an
+/// already-known solution are skipped without being evaluated.
+///
+/// Every branch starts from the same fixed incumbent and never observes
+/// another branch's progress, so two runs always fan out into the exact same
+/// per-branch work and arrive at the exact same per-branch result (see
+/// `search_combinations`'s doc comment) — the final result is then a
+/// deterministic reduction (`prefer`) over those branch-local results,
+/// rather than whichever branch happened to win a race on a shared
+/// incumbent.
+///
+/// `timeout`, if set, spawns a scoped watcher thread that sleeps for that
+/// many seconds and then sets a shared `stop` flag every branch checks once
+/// per node visited (see `search_combinations`). A branch mid-search simply
+/// stops recursing and returns whatever it had found so far, so the reported
+/// result is always feasible — just not necessarily optimal, which is why it
+/// comes back flagged as timed-out rather than silently reported the same
+/// way a completed search would be.
+#[allow(clippy::too_many_arguments)]
+fn run_exact_algorithm(
+    g: Graph,
+    h: Graph,
+    k: usize,
+    objective: Objective,
+    refine: Option<RefineMode>,
+    beam_width: usize,
+    seed_strategy: SeedStrategy,
+    deterministic_ties: bool,
+    undirected: bool,
+    resume_best_cost: Option<usize>,
+    checkpoint: Option<(PathBuf, Duration)>,
+    incumbent_file: Option<PathBuf>,
+    vertices_added: usize,
+    vertex_cost: usize,
+    memory_limit_mb: Option<usize>,
+    restarts: usize,
+    time_limit: Option<f64>,
+    trials_multiplier: usize,
+    rng_seed: u64,
+    timeout: Option<u64>,
+    max_combinations: usize,
+    force: bool,
+    tx: Sender<ProgressMessage>,
+) {
+    let start_time = Instant::now();
+    let vertex_surcharge = vertices_added * vertex_cost;
+
+    tx.send(ProgressMessage::Status(
+        "Finding all possible mappings...".to_string(),
+    ))
+    .ok();
+    let all_mappings = if undirected {
+        find_all_mappings_undirected(&g, &h)
+    } else {
+        // `current` is reported, not `current / total`-as-a-fraction, since
+        // `render_calculating` already knows how to draw a raw count against
+        // a total (see the branch-and-bound's own `MappingProgress` sends
+        // below) -- every 1000 mappings keeps the channel from being flooded
+        // on instances that enumerate into the millions.
+        let total = permutation_count(h.num_vertices(), g.num_vertices())
+            .min(usize::MAX as u128) as usize;
+        find_all_mappings_with_progress(&g, &h, |current| {
+            if current % 1000 == 0 {
+                tx.send(ProgressMessage::MappingProgress { current, total })
+                    .ok();
+            }
+        })
+    };
+
+    tx.send(ProgressMessage::Status(format!(
+        "Found {} total mappings",
+        all_mappings.len()
+    )))
+    .ok();
+
+    if all_mappings.len() < k {
+        tx.send(ProgressMessage::Error(format!(
+            "Not enough mappings. Need {}, found {} (the maximum achievable k here is {})",
+            k,
+            all_mappings.len(),
+            all_mappings.len()
+        )))
+        .ok();
+        return;
+    }
+
+    let estimated_memory_bytes = estimate_memory_bytes(all_mappings.len(), g.num_vertices());
+    tx.send(ProgressMessage::Status(format!(
+        "Estimated mapping-pool memory: {:.1} MB",
+        estimated_memory_bytes as f64 / 1_000_000.0
+    )))
+    .ok();
+
+    // `all_mappings.len() == k` leaves exactly one k-combination: the whole
+    // pool. There's nothing to branch on, so skip straight to scoring it
+    // instead of spinning up the rayon fan-out, greedy seed, and
+    // `search_combinations` recursion for a search space of size 1.
+    if all_mappings.len() == k {
+        tx.send(ProgressMessage::Status(
+            "Exactly k mappings available -- using all of them, no search needed".to_string(),
+        ))
+        .ok();
+        let edge_map = calculate_edge_map(&g, &h, &all_mappings);
+        let cost = objective.evaluate(&edge_map);
+        tx.send(ProgressMessage::Complete {
+            algorithm: Algorithm::Exact,
+            cost: cost + vertex_surcharge,
+            edge_map,
+            mappings: all_mappings,
+            elapsed: start_time.elapsed(),
+            search_stats: Some((1, 1)),
+            estimated_memory_bytes: Some(estimated_memory_bytes),
+            timed_out: false,
+        })
+        .ok();
+        return;
+    }
+
+    if let Some(limit_mb) = memory_limit_mb {
+        if estimated_memory_bytes > limit_mb.saturating_mul(1_000_000) {
+            tx.send(ProgressMessage::Status(format!(
+                "Estimated memory exceeds --memory-limit ({} MB); falling back to the approximation algorithm",
+                limit_mb
+            )))
+            .ok();
+            run_approx_algorithm(
+                g,
+                h,
+                k,
+                restarts,
+                time_limit,
+                trials_multiplier,
+                rng_seed,
+                objective,
+                refine,
+                beam_width,
+                seed_strategy,
+                deterministic_ties,
+                vertices_added,
+                vertex_cost,
+                Some(estimated_memory_bytes),
+                tx,
+            );
+            return;
+        }
+    }
+
+    let total_combinations = num_combinations(all_mappings.len(), k);
+
+    if total_combinations > max_combinations && !force {
+        let projected_runtime =
+            calibrate_projected_runtime(&all_mappings, &g, &h, k, &objective, total_combinations);
+        tx.send(ProgressMessage::CombinationGuardTriggered {
+            total_combinations,
+            max_combinations,
+            projected_runtime,
+        })
+        .ok();
+        return;
+    }
+
+    tx.send(ProgressMessage::Status(format!(
+        "Branch-and-bound search over {} combinations (exhaustive worst case)...",
+        total_combinations
+    )))
+    .ok();
+
+    let seed = greedy_incumbent(&all_mappings, &g, &h, k, &objective);
+    if let Some((seed_cost, _, _)) = &seed {
+        tx.send(ProgressMessage::Status(format!(
+            "Greedy seed cost: {}",
+            seed_cost + vertex_surcharge
+        )))
+        .ok();
+    }
+
+    // Visit cheapest-standalone-cost mappings first; see `search_combinations`'s
+    // doc comment for why the sort is load-bearing, not just an optimization.
+    let mut order: Vec<usize> = (0..all_mappings.len()).collect();
+    let standalone_cost: Vec<usize> = all_mappings
+        .iter()
+        .map(|mapping| {
+            objective.evaluate(&calculate_edge_map(&g, &h, std::slice::from_ref(mapping)))
+        })
+        .collect();
+    order.sort_by_key(|&i| standalone_cost[i]);
+    let sorted_mappings: Vec<Mapping> = order.iter().map(|&i| all_mappings[i].clone()).collect();
+    let sorted_costs: Vec<usize> = order.iter().map(|&i| standalone_cost[i]).collect();
+
+    // A resumed checkpoint's cost was written out with the vertex surcharge
+    // already included (see `write_results_to_file` above), so strip it back
+    // off before comparing it against the greedy seed's pure edge cost.
+    let resume_edge_cost = resume_best_cost.map(|cost| cost.saturating_sub(vertex_surcharge));
+    let seed_edge_cost = seed.as_ref().map(|(cost, _, _)| *cost);
+    let initial_best = seed_edge_cost
+        .into_iter()
+        .chain(resume_edge_cost)
+        .min()
+        .unwrap_or(usize::MAX);
+
+    let initial_best_result = seed.filter(|(cost, _, _)| *cost == initial_best);
+
+    // No combination -- not even the true optimum -- can cost less than
+    // `lower_bound`, so an incumbent that already matches it is provably
+    // optimal: skip the branch-and-bound search entirely instead of walking
+    // combinations that can only confirm what's already known.
+    let lower_bound = cost_lower_bound(&g, &h);
+    tx.send(ProgressMessage::Status(format!(
+        "Lower bound on optimal cost: {}",
+        lower_bound + vertex_surcharge
+    )))
+    .ok();
+    if initial_best <= lower_bound {
+        tx.send(ProgressMessage::Status(
+            "Incumbent already matches the lower bound -- search is provably optimal, skipping"
+                .to_string(),
+        ))
+        .ok();
+        tx.send(ProgressMessage::MappingProgress {
+            current: 0,
+            total: total_combinations,
+        })
+        .ok();
+        match initial_best_result {
+            Some((cost, edge_map, mappings)) => {
+                tx.send(ProgressMessage::Complete {
+                    algorithm: Algorithm::Exact,
+                    cost: cost + vertex_surcharge,
+                    edge_map,
+                    mappings,
+                    elapsed: start_time.elapsed(),
+                    search_stats: Some((0, total_combinations)),
+                    estimated_memory_bytes: Some(estimated_memory_bytes),
+                    timed_out: false,
+                })
+                .ok();
+            }
+            None => {
+                tx.send(ProgressMessage::Error(
+                    "Internal error: no incumbent despite matching the lower bound".to_string(),
+                ))
+                .ok();
+            }
+        }
+        return;
+    }
+
+    let nodes_visited = AtomicUsize::new(0);
+    let best_checkpointed_cost = AtomicUsize::new(initial_best);
+    let last_checkpoint: Mutex<Instant> = Mutex::new(start_time);
+    let stop = AtomicBool::new(false);
+
+    let n = sorted_mappings.len();
+    let branch_result = thread::scope(|scope| {
+        if let Some(timeout_secs) = timeout {
+            let stop = &stop;
+            scope.spawn(move || {
+                thread::sleep(Duration::from_secs(timeout_secs));
+                stop.store(true, Ordering::Relaxed);
+            });
+        }
+
+        // `incumbent_tx` is declared inside this scope (rather than passed
+        // in) so it gets dropped when this closure returns, before
+        // `thread::scope` joins the writer thread below: that's what lets
+        // the writer's `for update in rx` loop see the channel close and
+        // exit instead of blocking forever.
+        let incumbent_tx: Option<Sender<IncumbentUpdate>> = incumbent_file.map(|path| {
+            let (incumbent_tx, incumbent_rx) = channel();
+            let progress_tx = &tx;
+            let g = &g;
+            let h = &h;
+            scope.spawn(move || {
+                run_incumbent_writer(
+                    path,
+                    g,
+                    h,
+                    k,
+                    vertices_added,
+                    vertex_cost,
+                    estimated_memory_bytes,
+                    incumbent_rx,
+                    progress_tx,
+                );
+            });
+            incumbent_tx
+        });
+
+        (0..=n - k)
+            .into_par_iter()
+            .map(|first| {
+                if stop.load(Ordering::Relaxed) || sorted_costs[first] >= initial_best {
+                    // Same reasoning as `search_combinations`'s inner
+                    // `break`, just without one: a parallel iterator can't
+                    // short-circuit the remaining `first` values, so each
+                    // checks the bound (and the timeout) itself.
+                    return None;
+                }
+
+                let mut accumulator = EdgeMapAccumulator::new();
+                let mut chosen = Vec::with_capacity(k);
+                let mut best_cost = initial_best;
+                let mut best_result = None;
+
+                let visited = nodes_visited.fetch_add(1, Ordering::Relaxed) + 1;
+                if visited.is_multiple_of(10_000) {
+                    tx.send(ProgressMessage::MappingProgress {
+                        current: visited,
+                        total: total_combinations,
+                    })
+                    .ok();
+                }
+                accumulator.add_mapping(&g, &h, &sorted_mappings[first]);
+                chosen.push(first);
+
+                search_combinations(
+                    &sorted_mappings,
+                    &sorted_costs,
+                    &g,
+                    &h,
+                    k,
+                    first + 1,
+                    &objective,
+                    &mut accumulator,
+                    &mut chosen,
+                    &mut best_cost,
+                    &mut best_result,
+                    &nodes_visited,
+                    total_combinations,
+                    vertex_surcharge,
+                    vertices_added,
+                    vertex_cost,
+                    &checkpoint,
+                    &best_checkpointed_cost,
+                    &last_checkpoint,
+                    &incumbent_tx,
+                    start_time,
+                    estimated_memory_bytes,
+                    &tx,
+                    &stop,
+                );
+
+                best_result
+            })
+            .reduce(|| None, prefer)
+    });
+
+    let timed_out = stop.load(Ordering::Relaxed);
+    let final_result = prefer(initial_best_result, branch_result);
+    let nodes_visited = nodes_visited.into_inner();
+
+    // Always flush one final report, regardless of whether `nodes_visited`
+    // happens to land on a multiple of 10,000: a search small enough to
+    // finish before that, or one pruned down to far fewer visits, should
+    // still leave `render_calculating` showing a complete (not stalled) bar.
+    tx.send(ProgressMessage::MappingProgress {
+        current: nodes_visited,
+        total: total_combinations,
+    })
+    .ok();
+
+    if timed_out {
+        tx.send(ProgressMessage::Status(format!(
+            "TIME LIMIT REACHED -- best found, optimality not proven ({} of {} combinations evaluated, {:.1}%)",
+            nodes_visited,
+            total_combinations,
+            100.0 * nodes_visited as f64 / total_combinations.max(1) as f64
+        )))
+        .ok();
+    } else {
+        tx.send(ProgressMessage::Status(format!(
+            "Finished branch-and-bound search: {} nodes visited (of {} possible combinations)",
+            nodes_visited, total_combinations
+        )))
+        .ok();
+    }
+
+    match final_result {
+        Some((cost, edge_map, mappings)) => {
+            tx.send(ProgressMessage::Complete {
+                algorithm: Algorithm::Exact,
+                cost: cost + vertex_surcharge,
+                edge_map,
+                mappings,
+                elapsed: start_time.elapsed(),
+                search_stats: Some((nodes_visited, total_combinations)),
+                estimated_memory_bytes: Some(estimated_memory_bytes),
+                timed_out,
+            })
+            .ok();
+        }
+        None if timed_out => {
+            // The timeout fired before even the greedy seed could complete
+            // (see `run_exact_algorithm`'s doc comment) -- no feasible
+            // solution exists yet, so fall back to the approx algorithm on
+            // the same terms `--memory-limit` does above, using whatever of
+            // the original budget the caller still wants to spend.
+            tx.send(ProgressMessage::Status(
+                "Time limit reached before a solution was found; falling back to the approximation algorithm"
+                    .to_string(),
+            ))
+            .ok();
+            run_approx_algorithm(
+                g,
+                h,
+                k,
+                restarts,
+                time_limit,
+                trials_multiplier,
+                rng_seed,
+                objective,
+                refine,
+                beam_width,
+                seed_strategy,
+                deterministic_ties,
+                vertices_added,
+                vertex_cost,
+                Some(estimated_memory_bytes),
+                tx,
+            );
+        }
+        None if resume_best_cost.is_some() => {
+            tx.send(ProgressMessage::Error(
+                "No solution better than the resumed checkpoint was found".to_string(),
+            ))
+            .ok();
+        }
+        None => {
+            tx.send(ProgressMessage::Error("No solution found".to_string()))
+                .ok();
+        }
+    }
+}
+
+/// Drain `rx` of incumbent improvements and write each to `path`, one at a
+/// time, on its own thread -- moving this off the rayon search workers that
+/// find each improvement is the whole point (see `IncumbentUpdate`'s doc
+/// comment): a worker that had to block on `File::create` every time it beat
+/// the incumbent would spend more time on IO than on search as the incumbent
+/// keeps improving.
+///
+/// Each write goes to a sibling `.tmp` path first, then an atomic rename
+/// into `path`, so a reader polling `path` (the `--incumbent-file` contract)
+/// never observes a half-written file. `rx` yields its updates in the order
+/// `search_combinations` found them, and each only replaces the global best
+/// it's named after (see `best_checkpointed_cost`'s compare-exchange ratchet
+/// in `search_combinations`), so the cost written here is monotonically
+/// non-increasing across calls.
+#[allow(clippy::too_many_arguments)]
+fn run_incumbent_writer(
+    path: PathBuf,
+    g: &Graph,
+    h: &Graph,
+    k: usize,
+    vertices_added: usize,
+    vertex_cost: usize,
+    estimated_memory_bytes: usize,
+    rx: Receiver<IncumbentUpdate>,
+    tx: &Sender<ProgressMessage>,
+) {
+    let tmp_path = path.with_extension("tmp");
+    for update in rx {
+        let written = write_results_to_file(
+            &tmp_path,
+            g,
+            h,
+            k,
+            Algorithm::Exact,
+            update.cost,
+            vertices_added,
+            vertex_cost,
+            &update.edge_map,
+            &update.mappings,
+            update.elapsed,
+            true,
+            Some(estimated_memory_bytes),
+            false,
+            None,
+            None,
+        )
+        .and_then(|()| std::fs::rename(&tmp_path, &path));
+
+        if written.is_ok() {
+            tx.send(ProgressMessage::Incumbent {
+                cost: update.cost,
+                elapsed: update.elapsed,
+                nodes_visited: update.nodes_visited,
+            })
+            .ok();
+        }
+    }
+}
+
+/// One full sequential-greedy construction of k mappings, optionally
+/// reporting per-mapping progress over `tx` (suppressed when run as part of a
+/// parallel restart batch, since interleaved status lines from several
+/// threads would be meaningless).
+#[allow(clippy::too_many_arguments)]
+fn run_approx_once(
+    g: &Graph,
+    h: &Graph,
+    k: usize,
+    trials_multiplier: usize,
+    rng: &mut impl Rng,
+    objective: &Objective,
+    refine: Option<RefineMode>,
+    beam_width: usize,
+    seed_strategy: SeedStrategy,
+    deterministic_ties: bool,
+    tx: Option<&Sender<ProgressMessage>>,
+    deadline: Option<Instant>,
+) -> Option<(usize, EdgeMap, Vec<Mapping>)> {
+    let mut h_prime = h.clone();
+    let mut used_mappings = MappingSet::default();
+    let mut minimal_extension = EdgeMap::new();
+    let mut all_mappings = Vec::new();
+
+    let total_trials = g.num_vertices() * h.num_vertices() * trials_multiplier;
+    if let Some(tx) = tx {
+        tx.send(ProgressMessage::Status(format!(
+            "Trials per mapping: {} (n₁ × n₂ × {})",
+            total_trials, trials_multiplier
+        )))
+        .ok();
+    }
+
+    for i in 1..=k {
+        if let Some(tx) = tx {
+            tx.send(ProgressMessage::MappingProgress {
+                current: i,
+                total: k,
+            })
+            .ok();
+        }
+
+        // Recomputed for every mapping, not carved up once up front: the
+        // share left for the mappings still to come only shrinks by however
+        // much time this one actually used, not by an equal fixed slice.
+        let mapping_deadline = deadline.map(|d| {
+            let now = Instant::now();
+            let remaining_mappings = (k - i + 1) as u32;
+            now + d.saturating_duration_since(now) / remaining_mappings
+        });
+
+        match approximate_best_mapping(
+            g,
+            h,
+            &h_prime,
+            &minimal_extension,
+            &used_mappings,
+            trials_multiplier,
+            rng,
+            objective,
+            EarlyStop::Never,
+            None,
+            None,
+            None,
+            &HashSet::new(),
+            MergeSemantics::Shared,
+            beam_width,
+            seed_strategy,
+            deterministic_ties,
+            mapping_deadline,
+        ) {
+            Some((best_mapping, increments, _trials_executed)) => {
+                let refine_within_budget = if deadline.is_some_and(|d| Instant::now() >= d) {
+                    None
+                } else {
+                    refine
+                };
+                let (best_mapping, increments) = match refine_within_budget {
+                    Some(RefineMode::Sa(schedule)) => {
+                        let before_cost = objective.evaluate(&increments);
+                        let (refined_mapping, refined_increments) = refine_mapping_sa(
+                            g,
+                            h,
+                            &minimal_extension,
+                            &best_mapping,
+                            MergeSemantics::Shared,
+                            objective,
+                            &HashSet::new(),
+                            &used_mappings,
+                            &schedule,
+                            rng,
+                        );
+                        let after_cost = objective.evaluate(&refined_increments);
+                        if let Some(tx) = tx {
+                            if after_cost < before_cost {
+                                tx.send(ProgressMessage::Status(format!(
+                                    "Refined mapping {}/{}: cost {} -> {}",
+                                    i, k, before_cost, after_cost
+                                )))
+                                .ok();
+                            }
+                        }
+                        (refined_mapping, refined_increments)
+                    }
+                    Some(RefineMode::TwoOpt) => {
+                        let before_cost = objective.evaluate(&increments);
+                        let (refined_mapping, _) = local_search_2opt(g, &h_prime, &best_mapping);
+                        let (_, refined_increments) = marginal_cost(
+                            g,
+                            h,
+                            &minimal_extension,
+                            &refined_mapping,
+                            MergeSemantics::Shared,
+                        );
+                        let after_cost = objective.evaluate(&refined_increments);
+                        if let Some(tx) = tx {
+                            if after_cost < before_cost {
+                                tx.send(ProgressMessage::Status(format!(
+                                    "Refined mapping {}/{}: cost {} -> {}",
+                                    i, k, before_cost, after_cost
+                                )))
+                                .ok();
+                            }
+                        }
+                        (refined_mapping, refined_increments)
+                    }
+                    None => (best_mapping, increments),
+                };
+
+                for ((x, y), weight) in increments.iter() {
+                    *minimal_extension.entry((*x, *y)).or_insert(0) += *weight;
+                }
+
+                // Re-derive H' from the now-correct minimal_extension, rather
+                // than mutating it incrementally against itself.
+                h_prime = apply_edge_map(h, &minimal_extension);
+
+                used_mappings.insert(best_mapping.clone());
+                all_mappings.push(best_mapping);
+
+                if let Some(tx) = tx {
+                    tx.send(ProgressMessage::Status(format!(
+                        "✓ Mapping {}/{} found",
+                        i, k
+                    )))
+                    .ok();
+                }
+            }
+            None => {
+                if let Some(tx) = tx {
+                    tx.send(ProgressMessage::Error(format!(
+                        "Failed to find mapping {}/{}",
+                        i, k
+                    )))
+                    .ok();
+                }
+                return None;
+            }
+        }
+    }
+
+    let total_cost = objective.evaluate(&minimal_extension);
+    Some((total_cost, minimal_extension, all_mappings))
+}
+
+/// Run the approximation algorithm in a background thread
+#[allow(clippy::too_many_arguments)]
+fn run_approx_algorithm(
+    g: Graph,
+    h: Graph,
+    k: usize,
+    restarts: usize,
+    time_limit: Option<f64>,
+    trials_multiplier: usize,
+    seed: u64,
+    objective: Objective,
+    refine: Option<RefineMode>,
+    beam_width: usize,
+    seed_strategy: SeedStrategy,
+    deterministic_ties: bool,
+    vertices_added: usize,
+    vertex_cost: usize,
+    estimated_memory_bytes: Option<usize>,
+    tx: Sender<ProgressMessage>,
+) {
+    let start_time = Instant::now();
+    let vertex_surcharge = vertices_added * vertex_cost;
+
+    tx.send(ProgressMessage::Status(format!(
+        "RNG seed: {} (pass --seed {} to reproduce this run)",
+        seed, seed
+    )))
+    .ok();
+
+    // A single absolute deadline shared by every restart (each restart isn't
+    // granted its own copy of the budget) and divided across the k mappings
+    // inside `run_approx_once` itself.
+    let deadline = time_limit.map(|secs| {
+        tx.send(ProgressMessage::Status(format!("Time budget: {:.3}s", secs)))
+            .ok();
+        start_time + Duration::from_secs_f64(secs)
+    });
+
+    let best_result = if restarts > 1 {
+        tx.send(ProgressMessage::Status(format!(
+            "Running {} restarts of the approximation...",
+            restarts
+        )))
+        .ok();
+
+        let best_cost = Mutex::new(None::<usize>);
+        let restart_results: Vec<(usize, EdgeMap, Vec<Mapping>)> = (0..restarts)
+            .into_par_iter()
+            .map(|restart| {
+                let mut rng = StdRng::seed_from_u64(seed.wrapping_add(restart as u64));
+                let result = run_approx_once(
+                    &g,
+                    &h,
+                    k,
+                    trials_multiplier,
+                    &mut rng,
+                    &objective,
+                    refine,
+                    beam_width,
+                    seed_strategy,
+                    deterministic_ties,
+                    None,
+                    deadline,
+                );
+                if let Some((cost, _, _)) = &result {
+                    let mut guard = best_cost.lock().unwrap();
+                    if guard.is_none() || *cost < guard.unwrap() {
+                        *guard = Some(*cost);
+                    }
+                }
+                tx.send(ProgressMessage::RestartProgress {
+                    restart: restart + 1,
+                    total_restarts: restarts,
+                    best_cost_so_far: *best_cost.lock().unwrap(),
+                })
+                .ok();
+                result
+            })
+            .flatten()
+            .collect();
+
+        let mut costs: Vec<usize> = restart_results.iter().map(|(cost, _, _)| *cost).collect();
+        if !costs.is_empty() {
+            costs.sort_unstable();
+            tx.send(ProgressMessage::Status(format!(
+                "Restart costs across {} completed restart(s): min {}, median {}, max {}",
+                costs.len(),
+                costs[0],
+                costs[costs.len() / 2],
+                costs[costs.len() - 1],
+            )))
+            .ok();
+        }
+
+        restart_results.into_iter().min_by_key(|(cost, _, _)| *cost)
+    } else {
+        tx.send(ProgressMessage::Status(format!(
+            "Finding {} distinct mappings...",
+            k
+        )))
+        .ok();
+        let mut rng = StdRng::seed_from_u64(seed);
+        run_approx_once(
+            &g,
+            &h,
+            k,
+            trials_multiplier,
+            &mut rng,
+            &objective,
+            refine,
+            beam_width,
+            seed_strategy,
+            deterministic_ties,
+            Some(&tx),
+            deadline,
+        )
+    };
+
+    let elapsed = start_time.elapsed();
+    if let Some(secs) = time_limit {
+        tx.send(ProgressMessage::Status(format!(
+            "Time budget: {:.3}s, actual time used: {:.3}s",
+            secs,
+            elapsed.as_secs_f64()
+        )))
+        .ok();
+    }
+
+    match best_result {
+        Some((cost, edge_map, mappings)) => {
+            tx.send(ProgressMessage::Complete {
+                algorithm: Algorithm::Approx,
+                cost: cost + vertex_surcharge,
+                edge_map,
+                mappings,
+                elapsed,
+                search_stats: None,
+                estimated_memory_bytes,
+                timed_out: false,
+            })
+            .ok();
+        }
+        None => {
+            tx.send(ProgressMessage::Error(format!(
+                "Failed to find {} distinct mappings across {} restart(s)",
+                k, restarts
+            )))
+            .ok();
+        }
+    }
+}
+
+/// Run the exact algorithm to get the optimum, then the approx algorithm, and
+/// report the approximation ratio between them (`ProgressMessage::CompareComplete`).
+///
+/// Each sub-algorithm runs to completion on its own local channel (the same
+/// "run it, then drain its channel" pattern `run_batch_instance` uses) rather
+/// than sharing `tx` directly: `run_exact_algorithm` can itself fall back to
+/// the approx algorithm (via `--memory-limit`/`--timeout`) and send its own
+/// `Complete`, which would be ambiguous to tell apart from this function's own
+/// two runs on the same channel. Every `Status`/`MappingProgress` message from
+/// each sub-run is forwarded to `tx` as-is, so `render_calculating` still
+/// shows live progress through both phases; only the terminal
+/// `Complete`/`Error` message is intercepted instead of forwarded.
+#[allow(clippy::too_many_arguments)]
+fn run_compare_algorithm(
+    g: Graph,
+    h: Graph,
+    k: usize,
+    objective: Objective,
+    refine: Option<RefineMode>,
+    beam_width: usize,
+    seed_strategy: SeedStrategy,
+    deterministic_ties: bool,
+    undirected: bool,
+    vertices_added: usize,
+    vertex_cost: usize,
+    memory_limit_mb: Option<usize>,
+    restarts: usize,
+    time_limit: Option<f64>,
+    trials_multiplier: usize,
+    seed: u64,
+    timeout: Option<u64>,
+    tx: Sender<ProgressMessage>,
+) {
+    let (exact_tx, exact_rx) = channel();
+    // Compare mode always runs both algorithms to completion regardless of
+    // search size, so the `--max-combinations` guard (which exists to let a
+    // run be refused or redirected to approx) doesn't apply here -- force
+    // past it unconditionally.
+    run_exact_algorithm(
+        g.clone(),
+        h.clone(),
+        k,
+        objective.clone(),
+        refine,
+        beam_width,
+        seed_strategy,
+        deterministic_ties,
+        undirected,
+        None,
+        None,
+        None,
+        vertices_added,
+        vertex_cost,
+        memory_limit_mb,
+        restarts,
+        time_limit,
+        trials_multiplier,
+        seed,
+        timeout,
+        usize::MAX,
+        true,
+        exact_tx,
+    );
+
+    let mut exact_cost = None;
+    let mut exact_edge_map = None;
+    let mut exact_mappings = None;
+    let mut exact_elapsed = Duration::default();
+    let mut exact_timed_out = false;
+    for msg in exact_rx.try_iter() {
+        match msg {
+            ProgressMessage::Complete {
+                cost,
+                edge_map,
+                mappings,
+                elapsed,
+                timed_out,
+                ..
+            } => {
+                exact_cost = Some(cost);
+                exact_edge_map = Some(edge_map);
+                exact_mappings = Some(mappings);
+                exact_elapsed = elapsed;
+                exact_timed_out = timed_out;
+            }
+            ProgressMessage::Error(err) => {
+                tx.send(ProgressMessage::Error(format!(
+                    "Exact algorithm failed: {}",
+                    err
+                )))
+                .ok();
+                return;
+            }
+            other => {
+                tx.send(other).ok();
+            }
+        }
+    }
+    let (Some(exact_cost), Some(exact_edge_map), Some(exact_mappings)) =
+        (exact_cost, exact_edge_map, exact_mappings)
+    else {
+        tx.send(ProgressMessage::Error(
+            "Exact algorithm produced no completion message".to_string(),
+        ))
+        .ok();
+        return;
+    };
+
+    let (approx_tx, approx_rx) = channel();
+    run_approx_algorithm(
+        g,
+        h,
+        k,
+        restarts,
+        time_limit,
+        trials_multiplier,
+        seed,
+        objective,
+        refine,
+        beam_width,
+        seed_strategy,
+        deterministic_ties,
+        vertices_added,
+        vertex_cost,
+        None,
+        approx_tx,
+    );
+
+    let mut approx_cost = None;
+    let mut approx_edge_map = None;
+    let mut approx_mappings = None;
+    let mut approx_elapsed = Duration::default();
+    for msg in approx_rx.try_iter() {
+        match msg {
+            ProgressMessage::Complete {
+                cost,
+                edge_map,
+                mappings,
+                elapsed,
+                ..
+            } => {
+                approx_cost = Some(cost);
+                approx_edge_map = Some(edge_map);
+                approx_mappings = Some(mappings);
+                approx_elapsed = elapsed;
+            }
+            ProgressMessage::Error(err) => {
+                tx.send(ProgressMessage::Error(format!(
+                    "Approx algorithm failed: {}",
+                    err
+                )))
+                .ok();
+                return;
+            }
+            other => {
+                tx.send(other).ok();
+            }
+        }
+    }
+    let (Some(approx_cost), Some(approx_edge_map), Some(approx_mappings)) =
+        (approx_cost, approx_edge_map, approx_mappings)
+    else {
+        tx.send(ProgressMessage::Error(
+            "Approx algorithm produced no completion message".to_string(),
+        ))
+        .ok();
+        return;
+    };
+
+    let ratio = approximation_ratio(exact_cost, approx_cost);
+    tx.send(ProgressMessage::CompareComplete(Box::new(CompareResult {
+        exact_cost,
+        exact_edge_map,
+        exact_mappings,
+        exact_elapsed,
+        exact_timed_out,
+        approx_cost,
+        approx_edge_map,
+        approx_mappings,
+        approx_elapsed,
+        ratio,
+    })))
+    .ok();
+}
+
+/// Write results to a file
+#[allow(clippy::too_many_arguments)]
+fn write_results_to_file(
+    path: &PathBuf,
+    g: &Graph,
+    h: &Graph,
+    k: usize,
+    algorithm: Algorithm,
+    cost: usize,
+    vertices_added: usize,
+    vertex_cost: usize,
+    edge_map: &EdgeMap,
+    mappings: &[Mapping],
+    elapsed: Duration,
+    checkpoint: bool,
+    estimated_memory_bytes: Option<usize>,
+    timed_out: bool,
+    // `--threads`, as resolved for the run that produced this report. `None`
+    // for a `--checkpoint`/`--incumbent-file` snapshot taken mid-search,
+    // which doesn't have it threaded down to it; only the final solution
+    // report and `--batch` instance reports carry it.
+    threads: Option<usize>,
+    // Only set for `Algorithm::Approx` runs (see `AppState::approximation_lower_bound`).
+    approximation_lower_bound: Option<usize>,
+) -> io::Result<()> {
+    let mut file = File::create(path)?;
+
+    // Header
+    writeln!(
+        file,
+        "============================================================"
+    )?;
+    writeln!(
+        file,
+        "Minimal k-Isomorphic Subgraph Extension - Solution Report"
+    )?;
+    if checkpoint {
+        writeln!(file, "CHECKPOINT (NOT FINAL)")?;
+    }
+    if timed_out {
+        writeln!(
+            file,
+            "TIME LIMIT REACHED -- best found, optimality not proven"
+        )?;
+    }
+    writeln!(
+        file,
+        "============================================================"
+    )?;
+    writeln!(file)?;
+
+    // Algorithm info
+    writeln!(
+        file,
+        "Algorithm: {}",
+        match algorithm {
+            Algorithm::Exact => "Exact",
+            Algorithm::Approx => "Approximation",
+            Algorithm::Compare => "Comparison",
+        }
+    )?;
+    writeln!(file, "k (required mappings): {}", k)?;
+    if let Some(threads) = threads {
+        writeln!(
+            file,
+            "Threads: {}",
+            if threads == 0 {
+                "default (global rayon pool)".to_string()
+            } else {
+                format!("{} (scoped pool)", threads)
+            }
+        )?;
+    }
+    writeln!(file, "Time: {}ms", elapsed.as_millis())?;
+    if let Some(bytes) = estimated_memory_bytes {
+        writeln!(
+            file,
+            "Estimated mapping-pool memory: {:.1} MB",
+            bytes as f64 / 1_000_000.0
+        )?;
+    }
+    writeln!(file, "Total Cost (edges added): {}", cost)?;
+    if let Some(lower_bound) = approximation_lower_bound {
+        writeln!(
+            file,
+            "{} (lower bound on the true optimal cost, not the true optimum itself; \
+             see cost::approximation_lower_bound for its assumptions)",
+            format_approximation_gap(cost, lower_bound)
+        )?;
+    }
+    if vertices_added > 0 {
+        writeln!(
+            file,
+            "Synthetic vertices added to H (indices {}..{}): {} (cost {} each)",
+            h.num_vertices() - vertices_added,
+            h.num_vertices(),
+            vertices_added,
+            vertex_cost
+        )?;
+    }
+    if mappings.len() > 1 {
+        let stats = sharing_stats(g, h, mappings);
+        writeln!(
+            file,
+            "Edge sharing: {} edges shared across mappings ({} summed individually vs {} merged, {:.2}x savings)",
+            stats.shared_edge_count,
+            stats.sum_of_individual_costs,
+            stats.merged_total_cost,
+            stats.savings_ratio()
+        )?;
+    }
+    writeln!(file)?;
+
+    // Graph info
+    writeln!(
+        file,
+        "------------------------------------------------------------"
+    )?;
+    writeln!(file, "Graph G (pattern): {} vertices", g.num_vertices())?;
+    writeln!(
+        file,
+        "------------------------------------------------------------"
+    )?;
+    writeln!(file, "Adjacency Matrix:")?;
+    for i in 0..g.num_vertices() {
+        let row: Vec<String> = (0..g.num_vertices())
+            .map(|j| format!("{:3}", g.get_edge(i, j)))
+            .collect();
+        writeln!(file, "  {}: [{}]", i, row.join(", "))?;
+    }
+    writeln!(file)?;
+
+    // Graph H
+    writeln!(
+        file,
+        "------------------------------------------------------------"
+    )?;
+    writeln!(file, "Graph H (host): {} vertices", h.num_vertices())?;
+    writeln!(
+        file,
+        "------------------------------------------------------------"
+    )?;
+    writeln!(file, "Adjacency Matrix:")?;
+    for i in 0..h.num_vertices() {
+        let row: Vec<String> = (0..h.num_vertices())
+            .map(|j| format!("{:3}", h.get_edge(i, j)))
+            .collect();
+        writeln!(file, "  {}: [{}]", i, row.join(", "))?;
+    }
+    writeln!(file)?;
+
+    // Extended H matrix
+    let extended = apply_edge_map(h, edge_map);
+    let extension_delta = compute_edge_delta(h, &extended);
+    writeln!(file, "Extended H Matrix (original + added):")?;
+    for i in 0..h.num_vertices() {
+        let row: Vec<String> = (0..h.num_vertices())
+            .map(|j| {
+                let original = h.get_edge(i, j);
+                let added = extension_delta.get(&(i, j)).copied().unwrap_or(0);
+                if added > 0 {
+                    format!("{:3}+{}", original, added)
+                } else {
+                    format!("{:5}", original)
+                }
+            })
+            .collect();
+        writeln!(file, "  {}: [{}]", i, row.join(", "))?;
+    }
+    writeln!(file)?;
+
+    // Coverage: for each g edge, how many mappings already satisfy it in h.
+    if mappings.len() > 1 {
+        writeln!(
+            file,
+            "------------------------------------------------------------"
+        )?;
+        writeln!(file, "Coverage (G edges vs. satisfying mappings)")?;
+        writeln!(
+            file,
+            "------------------------------------------------------------"
+        )?;
+        let coverage = coverage_analysis(g, h, mappings);
+        let mut edges: Vec<_> = coverage.keys().copied().collect();
+        edges.sort();
+        for (u, v) in edges {
+            let count = coverage[&(u, v)];
+            let status = if count == mappings.len() {
+                "covered by all mappings"
+            } else if count == 0 {
+                "NEEDS EXTENSION in every mapping"
+            } else {
+                "partially covered"
+            };
+            writeln!(
+                file,
+                "  G[{}][{}] (weight {}): {}/{} mappings already satisfy it -- {}",
+                u,
+                v,
+                g.get_edge(u, v),
+                count,
+                mappings.len(),
+                status
+            )?;
+        }
+        writeln!(file)?;
+    }
+
+    // Mappings
+    writeln!(
+        file,
+        "------------------------------------------------------------"
+    )?;
+    writeln!(file, "Mappings (Permutation Matrix Format)")?;
+    writeln!(
+        file,
+        "------------------------------------------------------------"
+    )?;
+    let cost_breakdown = breakdown(g, h, mappings);
+
+    for (idx, mapping) in mappings.iter().enumerate() {
+        writeln!(file, "\nMapping {} of {}:", idx + 1, mappings.len())?;
+        writeln!(
+            file,
+            "Permutation Matrix - G vertices (rows) to H vertices (columns)"
+        )?;
+        writeln!(file)?;
+
+        // Header with H vertex numbers
+        write!(file, "  H vertices: ")?;
+        for h in 0..h.num_vertices() {
+            write!(file, "{:2} ", h)?;
+        }
+        writeln!(file)?;
+
+        // Top border
+        write!(file, "              ┌")?;
+        for _ in 0..h.num_vertices() {
+            write!(file, "───")?;
+        }
+        writeln!(file)?;
+
+        // Matrix rows
+        for (g_vertex, &h_vertex) in mapping.iter().enumerate() {
+            write!(file, "  G[{:2}] → {:2}  │", g_vertex, h_vertex)?;
+            for h in 0..h.num_vertices() {
+                if h == h_vertex {
+                    write!(file, "◉ ")?; // Filled circle for mapping
+                } else {
+                    write!(file, "· ")?; // Middle dot for empty
+                }
+            }
+            writeln!(file)?;
+        }
+
+        // Bottom border
+        write!(file, "              └")?;
+        for _ in 0..h.num_vertices() {
+            write!(file, "───")?;
+        }
+        writeln!(file)?;
+
+        writeln!(file)?;
+        writeln!(file, "  ◉ = mapped    · = not mapped")?;
+
+        // Also include simple list for reference
+        writeln!(file)?;
+        writeln!(file, "  Mapping list: G[vertex] → H[vertex]")?;
+        for (g_vertex, &h_vertex) in mapping.iter().enumerate() {
+            write!(file, "    G[{}]→H[{}]", g_vertex, h_vertex)?;
+            if (g_vertex + 1) % 8 == 0 || g_vertex == mapping.len() - 1 {
+                writeln!(file)?;
+            } else {
+                write!(file, "  ")?;
+            }
+        }
+        writeln!(file)?;
+
+        // Cost breakdown: which edges this mapping needs, already has, adds,
+        // and shares with other mappings in the set.
+        let detail = &cost_breakdown[idx];
+        writeln!(file, "\n  Cost breakdown:")?;
+        writeln!(
+            file,
+            "    Required edges: {}, already in H: {}",
+            detail.required.len(),
+            detail.already_present.len()
+        )?;
+        if detail.newly_added.is_empty() {
+            writeln!(file, "    Newly added: (none)")?;
+        } else {
+            writeln!(file, "    Newly added:")?;
+            let mut added: Vec<_> = detail.newly_added.iter().collect();
+            added.sort();
+            for (&(x, y), &weight) in added {
+                let shared = if detail.shared_with_others.contains_key(&(x, y)) {
+                    " (shared with another mapping)"
+                } else {
+                    ""
+                };
+                writeln!(file, "      H[{}][{}] += {}{}", x, y, weight, shared)?;
+            }
+        }
+    }
+
+    writeln!(
+        file,
+        "\n============================================================"
+    )?;
+    writeln!(file, "End of Report")?;
+    writeln!(
+        file,
+        "============================================================"
+    )?;
+
+    Ok(())
+}
+
+/// Write G's edges, H's edges, and the extension `edge_map` to `path` as
+/// three TSV sections (see [`write_edge_list`]), each preceded by a `#`
+/// comment header naming it -- a sparse alternative to `write_results_to_file`'s
+/// dense adjacency matrices for tools that prefer a 3-column TSV.
+fn write_edge_list_report(
+    path: &PathBuf,
+    g: &Graph,
+    h: &Graph,
+    edge_map: &EdgeMap,
+) -> io::Result<()> {
+    let mut file = File::create(path)?;
+    writeln!(file, "# G edges (u\tv\tweight)")?;
+    write_edge_list(
+        &mut file,
+        &compute_edge_delta(&Graph::new(g.num_vertices()), g),
+    )?;
+    writeln!(file, "# H edges (u\tv\tweight)")?;
+    write_edge_list(
+        &mut file,
+        &compute_edge_delta(&Graph::new(h.num_vertices()), h),
+    )?;
+    writeln!(file, "# Added edges (u\tv\tweight)")?;
+    write_edge_list(&mut file, edge_map)?;
+    Ok(())
+}
+
+/// Write `formats::to_networkx_solution_string`'s script for `g`, `h`, and
+/// `h` extended by `edge_map` to `path`.
+fn write_networkx_report(
+    path: &PathBuf,
+    g: &Graph,
+    h: &Graph,
+    mappings: &[Mapping],
+    edge_map: &EdgeMap,
+) -> io::Result<()> {
+    let h_extended = apply_edge_map(h, edge_map);
+    let script = to_networkx_solution_string(g, h, &h_extended, mappings);
+    std::fs::write(path, script)
+}
+
+/// Write a `--algorithm compare` report: the exact algorithm's cost and the
+/// approx algorithm's alongside it, with the approximation ratio between
+/// them. Lighter than `write_results_to_file`'s full adjacency-matrix dump --
+/// the comparison table is the point here, not a second copy of the graphs.
+///
+/// Still carries a "Total Cost (edges added): N" line (the exact cost) so
+/// `--resume-from` can pick a comparison report up the same as any other
+/// solution file.
+fn write_comparison_file(
+    path: &PathBuf,
+    g: &Graph,
+    h: &Graph,
+    k: usize,
+    compare: &CompareResult,
+) -> io::Result<()> {
+    let mut file = File::create(path)?;
+    writeln!(
+        file,
+        "============================================================"
+    )?;
+    writeln!(
+        file,
+        "Minimal k-Isomorphic Subgraph Extension - Comparison Report"
+    )?;
+    writeln!(
+        file,
+        "============================================================"
+    )?;
+    writeln!(file)?;
+    writeln!(file, "Graph G (pattern): {} vertices", g.num_vertices())?;
+    writeln!(file, "Graph H (host): {} vertices", h.num_vertices())?;
+    writeln!(file, "k (required mappings): {}", k)?;
+    writeln!(file)?;
+    writeln!(
+        file,
+        "{:<12} {:>10} {:>12}",
+        "Algorithm", "Cost", "Time (ms)"
+    )?;
+    writeln!(file, "{}", "-".repeat(36))?;
+    writeln!(
+        file,
+        "{:<12} {:>10} {:>12}",
+        if compare.exact_timed_out {
+            "Exact*"
+        } else {
+            "Exact"
+        },
+        compare.exact_cost,
+        compare.exact_elapsed.as_millis()
+    )?;
+    writeln!(
+        file,
+        "{:<12} {:>10} {:>12}",
+        "Approx",
+        compare.approx_cost,
+        compare.approx_elapsed.as_millis()
+    )?;
+    writeln!(file)?;
+    if compare.exact_timed_out {
+        writeln!(
+            file,
+            "* Exact search hit --timeout before proving optimality; ratio is only an upper bound."
+        )?;
+    }
+    if compare.ratio >= usize::MAX as f64 {
+        writeln!(
+            file,
+            "Approximation ratio (approx/optimal): infinite (optimal cost is 0, approx added {})",
+            compare.approx_cost
+        )?;
+    } else {
+        writeln!(
+            file,
+            "Approximation ratio (approx/optimal): {:.4}x",
+            compare.ratio
+        )?;
+    }
+    writeln!(file)?;
+    writeln!(file, "Total Cost (edges added): {}", compare.exact_cost)?;
+    writeln!(file)?;
+    writeln!(
+        file,
+        "============================================================"
+    )?;
+    writeln!(file, "End of Report")?;
+    writeln!(
+        file,
+        "============================================================"
+    )?;
+
+    Ok(())
+}
+
+/// Extract the best cost recorded in a checkpoint or completed solution file
+/// written by `write_results_to_file`, by scanning for its
+/// "Total Cost (edges added): N" line. Returns `None` if the file can't be
+/// read or doesn't contain that line.
+fn read_resume_cost(path: &PathBuf) -> Option<usize> {
+    let contents = std::fs::read_to_string(path).ok()?;
+    contents
+        .lines()
+        .find_map(|line| line.strip_prefix("Total Cost (edges added): "))
+        .and_then(|rest| rest.trim().parse().ok())
+}
+
+/// One row of the `--batch` summary table: the outcome of solving a single
+/// matched file.
+struct BatchResult {
+    file_name: String,
+    cost: Option<usize>,
+    elapsed: Duration,
+    optimal: bool,
+    error: Option<String>,
+}
+
+/// Solve one `--batch` instance headlessly: parse, run the requested
+/// algorithm synchronously on the calling thread (the TUI's progress
+/// channel is only drained for its final `Complete`/`Error` message, never
+/// rendered), and write its report via `write_results_to_file` under
+/// `output_dir`. Every instance applies the same flags from `args` that a
+/// single non-batch run would, aside from `--input` and `--output-file`,
+/// which `--batch` and `--batch-output-dir` replace respectively.
+fn run_batch_instance(path: &Path, args: &Args, output_dir: &Path) -> Result<BatchResult, String> {
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string());
+
+    let (g, h) = args.format.parse(&path.to_path_buf())?;
+    run_batch_pair(file_name, g, h, args, output_dir)
+}
+
+/// The parsed-graphs half of `run_batch_instance`, shared with
+/// `run_batch_file` (whose pairs come from one combined file instead of one
+/// path per instance, so there's no per-instance file to hand `--format`).
+fn run_batch_pair(
+    file_name: String,
+    g: Graph,
+    h: Graph,
+    args: &Args,
+    output_dir: &Path,
+) -> Result<BatchResult, String> {
+    let (g, h) = if args.undirected {
+        (g.as_undirected(), h.as_undirected())
+    } else {
+        (g, h)
+    };
+    let (h, vertices_added) = if args.allow_vertex_additions {
+        pad_host_to_pattern_size(&g, &h)
+    } else {
+        (h, 0)
+    };
+    let vertex_cost = args.vertex_cost;
+    let objective: Objective = args.objective.into();
+    // Each instance draws its own seed from entropy when `--seed` isn't
+    // given, rather than sharing one across the whole batch, so instances
+    // run in parallel don't all reproduce the exact same randomized search.
+    let seed = args.seed.unwrap_or_else(|| rand::thread_rng().gen());
+    let refine = refine_schedule_from_args(args);
+    let beam_width = match args.construction {
+        ConstructionArg::Greedy => 1,
+        ConstructionArg::Beam => args.beam_width,
+    };
+    let seed_strategy: SeedStrategy = args.seed_strategy.into();
+
+    let (tx, rx) = channel();
+    match args.algorithm {
+        Algorithm::Exact => run_exact_algorithm(
+            g.clone(),
+            h.clone(),
+            args.k,
+            objective,
+            refine,
+            beam_width,
+            seed_strategy,
+            args.deterministic_ties,
+            args.undirected,
+            None,
+            None,
+            // `--incumbent-file` is a single-instance TUI feature (see its
+            // doc comment); `--batch` already writes one solution file per
+            // instance, so there's nothing to stream it to here.
+            None,
+            vertices_added,
+            vertex_cost,
+            args.memory_limit,
+            args.restarts,
+            args.time_limit,
+            args.trials_multiplier,
+            seed,
+            args.timeout,
+            // `--batch` runs unattended, so there's no one to offer an
+            // approx-switch keypress to -- always proceed as if `--force`
+            // were given, same as compare mode.
+            usize::MAX,
+            true,
+            tx,
+        ),
+        Algorithm::Approx => run_approx_algorithm(
+            g.clone(),
+            h.clone(),
+            args.k,
+            args.restarts,
+            args.time_limit,
+            args.trials_multiplier,
+            seed,
+            objective,
+            refine,
+            beam_width,
+            seed_strategy,
+            args.deterministic_ties,
+            vertices_added,
+            vertex_cost,
+            None,
+            tx,
+        ),
+        Algorithm::Compare => run_compare_algorithm(
+            g.clone(),
+            h.clone(),
+            args.k,
+            objective,
+            refine,
+            beam_width,
+            seed_strategy,
+            args.deterministic_ties,
+            args.undirected,
+            vertices_added,
+            vertex_cost,
+            args.memory_limit,
+            args.restarts,
+            args.time_limit,
+            args.trials_multiplier,
+            seed,
+            args.timeout,
+            tx,
+        ),
+    }
+
+    let messages: Vec<ProgressMessage> = rx.try_iter().collect();
+    if let Some(error) = messages.iter().find_map(|msg| match msg {
+        ProgressMessage::Error(e) => Some(e.clone()),
+        _ => None,
+    }) {
+        return Err(error);
+    }
+
+    if let Some(compare) = messages.iter().find_map(|msg| match msg {
+        ProgressMessage::CompareComplete(compare) => Some(compare.as_ref().clone()),
+        _ => None,
+    }) {
+        let output_path = output_dir.join(format!("solution_{}.txt", file_name));
+        write_comparison_file(&output_path, &g, &h, args.k, &compare)
+            .map_err(|e| format!("failed to write {}: {}", output_path.display(), e))?;
+
+        if args.output_edge_list.is_some() {
+            let edge_list_path = output_dir.join(format!("edges_{}.tsv", file_name));
+            write_edge_list_report(&edge_list_path, &g, &h, &compare.exact_edge_map)
+                .map_err(|e| format!("failed to write {}: {}", edge_list_path.display(), e))?;
+        }
+        if args.output_networkx.is_some() {
+            let networkx_path = output_dir.join(format!("graph_{}.py", file_name));
+            write_networkx_report(
+                &networkx_path,
+                &g,
+                &h,
+                &compare.exact_mappings,
+                &compare.exact_edge_map,
+            )
+            .map_err(|e| format!("failed to write {}: {}", networkx_path.display(), e))?;
+        }
+
+        return Ok(BatchResult {
+            file_name,
+            cost: Some(compare.exact_cost),
+            elapsed: compare.exact_elapsed + compare.approx_elapsed,
+            optimal: !compare.exact_timed_out,
+            error: None,
+        });
+    }
+
+    let (algorithm, cost, edge_map, mappings, elapsed, timed_out) = messages
+        .into_iter()
+        .find_map(|msg| match msg {
+            ProgressMessage::Complete {
+                algorithm,
+                cost,
+                edge_map,
+                mappings,
+                elapsed,
+                timed_out,
+                ..
+            } => Some((algorithm, cost, edge_map, mappings, elapsed, timed_out)),
+            _ => None,
+        })
+        .ok_or_else(|| "no completion message received".to_string())?;
+
+    let approx_lower_bound = if algorithm == Algorithm::Approx {
+        Some(approximation_lower_bound(&g, &h, args.k, MergeSemantics::Shared))
+    } else {
+        None
+    };
+
+    let output_path = output_dir.join(format!("solution_{}.txt", file_name));
+    write_results_to_file(
+        &output_path,
+        &g,
+        &h,
+        args.k,
+        algorithm,
+        cost,
+        vertices_added,
+        vertex_cost,
+        &edge_map,
+        &mappings,
+        elapsed,
+        false,
+        None,
+        timed_out,
+        Some(args.threads),
+        approx_lower_bound,
+    )
+    .map_err(|e| format!("failed to write {}: {}", output_path.display(), e))?;
+
+    if args.output_edge_list.is_some() {
+        let edge_list_path = output_dir.join(format!("edges_{}.tsv", file_name));
+        write_edge_list_report(&edge_list_path, &g, &h, &edge_map)
+            .map_err(|e| format!("failed to write {}: {}", edge_list_path.display(), e))?;
+    }
+    if args.output_networkx.is_some() {
+        let networkx_path = output_dir.join(format!("graph_{}.py", file_name));
+        write_networkx_report(&networkx_path, &g, &h, &mappings, &edge_map)
+            .map_err(|e| format!("failed to write {}: {}", networkx_path.display(), e))?;
+    }
+
+    Ok(BatchResult {
+        file_name,
+        cost: Some(cost),
+        elapsed,
+        optimal: algorithm == Algorithm::Exact && !timed_out,
+        error: None,
+    })
+}
+
+/// Run `run_all` (one `BatchResult` per instance, in whatever order rayon's
+/// `par_iter` finishes them) under a scoped thread pool sized by
+/// `--threads` (see `run_batch_instance`'s doc comment for why that cap
+/// applies to nested fan-out too), then print the summary table
+/// `run_batch`/`run_batch_file` share. `output_dir` is created if it
+/// doesn't already exist.
+fn run_batch_results(
+    output_dir: &Path,
+    args: &Args,
+    run_all: impl Fn() -> Vec<BatchResult> + Sync + Send,
+) -> Result<(), Box<dyn std::error::Error>> {
+    std::fs::create_dir_all(output_dir)?;
+
+    let results: Vec<BatchResult> = if args.threads > 0 {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(args.threads)
+            .build()?
+            .install(run_all)
+    } else {
+        run_all()
+    };
+
+    println!();
+    println!(
+        "{:<30} {:>10} {:>10} {:>8}",
+        "Instance", "Cost", "Time (ms)", "Optimal"
+    );
+    println!("{}", "-".repeat(62));
+    for result in &results {
+        match (&result.cost, &result.error) {
+            (Some(cost), _) => println!(
+                "{:<30} {:>10} {:>10} {:>8}",
+                result.file_name,
+                cost,
+                result.elapsed.as_millis(),
+                if result.optimal { "yes" } else { "no" }
+            ),
+            (None, Some(error)) => {
+                println!(
+                    "{:<30} {:>10} {:>10} {:>8}",
+                    result.file_name, "ERROR", "-", "-"
+                );
+                println!("  -> {}", error);
+            }
+            (None, None) => unreachable!("a failed instance always carries its error"),
+        }
+    }
+
+    Ok(())
+}
+
+/// Solve every file matching `pattern` in parallel (see `rayon::par_iter`),
+/// writing each instance's report into `output_dir`, then print a summary
+/// table once all of them finish. A failure on one instance is logged to
+/// stderr and recorded as an error row rather than aborting the rest of the
+/// batch.
+fn run_batch(
+    pattern: &str,
+    output_dir: &Path,
+    args: &Args,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut paths: Vec<PathBuf> = glob::glob(pattern)?.filter_map(Result::ok).collect();
+    paths.sort();
+
+    if paths.is_empty() {
+        eprintln!("No files matched --batch pattern '{}'", pattern);
+        return Ok(());
+    }
+
+    run_batch_results(output_dir, args, || {
+        paths
+            .par_iter()
+            .map(|path| {
+                run_batch_instance(path, args, output_dir).unwrap_or_else(|error| {
+                    let file_name = path
+                        .file_name()
+                        .map(|n| n.to_string_lossy().into_owned())
+                        .unwrap_or_else(|| path.display().to_string());
+                    eprintln!("{}: {}", file_name, error);
+                    BatchResult {
+                        file_name,
+                        cost: None,
+                        elapsed: Duration::default(),
+                        optimal: false,
+                        error: Some(error),
+                    }
+                })
+            })
+            .collect()
+    })
+}
+
+/// Solve every graph pair in `path` (a single combined instances file, see
+/// `parser::parse_all_graph_pairs_file`) in parallel, writing each pair's
+/// report into `output_dir` and naming each pair `pair_{n}` (1-based) in the
+/// summary table, since a combined file has no per-instance filename to
+/// report instead. Otherwise identical to `run_batch`.
+fn run_batch_file(
+    path: &PathBuf,
+    output_dir: &Path,
+    args: &Args,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let pairs = parse_all_graph_pairs_file(path)
+        .map_err(|e| format!("failed to parse --batch-file '{}': {}", path.display(), e))?;
+
+    if pairs.is_empty() {
+        eprintln!("--batch-file '{}' contains no graph pairs", path.display());
+        return Ok(());
+    }
+
+    run_batch_results(output_dir, args, || {
+        pairs
+            .par_iter()
+            .enumerate()
+            .map(|(i, (g, h))| {
+                let file_name = format!("pair_{}", i + 1);
+                run_batch_pair(file_name.clone(), g.clone(), h.clone(), args, output_dir)
+                    .unwrap_or_else(|error| {
+                        eprintln!("{}: {}", file_name, error);
+                        BatchResult {
+                            file_name,
+                            cost: None,
+                            elapsed: Duration::default(),
+                            optimal: false,
+                            error: Some(error),
+                        }
+                    })
+            })
+            .collect()
+    })
+}
+
+/// Print `--stats-only`'s report: `GraphStats::compute` on each of `g` and
+/// `h`, plus an estimate of how many injective mappings `g` has into `h` and
+/// how many `k`-combinations of those there are. The mapping estimate
+/// ignores edge compatibility (it's `permutation_count`, not
+/// `count_satisfying_mappings`), so it's always O(1) to compute and never
+/// blows up for a large or unsatisfiable instance, unlike the exact count
+/// `--dry-run` reports.
+fn print_stats_report(g: &Graph, h: &Graph, k: usize) {
+    println!("--- Graph statistics ---");
+    for (name, graph) in [("G", g), ("H", h)] {
+        let stats = GraphStats::compute(graph);
+        println!();
+        println!("{}:", name);
+        println!("  Vertices: {}", stats.num_vertices);
+        println!("  Edges (counting multiplicity): {}", stats.num_edges);
+        println!("  Density: {:.4}", stats.density);
+        println!("  Degree histogram (weighted in + out degree -> count):");
+        for (degree, count) in &stats.degree_histogram {
+            println!("    {:>6} -> {}", degree, count);
+        }
+        println!(
+            "  Strongly connected component sizes: {:?}",
+            stats.scc_sizes
+        );
+        match stats.diameter {
+            Some(diameter) => println!("  Diameter: {}", diameter),
+            None => println!(
+                "  Diameter: not computed (disconnected, or more than {} vertices)",
+                MAX_VERTICES_FOR_DIAMETER
+            ),
+        }
+    }
+
+    let estimated_mappings = permutation_count(h.num_vertices(), g.num_vertices());
+    println!();
+    println!(
+        "Estimated injective mappings of G into H (ignoring edge compatibility): {}",
+        estimated_mappings
+    );
+    let estimated_mappings = estimated_mappings.min(usize::MAX as u128) as usize;
+    println!(
+        "Estimated candidate combinations (n choose k) for k={}: {}",
+        k,
+        num_combinations(estimated_mappings, k)
+    );
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = Args::parse();
+
+    if let Some(ref path) = args.batch_file {
+        return run_batch_file(path, &args.batch_output_dir, &args);
+    }
+    if let Some(ref pattern) = args.batch {
+        return run_batch(pattern, &args.batch_output_dir, &args);
+    }
+
+    // Parse input graphs, either from `--input` or (if that's omitted, or
+    // `--stdin` forces it) from stdin directly -- always before the TUI
+    // starts, since the TUI also reads keystrokes from stdin.
+    let (g, h) = match (&args.input, args.stdin) {
+        (Some(path), false) => match args.format.parse(path) {
+            Ok(graphs) => graphs,
+            Err(e) => {
+                eprintln!("Error parsing input file: {}", e);
+                std::process::exit(1);
+            }
+        },
+        _ => match args.format.parse_stdin() {
+            Ok(graphs) => graphs,
+            Err(e) => {
+                eprintln!("Error parsing stdin input: {}", e);
+                std::process::exit(1);
+            }
+        },
+    };
+    let (g, h) = if args.undirected {
+        (g.as_undirected(), h.as_undirected())
+    } else {
+        (g, h)
+    };
+
+    let (h, vertices_added) = if args.allow_vertex_additions {
+        pad_host_to_pattern_size(&g, &h)
+    } else {
+        (h, 0)
+    };
+    let vertex_cost = args.vertex_cost;
+
+    if args.stats_only {
+        print_stats_report(&g, &h, args.k);
+        return Ok(());
+    }
+
+    // Validate k
+    if args.k == 0 {
+        eprintln!("Error: k must be at least 1");
+        std::process::exit(1);
+    }
+
+    let refine = refine_schedule_from_args(&args);
+    let beam_width = match args.construction {
+        ConstructionArg::Greedy => 1,
+        ConstructionArg::Beam => args.beam_width,
+    };
+    let seed_strategy: SeedStrategy = args.seed_strategy.into();
+
+    // Determine if we should save to file
+    // Save to file if: either graph has >15 vertices OR --output-file was specified
+    let output_file =
+        if g.num_vertices() > 15 || h.num_vertices() > 15 || args.output_file.is_some() {
+            Some(args.output_file.unwrap_or_else(|| {
+                let algo_name = match args.algorithm {
+                    Algorithm::Exact => "exact",
+                    Algorithm::Approx => "approx",
+                    Algorithm::Compare => "compare",
+                };
+                PathBuf::from(format!("solution_{}.txt", algo_name))
+            }))
+        } else {
+            None
+        };
+
+    let resume_best_cost = args.resume_from.as_ref().and_then(|path| {
+        let cost = read_resume_cost(path);
+        if cost.is_none() {
+            eprintln!(
+                "Warning: could not read a resumable cost from {}; starting fresh",
+                path.display()
+            );
+        }
+        cost
+    });
+
+    if args.dry_run {
+        let objective: Objective = args.objective.into();
+        println!("--- Dry run: problem size statistics ---");
+
+        // `count_satisfying_mappings`'s weighted-degree pruning assumes a
+        // mapping's edges already exist in H (see its doc comment); synthetic
+        // vertices added by `--allow-vertex-additions` start with no edges of
+        // their own by construction, so the estimate would always come back 0
+        // for them. Skip it in that case, same as `exact_solver`.
+        if args.allow_vertex_additions {
+            println!(
+                "Satisfying mapping count: not estimated (--allow-vertex-additions adds \
+                 synthetic vertices with no edges of their own, which the estimator would \
+                 always see as unsatisfying)"
+            );
+        } else {
+            let satisfying_count = count_satisfying_mappings(&g, &h);
+            println!("Estimated satisfying mappings: {}", satisfying_count);
+            println!(
+                "Candidate combinations (n choose k): {}",
+                BinomialTable::new(satisfying_count).get(satisfying_count, args.k)
+            );
+        }
+
+        let all_mappings = find_all_mappings(&g, &h);
+        let estimated_bytes = estimate_memory_bytes(all_mappings.len(), g.num_vertices());
+        println!(
+            "Exact algorithm's full candidate pool: {} mappings (~{} MB)",
+            all_mappings.len(),
+            estimated_bytes / 1_000_000
+        );
+        println!(
+            "Lower bound on optimal cost: {}",
+            cost_lower_bound(&g, &h) + vertices_added * vertex_cost
+        );
+        if let Some(limit) = args.memory_limit {
+            println!(
+                "--memory-limit is {} MB, so the exact algorithm would {}",
+                limit,
+                if estimated_bytes / 1_000_000 > limit {
+                    "fall back to approx"
+                } else {
+                    "run directly"
+                }
+            );
+        }
+
+        let mut dry_run_rng = StdRng::seed_from_u64(rand::thread_rng().gen());
+        match run_approx_once(
+            &g,
+            &h,
+            args.k,
+            args.trials_multiplier,
+            &mut dry_run_rng,
+            &objective,
+            refine,
+            beam_width,
+            seed_strategy,
+            args.deterministic_ties,
+            None,
+            None,
+        ) {
+            Some((cost, _, _)) => {
+                println!("One-trial approx cost upper bound: {}", cost);
+            }
+            None => {
+                println!("One-trial approx run could not find {} mappings", args.k);
+            }
+        }
+
+        print!("Proceed with the full computation? [y/N] ");
+        io::stdout().flush()?;
+        let mut answer = String::new();
+        io::stdin().read_line(&mut answer)?;
+        if !matches!(answer.trim(), "y" | "Y" | "yes" | "Yes") {
+            println!("Aborted.");
+            return Ok(());
+        }
+    }
+
+    // Always use interactive TUI
+    // Create channel for progress updates
+    let (tx, rx) = channel();
+
+    // Spawn algorithm thread
+    let g_clone = g.clone();
+    let h_clone = h.clone();
+    let k = args.k;
+    let algorithm = args.algorithm;
+    let restarts = args.restarts;
+    let time_limit = args.time_limit;
+    let trials_multiplier = args.trials_multiplier;
+    let deterministic_ties = args.deterministic_ties;
+    let seed = args.seed.unwrap_or_else(|| rand::thread_rng().gen());
+    println!(
+        "RNG seed: {} (pass --seed {} to reproduce this run)",
+        seed, seed
+    );
+    let objective: Objective = args.objective.into();
+    let undirected = args.undirected;
+    let checkpoint = output_file
+        .clone()
+        .zip(args.checkpoint_interval)
+        .map(|(path, secs)| (path, Duration::from_secs(secs)));
+
+    let memory_limit = args.memory_limit;
+    let timeout = args.timeout;
+    let incumbent_file = args.incumbent_file.clone();
+    let max_combinations = args.max_combinations;
+    let force = args.force;
+    // Kept on the app side so the Calculating view's "switch to approx"
+    // keypress (see `handle_key`'s `CombinationGuardTriggered` handling) can
+    // spawn a fresh `run_approx_algorithm` thread without needing access to
+    // `args` again.
+    let app_objective = objective.clone();
+    let app_refine = refine;
+    let app_beam_width = beam_width;
+    let app_seed_strategy = seed_strategy;
+    let app_deterministic_ties = args.deterministic_ties;
+    let app_tx = tx.clone();
+    let threads = args.threads;
+    thread::spawn(move || {
+        let run = move || match algorithm {
+            Algorithm::Exact => run_exact_algorithm(
+                g_clone,
+                h_clone,
+                k,
+                objective,
+                refine,
+                beam_width,
+                seed_strategy,
+                deterministic_ties,
+                undirected,
+                resume_best_cost,
+                checkpoint,
+                incumbent_file,
+                vertices_added,
+                vertex_cost,
+                memory_limit,
+                restarts,
+                time_limit,
+                trials_multiplier,
+                seed,
+                timeout,
+                max_combinations,
+                force,
+                tx,
+            ),
+            Algorithm::Approx => run_approx_algorithm(
+                g_clone,
+                h_clone,
+                k,
+                restarts,
+                time_limit,
+                trials_multiplier,
+                seed,
+                objective,
+                refine,
+                beam_width,
+                seed_strategy,
+                deterministic_ties,
+                vertices_added,
+                vertex_cost,
+                None,
+                tx,
+            ),
+            // Checkpoint/resume are exact-search-only concepts (see their own
+            // doc comments); compare mode always runs both algorithms fresh.
+            Algorithm::Compare => run_compare_algorithm(
+                g_clone,
+                h_clone,
+                k,
+                objective,
+                refine,
+                beam_width,
+                seed_strategy,
+                deterministic_ties,
+                undirected,
+                vertices_added,
+                vertex_cost,
+                memory_limit,
+                restarts,
+                time_limit,
+                trials_multiplier,
+                seed,
+                timeout,
+                tx,
+            ),
+        };
+
+        // `--threads` builds a scoped pool here, around the whole algorithm
+        // thread, rather than threading it into every search function: any
+        // rayon parallel call made synchronously on this thread (including
+        // deep inside `run_exact_algorithm`'s `into_par_iter` fan-out) picks
+        // up whichever pool is installed, without needing to know about it.
+        if threads > 0 {
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .expect("requested thread count builds a valid pool")
+                .install(run);
+        } else {
+            run();
+        }
+    });
+
+    // Setup terminal
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    let backend = CrosstermBackend::new(stdout);
+    let mut terminal = Terminal::new(backend)?;
+
+    // Create app state
+    let mut app = AppState::new(
+        args.algorithm,
+        g,
+        h,
+        args.k,
+        vertices_added,
+        vertex_cost,
+        output_file,
+        args.output_edge_list.clone(),
+        args.output_networkx.clone(),
+        rx,
+        app_objective,
+        app_refine,
+        app_beam_width,
+        app_seed_strategy,
+        app_deterministic_ties,
+        args.restarts,
+        args.time_limit,
+        args.trials_multiplier,
+        seed,
+        app_tx,
+        args.threads,
+    );
+
+    // Main loop
+    let tick_rate = Duration::from_millis(100);
+    let mut last_tick = Instant::now();
+
+    loop {
+        terminal.draw(|f| ui(f, &app))?;
+
+        let timeout = tick_rate
+            .checked_sub(last_tick.elapsed())
+            .unwrap_or_else(|| Duration::from_secs(0));
+
+        if event::poll(timeout)? {
+            match event::read()? {
+                Event::Key(key) => {
+                    // Only handle key press events, not release (fixes Windows double-trigger)
+                    if key.kind != KeyEventKind::Press {
+                        continue;
+                    }
+                    match key.code {
+                        KeyCode::Char('q') | KeyCode::Char('Q')
+                            if !matches!(app.current_view, View::Help(_)) =>
+                        {
+                            if app.current_view != View::Calculating {
+                                break;
+                            }
+                        }
+                        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                            break;
+                        }
+                        _ => app.handle_key(key.code),
+                    }
+                }
+                Event::Mouse(mouse) => {
+                    let size = terminal.size()?;
+                    app.handle_mouse(mouse, Rect::new(0, 0, size.width, size.height))
+                }
+                _ => {}
+            }
+        }
+
+        if last_tick.elapsed() >= tick_rate {
+            app.update()?;
+            last_tick = Instant::now();
+        }
+    }
+
+    // Restore terminal
+    disable_raw_mode()?;
+    execute!(
+        terminal.backend_mut(),
+        LeaveAlternateScreen,
+        DisableMouseCapture
+    )?;
+    terminal.show_cursor()?;
+
+    if let Some((nodes_visited, total_combinations)) = app.search_stats {
+        println!(
+            "Branch-and-bound search: {} nodes visited (of {} possible combinations, {:.1}%)",
+            nodes_visited,
+            total_combinations,
+            100.0 * nodes_visited as f64 / total_combinations.max(1) as f64
+        );
+    }
+    if app.timed_out {
+        println!("TIME LIMIT REACHED -- best found, optimality not proven");
+    }
+
+    Ok(())
+}
+
+// `read_resume_cost` is private to this binary crate, so unlike the
+// library's tests (centralized in `minimal-k-iso-lib/src/lib.rs`) this one
+// has to live here instead.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_resume_cost_finds_the_total_cost_line() {
+        let contents = "Algorithm: Exact\nTotal Cost (edges added): 42\nTime: 10ms\n";
+        let dir = std::env::temp_dir();
+        let path = dir.join("solver_test_read_resume_cost.txt");
+        std::fs::write(&path, contents).unwrap();
+
+        assert_eq!(read_resume_cost(&path), Some(42));
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_read_resume_cost_missing_file_is_none() {
+        assert_eq!(
+            read_resume_cost(&PathBuf::from("/nonexistent/path/to/nowhere.txt")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_memory_limit_falls_back_to_approx() {
+        // A pattern with a few vertices against a larger host has plenty of
+        // mappings, so its estimate is comfortably above a 0 MB limit -
+        // cheaper than constructing an instance that overflows any realistic
+        // limit, and just as good a test of the fallback wiring.
+        let g = Graph::from_adjacency_matrix(vec![vec![0, 1], vec![0, 0]]);
+        let h = Graph::from_adjacency_matrix(vec![vec![0; 5]; 5]);
+        let k = 1;
+
+        let (tx, rx) = channel();
+        run_exact_algorithm(
+            g,
+            h,
+            k,
+            Objective::TotalEdges,
+            None,
+            1,
+            SeedStrategy::Random,
+            true,
+            false,
+            None,
+            None,
+            None,
+            0,
+            1,
+            Some(0),
+            1,
+            None,
+            1,
+            1,
+            None,
+            usize::MAX,
+            false,
+            tx,
+        );
+
+        let messages: Vec<ProgressMessage> = rx.try_iter().collect();
+        let completed = messages
+            .into_iter()
+            .find_map(|msg| match msg {
+                ProgressMessage::Complete { algorithm, .. } => Some(algorithm),
+                _ => None,
+            })
+            .expect("a completion message should have been sent");
+
+        assert_eq!(completed, Algorithm::Approx);
+    }
+
+    /// `run_approx_once` announces its trial budget up front (see its doc
+    /// comment on the `Status` message it sends before the first mapping),
+    /// so `--trials-multiplier 5` should report exactly 5x the trial count
+    /// `--trials-multiplier 1` does, for the same pair of graphs.
+    #[test]
+    fn test_trials_multiplier_scales_the_reported_trial_count() {
+        let g = Graph::from_adjacency_matrix(vec![vec![0, 1], vec![0, 0]]);
+        let h = Graph::from_adjacency_matrix(vec![vec![0; 4]; 4]);
+        let k = 1;
+
+        let trial_count = |trials_multiplier: usize| {
+            let (tx, rx) = channel();
+            let mut rng = StdRng::seed_from_u64(0);
+            run_approx_once(
+                &g,
+                &h,
+                k,
+                trials_multiplier,
+                &mut rng,
+                &Objective::TotalEdges,
+                None,
+                1,
+                SeedStrategy::Random,
+                true,
+                Some(&tx),
+                None,
+            );
+            rx.try_iter()
+                .find_map(|msg| match msg {
+                    ProgressMessage::Status(s) if s.starts_with("Trials per mapping: ") => s
+                        .split_whitespace()
+                        .nth(3)
+                        .and_then(|n| n.parse::<usize>().ok()),
+                    _ => None,
+                })
+                .expect("a trial-count status message should have been sent")
+        };
+
+        assert_eq!(trial_count(5), 5 * trial_count(1));
+    }
+
+    #[test]
+    fn test_same_seed_reproduces_an_identical_mapping_set_and_cost() {
+        let g = Graph::from_adjacency_matrix(vec![vec![0, 1, 0], vec![0, 0, 1], vec![1, 0, 0]]);
+        let h = Graph::from_adjacency_matrix(vec![vec![0; 6]; 6]);
+        let k = 2;
+
+        let run = || {
+            let mut rng = StdRng::seed_from_u64(12345);
+            run_approx_once(
+                &g,
+                &h,
+                k,
+                3,
+                &mut rng,
+                &Objective::TotalEdges,
+                None,
+                1,
+                SeedStrategy::Random,
+                true,
+                None,
+                None,
+            )
+                .expect("k mappings exist")
+        };
+
+        let (cost_a, edge_map_a, mappings_a) = run();
+        let (cost_b, edge_map_b, mappings_b) = run();
+
+        assert_eq!(cost_a, cost_b);
+        assert_eq!(edge_map_a, edge_map_b);
+        assert_eq!(mappings_a, mappings_b);
+    }
+
+    /// G = a directed triangle, H = a directed 5-cycle: every vertex in both
+    /// has out-degree 1, and `find_all_mappings`'s count (`P(5, 3) = 60`) has
+    /// a convenient number of divisors to pick `k == N`, `k == N - 1`, and
+    /// `k == N + 1` from.
+    fn triangle_into_five_cycle() -> (Graph, Graph) {
+        let g = Graph::from_adjacency_matrix(vec![vec![0, 1, 0], vec![0, 0, 1], vec![1, 0, 0]]);
+        let h = Graph::from_adjacency_matrix(vec![
+            vec![0, 1, 0, 0, 0],
+            vec![0, 0, 1, 0, 0],
+            vec![0, 0, 0, 1, 0],
+            vec![0, 0, 0, 0, 1],
+            vec![1, 0, 0, 0, 0],
+        ]);
+        (g, h)
+    }
+
+    #[test]
+    fn test_run_exact_algorithm_k_equals_available_mappings_takes_the_shortcut() {
+        let (g, h) = triangle_into_five_cycle();
+        let n = find_all_mappings(&g, &h).len();
+
+        let (tx, rx) = channel();
+        run_exact_algorithm(
+            g,
+            h,
+            n,
+            Objective::TotalEdges,
+            None,
+            1,
+            SeedStrategy::Random,
+            true,
+            false,
+            None,
+            None,
+            None,
+            0,
+            1,
+            None,
+            1,
+            None,
+            1,
+            1,
+            None,
+            usize::MAX,
+            false,
+            tx,
+        );
+
+        let messages: Vec<ProgressMessage> = rx.try_iter().collect();
+        let (cost, mappings, search_stats) = messages
+            .into_iter()
+            .find_map(|msg| match msg {
+                ProgressMessage::Complete {
+                    cost,
+                    mappings,
+                    search_stats,
+                    ..
+                } => Some((cost, mappings, search_stats)),
+                _ => None,
+            })
+            .expect("a completion message should have been sent");
+
+        assert_eq!(mappings.len(), n);
+        assert_eq!(search_stats, Some((1, 1)));
+        assert_eq!(
+            cost,
+            Objective::TotalEdges.evaluate(&calculate_edge_map(
+                &Graph::from_adjacency_matrix(vec![vec![0, 1, 0], vec![0, 0, 1], vec![1, 0, 0]]),
+                &Graph::from_adjacency_matrix(vec![
+                    vec![0, 1, 0, 0, 0],
+                    vec![0, 0, 1, 0, 0],
+                    vec![0, 0, 0, 1, 0],
+                    vec![0, 0, 0, 0, 1],
+                    vec![1, 0, 0, 0, 0],
+                ]),
+                &mappings,
+            ))
+        );
+    }
+
+    #[test]
+    fn test_run_exact_algorithm_k_one_less_than_available_finds_the_true_optimum() {
+        let (g, h) = triangle_into_five_cycle();
+        let all_mappings = find_all_mappings(&g, &h);
+        let k = all_mappings.len() - 1;
+        let objective = Objective::TotalEdges;
+
+        let brute_force_best = (0..all_mappings.len())
+            .combinations(k)
+            .map(|indices| {
+                let chosen: Vec<&Mapping> = indices.iter().map(|&i| &all_mappings[i]).collect();
+                objective.evaluate(&calculate_edge_map(&g, &h, &chosen))
+            })
+            .min()
+            .unwrap();
+
+        let (tx, rx) = channel();
+        run_exact_algorithm(
+            g,
+            h,
+            k,
+            objective,
+            None,
+            1,
+            SeedStrategy::Random,
+            true,
+            false,
+            None,
+            None,
+            None,
+            0,
+            1,
+            None,
+            1,
+            None,
+            1,
+            1,
+            None,
+            usize::MAX,
+            false,
+            tx,
+        );
+
+        let cost = rx
+            .try_iter()
+            .find_map(|msg| match msg {
+                ProgressMessage::Complete { cost, .. } => Some(cost),
+                _ => None,
+            })
+            .expect("a completion message should have been sent");
+
+        assert_eq!(cost, brute_force_best);
+    }
+
+    /// A scoped single-threaded pool installed around the call must not
+    /// change the reported cost: `run_exact_algorithm`'s branch-and-bound
+    /// fan-out picks up whichever pool is current on the calling thread (see
+    /// `--threads`'s doc comment on `Args`), so this exercises the same
+    /// search with `--threads 1`'s pool in place instead of the default one.
+    #[test]
+    fn test_single_threaded_pool_matches_default_pool_cost() {
+        let (g, h) = triangle_into_five_cycle();
+        let all_mappings = find_all_mappings(&g, &h);
+        let k = all_mappings.len() - 1;
+        let objective = Objective::TotalEdges;
+
+        let run = |g: Graph, h: Graph| {
+            let (tx, rx) = channel();
+            run_exact_algorithm(
+                g,
+                h,
+                k,
+                objective.clone(),
+                None,
+                1,
+                SeedStrategy::Random,
+                true,
+                false,
+                None,
+                None,
+                None,
+                0,
+                1,
+                None,
+                1,
+                None,
+                1,
+                1,
+                None,
+                usize::MAX,
+                false,
+                tx,
+            );
+            rx.try_iter()
+                .find_map(|msg| match msg {
+                    ProgressMessage::Complete { cost, .. } => Some(cost),
+                    _ => None,
+                })
+                .expect("a completion message should have been sent")
+        };
+
+        let default_pool_cost = run(g.clone(), h.clone());
+
+        let single_threaded_pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(1)
+            .build()
+            .expect("a single-threaded pool always builds");
+        let single_threaded_cost = single_threaded_pool.install(|| run(g, h));
+
+        assert_eq!(default_pool_cost, single_threaded_cost);
+    }
+
+    #[test]
+    fn test_run_exact_algorithm_k_greater_than_available_mappings_reports_the_max_achievable_k() {
+        let (g, h) = triangle_into_five_cycle();
+        let n = find_all_mappings(&g, &h).len();
+
+        let (tx, rx) = channel();
+        run_exact_algorithm(
+            g,
+            h,
+            n + 1,
+            Objective::TotalEdges,
+            None,
+            1,
+            SeedStrategy::Random,
+            true,
+            false,
+            None,
+            None,
+            None,
+            0,
+            1,
+            None,
+            1,
+            None,
+            1,
+            1,
+            None,
+            usize::MAX,
+            false,
+            tx,
+        );
+
+        let error = rx
+            .try_iter()
+            .find_map(|msg| match msg {
+                ProgressMessage::Error(err) => Some(err),
+                _ => None,
+            })
+            .expect("an error message should have been sent");
+
+        assert!(
+            error.contains(&n.to_string()),
+            "error should mention the maximum achievable k ({}): {}",
+            n,
+            error
+        );
+    }
+
+    #[test]
+    fn test_run_exact_algorithm_refuses_a_search_over_max_combinations_unless_forced() {
+        let (g, h) = triangle_into_five_cycle();
+        let all_mappings = find_all_mappings(&g, &h);
+        let k = all_mappings.len() - 1;
+        let total_combinations = num_combinations(all_mappings.len(), k);
+
+        let (tx, rx) = channel();
+        run_exact_algorithm(
+            g.clone(),
+            h.clone(),
+            k,
+            Objective::TotalEdges,
+            None,
+            1,
+            SeedStrategy::Random,
+            true,
+            false,
+            None,
+            None,
+            None,
+            0,
+            1,
+            None,
+            1,
+            None,
+            1,
+            1,
+            None,
+            total_combinations - 1,
+            false,
+            tx,
+        );
+        let guard = rx.try_iter().find_map(|msg| match msg {
+            ProgressMessage::CombinationGuardTriggered {
+                total_combinations, ..
+            } => Some(total_combinations),
+            _ => None,
+        });
+        assert_eq!(guard, Some(total_combinations));
+
+        let (tx, rx) = channel();
+        run_exact_algorithm(
+            g,
+            h,
+            k,
+            Objective::TotalEdges,
+            None,
+            1,
+            SeedStrategy::Random,
+            true,
+            false,
+            None,
+            None,
+            None,
+            0,
+            1,
+            None,
+            1,
+            None,
+            1,
+            1,
+            None,
+            total_combinations - 1,
+            true,
+            tx,
+        );
+        let completed = rx
+            .try_iter()
+            .any(|msg| matches!(msg, ProgressMessage::Complete { .. }));
+        assert!(completed, "--force should bypass the guard");
+    }
+
+    #[test]
+    fn test_mapping_progress_reports_the_true_combination_count() {
+        // A small, fully exhaustive instance: 3 pattern vertices into a
+        // 5-vertex edgeless host, k=3. `run_exact_algorithm` always flushes a
+        // final `MappingProgress` after the search finishes (see its body),
+        // regardless of whether `nodes_visited` ever crossed the 10,000
+        // sampling threshold, so even an instance this small reports one.
+        let g = Graph::from_adjacency_matrix(vec![vec![0; 3]; 3]);
+        let h = Graph::from_adjacency_matrix(vec![vec![0; 5]; 5]);
+        let k = 3;
+        let expected_total = num_combinations(find_all_mappings(&g, &h).len(), k);
+
+        let (tx, rx) = channel();
+        run_exact_algorithm(
+            g,
+            h,
+            k,
+            Objective::TotalEdges,
+            None,
+            1,
+            SeedStrategy::Random,
+            true,
+            false,
+            None,
+            None,
+            None,
+            0,
+            1,
+            None,
+            1,
+            None,
+            1,
+            1,
+            None,
+            usize::MAX,
+            false,
+            tx,
+        );
+
+        let messages: Vec<ProgressMessage> = rx.try_iter().collect();
+        let final_progress = messages
+            .into_iter()
+            .filter_map(|msg| match msg {
+                ProgressMessage::MappingProgress { current, total } => Some((current, total)),
+                _ => None,
+            })
+            .next_back()
+            .expect("a mapping-progress report should have been sent");
+
+        assert_eq!(final_progress.1, expected_total);
+        assert!(final_progress.0 <= final_progress.1);
+    }
+
+    #[test]
+    fn test_dry_run_statistics_agree_with_the_primitives_they_report() {
+        // Covers the computation `--dry-run` reports, not its interactive
+        // confirm/cancel prompt: a 2-vertex pattern into a 4-vertex edgeless
+        // host has every injective assignment satisfying it vacuously (no
+        // edges to violate), so `count_satisfying_mappings` and
+        // `find_all_mappings` should agree on the count.
+        let g = Graph::from_adjacency_matrix(vec![vec![0; 2]; 2]);
+        let h = Graph::from_adjacency_matrix(vec![vec![0; 4]; 4]);
+        let k = 2;
+
+        let satisfying_count = count_satisfying_mappings(&g, &h);
+        let all_mappings = find_all_mappings(&g, &h);
+        assert_eq!(satisfying_count, all_mappings.len());
+        assert_eq!(
+            num_combinations(satisfying_count, k),
+            num_combinations(all_mappings.len(), k)
+        );
+
+        let estimated_bytes = estimate_memory_bytes(all_mappings.len(), g.num_vertices());
+        assert_eq!(
+            estimated_bytes,
+            all_mappings.len() * 2 * std::mem::size_of::<usize>()
+        );
+
+        let mut rng = StdRng::seed_from_u64(0);
+        let (cost, _, mappings) =
+            run_approx_once(
+                &g,
+                &h,
+                k,
+                1,
+                &mut rng,
+                &Objective::TotalEdges,
+                None,
+                1,
+                SeedStrategy::Random,
+                true,
+                None,
+                None,
+            )
+                .expect("k mappings exist");
+        assert_eq!(mappings.len(), k);
+        assert_eq!(cost, 0); // No edges anywhere, so nothing needs adding.
+    }
+
+    /// Covers the computation `--stats-only` reports: a known native-format
+    /// input file (a 3-cycle into a 4-cycle) parsed via `parse_input_file`,
+    /// with every `GraphStats` field checked against a value worked out by
+    /// hand for that exact shape.
+    #[test]
+    fn test_stats_only_report_matches_hand_computed_values_for_a_known_input() {
+        let contents = "3\n0 1 0\n0 0 1\n1 0 0\n4\n0 1 0 0\n0 0 1 0\n0 0 0 1\n1 0 0 0\n";
+        let dir = std::env::temp_dir();
+        let path = dir.join("solver_test_stats_only_report.txt");
+        std::fs::write(&path, contents).unwrap();
+
+        let (g, h) = parse_input_file(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        let g_stats = GraphStats::compute(&g);
+        assert_eq!(g_stats.num_vertices, 3);
+        assert_eq!(g_stats.num_edges, 3);
+        assert_eq!(g_stats.density, 0.5);
+        assert_eq!(g_stats.degree_histogram, vec![(2, 3)]);
+        assert_eq!(g_stats.scc_sizes, vec![3]);
+        assert_eq!(g_stats.diameter, Some(2));
+
+        let h_stats = GraphStats::compute(&h);
+        assert_eq!(h_stats.num_vertices, 4);
+        assert_eq!(h_stats.num_edges, 4);
+        assert!((h_stats.density - 1.0 / 3.0).abs() < 1e-9);
+        assert_eq!(h_stats.degree_histogram, vec![(2, 4)]);
+        assert_eq!(h_stats.scc_sizes, vec![4]);
+        assert_eq!(h_stats.diameter, Some(3));
+
+        let estimated_mappings = permutation_count(h.num_vertices(), g.num_vertices());
+        assert_eq!(estimated_mappings, 24);
+        assert_eq!(
+            num_combinations(estimated_mappings as usize, 2),
+            276 // C(24, 2)
+        );
+    }
+
+    #[test]
+    fn test_timeout_reports_a_verifiable_suboptimal_solution_promptly() {
+        // A path, not a closed cycle: an edgeless host large relative to the
+        // pattern still ties every standalone mapping's cost, but (unlike a
+        // cycle, whose rotations can all be realized with the very same
+        // added host edges) a k=3 solution can't collapse onto the
+        // single-mapping lower bound, so `cost_lower_bound` can't short-circuit
+        // the search and it really does run until the 1s timeout fires.
+        let g = Graph::from_adjacency_matrix(vec![vec![0, 1, 0], vec![0, 0, 1], vec![0, 0, 0]]);
+        let h = Graph::from_adjacency_matrix(vec![vec![0; 9]; 9]);
+        let k = 3;
+
+        let (tx, rx) = channel();
+        let start = Instant::now();
+        run_exact_algorithm(
+            g,
+            h,
+            k,
+            Objective::TotalEdges,
+            None,
+            1,
+            SeedStrategy::Random,
+            true,
+            false,
+            None,
+            None,
+            None,
+            0,
+            1,
+            None,
+            1,
+            None,
+            1,
+            1,
+            Some(1),
+            usize::MAX,
+            false,
+            tx,
+        );
+        let elapsed = start.elapsed();
+
+        assert!(
+            elapsed < Duration::from_secs(10),
+            "expected the search to stop close to the 1s timeout, took {:?}",
+            elapsed
+        );
+
+        let messages: Vec<ProgressMessage> = rx.try_iter().collect();
+        let (cost, edge_map, mappings, timed_out) = messages
+            .into_iter()
+            .find_map(|msg| match msg {
+                ProgressMessage::Complete {
+                    cost,
+                    edge_map,
+                    mappings,
+                    timed_out,
+                    ..
+                } => Some((cost, edge_map, mappings, timed_out)),
+                _ => None,
+            })
+            .expect("a completion message should have been sent");
+
+        assert!(timed_out);
+        assert_eq!(mappings.len(), k);
+        assert_eq!(cost, edge_map.values().sum::<usize>());
+    }
+
+    #[test]
+    fn test_approx_time_limit_finishes_promptly_with_a_complete_valid_solution() {
+        let g = Graph::random_simple(10, 0.3, 21);
+        let h = Graph::random_simple(30, 0.3, 22);
+        let k = 3;
+
+        let (tx, rx) = channel();
+        let start = Instant::now();
+        run_approx_algorithm(
+            g,
+            h,
+            k,
+            1,
+            Some(1.0),
+            // A trial multiplier this large would run for far longer than the
+            // 1-second deadline if it weren't cutting the trial loop short.
+            1_000_000,
+            9,
+            Objective::TotalEdges,
+            None,
+            1,
+            SeedStrategy::Random,
+            true,
+            0,
+            1,
+            None,
+            tx,
+        );
+        let elapsed = start.elapsed();
+
+        assert!(
+            elapsed < Duration::from_millis(1_500),
+            "1s budget should finish within ~1.5s, took {:?}",
+            elapsed
+        );
+
+        let messages: Vec<ProgressMessage> = rx.try_iter().collect();
+        let (cost, edge_map, mappings) = messages
+            .into_iter()
+            .find_map(|msg| match msg {
+                ProgressMessage::Complete {
+                    cost,
+                    edge_map,
+                    mappings,
+                    ..
+                } => Some((cost, edge_map, mappings)),
+                _ => None,
+            })
+            .expect("a completion message should have been sent");
+
+        assert_eq!(mappings.len(), k);
+        assert_eq!(cost, edge_map.values().sum::<usize>());
+    }
+
+    #[test]
+    fn test_incumbent_file_is_monotonically_non_increasing_and_always_resumable() {
+        // A 4-vertex directed path into a 6-vertex edgeless host: every
+        // mapping ties on standalone cost, so the greedy seed alone doesn't
+        // land on the optimum, and the branch-and-bound search improves on
+        // it a couple of times before proving which combination is best --
+        // enough incumbent writes to exercise the monotonic-cost guarantee,
+        // not just a single one.
+        let g = Graph::from_adjacency_matrix(vec![
+            vec![0, 1, 0, 0],
+            vec![0, 0, 1, 0],
+            vec![0, 0, 0, 1],
+            vec![0, 0, 0, 0],
+        ]);
+        let h = Graph::from_adjacency_matrix(vec![vec![0; 6]; 6]);
+        let k = 4;
+
+        let path = std::env::temp_dir().join("solver_test_incumbent_file.txt");
+        std::fs::remove_file(&path).ok();
+
+        let (tx, rx) = channel();
+        run_exact_algorithm(
+            g,
+            h,
+            k,
+            Objective::TotalEdges,
+            None,
+            1,
+            SeedStrategy::Random,
+            true,
+            false,
+            None,
+            None,
+            Some(path.clone()),
+            0,
+            1,
+            None,
+            1,
+            None,
+            1,
+            1,
+            None,
+            usize::MAX,
+            false,
+            tx,
+        );
+
+        let messages: Vec<ProgressMessage> = rx.try_iter().collect();
+        let incumbent_costs: Vec<usize> = messages
+            .iter()
+            .filter_map(|msg| match msg {
+                ProgressMessage::Incumbent { cost, .. } => Some(*cost),
+                _ => None,
+            })
+            .collect();
+        assert!(
+            !incumbent_costs.is_empty(),
+            "expected at least one incumbent write"
+        );
+        assert!(
+            incumbent_costs.windows(2).all(|pair| pair[1] <= pair[0]),
+            "incumbent costs should never increase: {:?}",
+            incumbent_costs
+        );
+
+        let final_cost = messages
+            .into_iter()
+            .find_map(|msg| match msg {
+                ProgressMessage::Complete { cost, .. } => Some(cost),
+                _ => None,
+            })
+            .expect("a completion message should have been sent");
+
+        // The last write to the incumbent file is the same verifiable
+        // solution `Complete` reports -- and, since it carries the same
+        // "Total Cost (edges added)" line any other solution file does, it's
+        // just as usable as a `--resume-from` source.
+        assert_eq!(
+            read_resume_cost(&path),
+            Some(final_cost),
+            "the last write to --incumbent-file should match the final reported cost"
+        );
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_batch_solves_every_matching_instance_and_writes_its_report() {
+        let dir = std::env::temp_dir().join("solver_test_batch_instances");
+        std::fs::create_dir_all(&dir).unwrap();
+        let output_dir = dir.join("out");
+
+        // Three small edgeless-host instances, differing only in host size,
+        // so every mapping ties on cost and each instance is solved quickly
+        // by the exact algorithm.
+        for (name, host_size) in [("a.txt", 4), ("b.txt", 5), ("c.txt", 6)] {
+            let contents = format!(
+                "2\n0 0\n0 0\n{}\n{}\n",
+                host_size,
+                (0..host_size)
+                    .map(|_| vec!["0"; host_size].join(" "))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            );
+            std::fs::write(dir.join(name), contents).unwrap();
+        }
+
+        let args = Args::parse_from([
+            "solver",
+            "--algorithm",
+            "exact",
+            "--k",
+            "2",
+            "--batch",
+            dir.join("*.txt").to_str().unwrap(),
+            "--batch-output-dir",
+            output_dir.to_str().unwrap(),
+        ]);
+
+        run_batch(args.batch.as_ref().unwrap(), &output_dir, &args).unwrap();
+
+        for name in ["a.txt", "b.txt", "c.txt"] {
+            let report = output_dir.join(format!("solution_{}.txt", name));
+            assert!(report.exists(), "expected a report for {}", name);
+            let contents = std::fs::read_to_string(&report).unwrap();
+            assert!(contents.contains("Total Cost (edges added): 0"));
+        }
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_batch_file_solves_every_pair_in_a_combined_instances_file_and_writes_its_report() {
+        let dir = std::env::temp_dir().join("solver_test_batch_file_instances");
+        std::fs::create_dir_all(&dir).unwrap();
+        let output_dir = dir.join("out");
+
+        // Three small edgeless-host instances, differing only in host size,
+        // combined into a single `---`-delimited file (see
+        // `parser::parse_all_graph_pairs_file`).
+        let combined: String = [4, 5, 6]
+            .iter()
+            .map(|&host_size| {
+                format!(
+                    "2\n0 0\n0 0\n{}\n{}\n",
+                    host_size,
+                    (0..host_size)
+                        .map(|_| vec!["0"; host_size].join(" "))
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("---\n");
+        let combined_path = dir.join("combined.txt");
+        std::fs::write(&combined_path, combined).unwrap();
+
+        let args = Args::parse_from([
+            "solver",
+            "--algorithm",
+            "exact",
+            "--k",
+            "2",
+            "--batch-file",
+            combined_path.to_str().unwrap(),
+            "--batch-output-dir",
+            output_dir.to_str().unwrap(),
+        ]);
+
+        run_batch_file(args.batch_file.as_ref().unwrap(), &output_dir, &args).unwrap();
+
+        for pair_num in 1..=3 {
+            let report = output_dir.join(format!("solution_pair_{}.txt", pair_num));
+            assert!(report.exists(), "expected a report for pair {}", pair_num);
+            let contents = std::fs::read_to_string(&report).unwrap();
+            assert!(contents.contains("Total Cost (edges added): 0"));
+        }
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_approximation_ratio_is_infinite_only_when_optimal_is_zero_but_approx_isnt() {
+        assert_eq!(approximation_ratio(0, 0), 1.0);
+        assert_eq!(approximation_ratio(0, 5), usize::MAX as f64);
+        assert_eq!(approximation_ratio(2, 3), 1.5);
+        assert_eq!(approximation_ratio(2, 2), 1.0);
+    }
+
+    #[test]
+    fn test_compare_mode_reports_the_correct_approximation_ratio_on_a_known_instance() {
+        // A single pattern edge against a 4-vertex edgeless host: every
+        // mapping needs exactly one new edge, and no two of k=2 distinct
+        // mappings can land on the same host edge (that would make them the
+        // same mapping), so both algorithms necessarily agree the optimum is
+        // 2 -- a small, fully deterministic instance to check the ratio math
+        // against, independent of which mappings either algorithm happens to
+        // pick.
+        let g = Graph::from_adjacency_matrix(vec![vec![0, 1], vec![0, 0]]);
+        let h = Graph::from_adjacency_matrix(vec![vec![0; 4]; 4]);
+        let k = 2;
+
+        let (tx, rx) = channel();
+        run_compare_algorithm(
+            g,
+            h,
+            k,
+            Objective::TotalEdges,
+            None,
+            1,
+            SeedStrategy::Random,
+            true,
+            false,
+            0,
+            1,
+            None,
+            1,
+            None,
+            1,
+            1,
+            None,
+            tx,
+        );
+
+        let compare = rx
+            .try_iter()
+            .find_map(|msg| match msg {
+                ProgressMessage::CompareComplete(compare) => Some(*compare),
+                _ => None,
+            })
+            .expect("a CompareComplete message should have been sent");
+
+        assert_eq!(compare.exact_cost, 2);
+        assert_eq!(
+            compare.ratio,
+            approximation_ratio(compare.exact_cost, compare.approx_cost)
+        );
+        assert_eq!(compare.ratio, compare.approx_cost as f64 / 2.0);
+    }
+
+    /// A minimal `AppState` for exercising `handle_key`/`handle_mouse` in
+    /// isolation, without spinning up an algorithm thread. `g`/`h` are large
+    /// enough that every viewport has somewhere to scroll to.
+    fn test_app_state(current_view: View) -> AppState {
+        let g = Graph::random_simple(20, 0.3, 1);
+        let h = Graph::random_simple(20, 0.3, 2);
+        let (_tx, rx) = channel();
+        let (progress_tx, _progress_rx) = channel();
+        let mut app = AppState::new(
+            Algorithm::Approx,
+            g,
+            h,
+            2,
+            0,
+            1,
+            None,
+            None,
+            None,
+            rx,
+            Objective::TotalEdges,
+            None,
+            1,
+            SeedStrategy::Random,
+            true,
+            1,
+            None,
+            1,
+            0,
+            progress_tx,
+            1,
+        );
+        app.current_view = current_view;
+        app
+    }
+
+    #[test]
+    fn test_mouse_scroll_down_advances_the_active_viewport_by_three() {
+        let mut app = test_app_state(View::Extension);
+
+        app.handle_mouse(
+            MouseEvent {
+                kind: MouseEventKind::ScrollDown,
+                column: 0,
+                row: 0,
+                modifiers: KeyModifiers::NONE,
+            },
+            Rect::new(0, 0, 80, 24),
+        );
+
+        assert_eq!(app.viewport_ext.row_offset, 3);
+        // Scrolling the extension view shouldn't touch an unrelated viewport.
+        assert_eq!(app.viewport_mappings.row_offset, 0);
+    }
+
+    #[test]
+    fn test_mouse_scroll_up_stops_at_zero_instead_of_wrapping() {
+        let mut app = test_app_state(View::Mappings);
+        app.viewport_mappings.row_offset = 2;
+
+        app.handle_mouse(
+            MouseEvent {
+                kind: MouseEventKind::ScrollUp,
+                column: 0,
+                row: 0,
+                modifiers: KeyModifiers::NONE,
+            },
+            Rect::new(0, 0, 80, 24),
+        );
+        assert_eq!(app.viewport_mappings.row_offset, 0);
+
+        app.handle_mouse(
+            MouseEvent {
+                kind: MouseEventKind::ScrollUp,
+                column: 0,
+                row: 0,
+                modifiers: KeyModifiers::NONE,
+            },
+            Rect::new(0, 0, 80, 24),
+        );
+        assert_eq!(app.viewport_mappings.row_offset, 0);
+    }
+
+    #[test]
+    fn test_mouse_scroll_right_advances_the_active_viewports_column_offset() {
+        let mut app = test_app_state(View::Graphs);
+
+        app.handle_mouse(
+            MouseEvent {
+                kind: MouseEventKind::ScrollRight,
+                column: 0,
+                row: 0,
+                modifiers: KeyModifiers::NONE,
+            },
+            Rect::new(0, 0, 80, 24),
+        );
+
+        assert_eq!(app.viewport_g.col_offset, 3);
+        assert_eq!(app.viewport_h.col_offset, 3);
+    }
+
+    #[test]
+    fn test_mouse_left_click_on_a_menu_item_switches_the_view() {
+        let mut app = test_app_state(View::Menu);
+        let area = Rect::new(0, 0, 80, 24);
+
+        // menu_list_area's header (3) and results (6) rows are inset by the
+        // top margin(1), then the list's own top border, before its first
+        // item ("[G] View Graphs...") is drawn -- 1 + 3 + 6 + 1 = 11.
+        let first_item_row = 11;
+        app.handle_mouse(
+            MouseEvent {
+                kind: MouseEventKind::Down(MouseButton::Left),
+                column: 5,
+                row: first_item_row,
+                modifiers: KeyModifiers::NONE,
+            },
+            area,
+        );
+
+        assert_eq!(app.current_view, View::Graphs);
+    }
+
+    #[test]
+    fn test_mouse_left_click_outside_the_menu_list_is_ignored() {
+        let mut app = test_app_state(View::Menu);
+
+        app.handle_mouse(
+            MouseEvent {
+                kind: MouseEventKind::Down(MouseButton::Left),
+                column: 0,
+                row: 0,
+                modifiers: KeyModifiers::NONE,
+            },
+            Rect::new(0, 0, 80, 24),
+        );
+
+        assert_eq!(app.current_view, View::Menu);
+    }
+}
@@ -0,0 +1,191 @@
+use clap::Parser;
+use itertools::Itertools;
+use minimal_k_iso_lib::{
+    cost::{calculate_edge_map, calculate_total_cost, validate_solution},
+    mapping::find_all_mappings,
+    parser::parse_input_file,
+    Mapping,
+};
+use std::path::{Path, PathBuf};
+
+/// Standalone verifier for a solution report produced by the `solver`
+/// binary, for independently confirming correctness before submitting a
+/// result (e.g. to a contest or a paper) without trusting whichever solver
+/// produced it.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// Path to the input file containing graph descriptions
+    #[arg(long)]
+    graph_file: PathBuf,
+
+    /// Path to a solution report (the text file `write_results_to_file`
+    /// produces)
+    #[arg(long)]
+    solution_file: PathBuf,
+
+    /// Also confirm the reported cost is optimal, not just feasible, by
+    /// brute-force searching every k-combination of mappings. Only practical
+    /// for small instances, since the search is exponential in the number of
+    /// candidate mappings.
+    #[arg(long)]
+    strict: bool,
+}
+
+/// The pieces of a solution report this binary cares about: the mappings it
+/// claims and the cost it claims they cost. Everything else in the report
+/// (adjacency matrices, permutation diagrams, ...) is ignored.
+struct ParsedSolution {
+    mappings: Vec<Mapping>,
+    cost: usize,
+}
+
+/// Parse a solution report's "Mapping N of M:" sections and its
+/// "Total Cost (edges added): N" line.
+fn parse_solution_file(path: &Path) -> Result<ParsedSolution, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("could not read solution file: {}", e))?;
+
+    let cost = content
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("Total Cost (edges added): "))
+        .ok_or_else(|| "solution file has no 'Total Cost (edges added):' line".to_string())?
+        .trim()
+        .parse::<usize>()
+        .map_err(|e| format!("could not parse reported cost: {}", e))?;
+
+    let mut mappings: Vec<(usize, Mapping)> = Vec::new();
+    let mut current: Option<(usize, Vec<(usize, usize)>)> = None;
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if let Some(idx) = mapping_header_index(trimmed) {
+            if let Some((prev_idx, pairs)) = current.take() {
+                mappings.push((prev_idx, pairs_to_mapping(pairs)));
+            }
+            current = Some((idx, Vec::new()));
+            continue;
+        }
+
+        if let Some((_, pairs)) = current.as_mut() {
+            pairs.extend(parse_g_to_h_pairs(trimmed));
+        }
+    }
+    if let Some((idx, pairs)) = current.take() {
+        mappings.push((idx, pairs_to_mapping(pairs)));
+    }
+
+    mappings.sort_by_key(|(idx, _)| *idx);
+    Ok(ParsedSolution {
+        mappings: mappings.into_iter().map(|(_, m)| m).collect(),
+        cost,
+    })
+}
+
+/// If `line` is a "Mapping N of M:" header, the 1-based index N.
+fn mapping_header_index(line: &str) -> Option<usize> {
+    let rest = line.strip_prefix("Mapping ")?;
+    let (idx_str, _) = rest.split_once(" of ")?;
+    idx_str.trim().parse().ok()
+}
+
+/// Every `G[u]→H[v]` pair appearing in `line` (the "Mapping list" rows are
+/// the only lines that have any).
+fn parse_g_to_h_pairs(line: &str) -> Vec<(usize, usize)> {
+    let mut pairs = Vec::new();
+    let mut rest = line;
+    while let Some(g_start) = rest.find("G[") {
+        rest = &rest[g_start + 2..];
+        let Some(g_end) = rest.find(']') else { break };
+        let Ok(u) = rest[..g_end].parse::<usize>() else {
+            break;
+        };
+        rest = &rest[g_end + 1..];
+
+        let Some(h_start) = rest.find("H[") else {
+            break;
+        };
+        rest = &rest[h_start + 2..];
+        let Some(h_end) = rest.find(']') else { break };
+        let Ok(v) = rest[..h_end].parse::<usize>() else {
+            break;
+        };
+        rest = &rest[h_end + 1..];
+
+        pairs.push((u, v));
+    }
+    pairs
+}
+
+fn pairs_to_mapping(mut pairs: Vec<(usize, usize)>) -> Mapping {
+    pairs.sort_by_key(|&(u, _)| u);
+    pairs.into_iter().map(|(_, v)| v).collect()
+}
+
+/// Brute-force the minimal cost achievable with `k` mappings from `g` into
+/// `h`, by trying every k-combination of candidate mappings. Only practical
+/// for small instances; this is what `--strict` uses instead of the full
+/// exact solver's pruned combination walk.
+fn brute_force_optimal_cost(
+    g: &minimal_k_iso_lib::Graph,
+    h: &minimal_k_iso_lib::Graph,
+    k: usize,
+) -> Option<usize> {
+    let all_mappings = find_all_mappings(g, h);
+    if all_mappings.len() < k {
+        return None;
+    }
+
+    all_mappings
+        .iter()
+        .combinations(k)
+        .map(|combo| calculate_total_cost(&calculate_edge_map(g, h, &combo)))
+        .min()
+}
+
+fn main() {
+    let args = Args::parse();
+
+    let (g, h) = match parse_input_file(&args.graph_file) {
+        Ok(graphs) => graphs,
+        Err(e) => {
+            eprintln!("Error parsing graph file: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let solution = match parse_solution_file(&args.solution_file) {
+        Ok(solution) => solution,
+        Err(e) => {
+            eprintln!("Error parsing solution file: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    if let Err(e) = validate_solution(&g, &h, &solution.mappings, solution.cost) {
+        eprintln!("INVALID: {}", e);
+        std::process::exit(1);
+    }
+
+    if args.strict {
+        match brute_force_optimal_cost(&g, &h, solution.mappings.len()) {
+            Some(optimal) if optimal < solution.cost => {
+                eprintln!(
+                    "INVALID: reported cost {} is not optimal (found {})",
+                    solution.cost, optimal
+                );
+                std::process::exit(1);
+            }
+            Some(_) => {}
+            None => {
+                eprintln!(
+                    "INVALID: could not confirm optimality; fewer than {} candidate mappings exist",
+                    solution.mappings.len()
+                );
+                std::process::exit(1);
+            }
+        }
+    }
+
+    println!("VALID: cost {} confirmed", solution.cost);
+}